@@ -0,0 +1,13 @@
+-- Tokens are only stored as SHA-256 hashes from now on.
+ALTER TABLE subscription_tokens
+    ADD COLUMN token_hash bytea,
+    ADD COLUMN created_at timestamptz NOT NULL DEFAULT now();
+
+UPDATE subscription_tokens
+SET token_hash = sha256(convert_to(id::text, 'UTF8'));
+
+ALTER TABLE subscription_tokens
+    DROP CONSTRAINT subscription_tokens_pkey,
+    DROP COLUMN id,
+    ALTER COLUMN token_hash SET NOT NULL,
+    ADD PRIMARY KEY (token_hash);
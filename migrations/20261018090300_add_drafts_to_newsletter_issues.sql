@@ -0,0 +1,7 @@
+-- Drafts are not published yet, so they have no `published_at`.
+ALTER TABLE newsletter_issues
+    ADD COLUMN status TEXT NOT NULL DEFAULT 'published'
+        CHECK (status IN ('draft', 'published')),
+    ADD COLUMN updated_at timestamptz NOT NULL DEFAULT now(),
+    ALTER COLUMN published_at DROP NOT NULL,
+    ALTER COLUMN published_at DROP DEFAULT;
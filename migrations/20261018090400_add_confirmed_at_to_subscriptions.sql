@@ -0,0 +1,7 @@
+ALTER TABLE subscriptions
+    ADD COLUMN confirmed_at timestamptz;
+
+-- The actual confirmation time of existing subscribers is unknown.
+UPDATE subscriptions
+SET confirmed_at = subscribed_at
+WHERE status = 'confirmed';
@@ -0,0 +1,4 @@
+-- Source of issues authored as Markdown, `html_content` & `text_content`
+-- are generated from it.
+ALTER TABLE newsletter_issues
+    ADD COLUMN markdown_content TEXT;
@@ -0,0 +1,3 @@
+-- Private issues are still sent, but left out of the public archive & feeds.
+ALTER TABLE newsletter_issues
+    ADD COLUMN is_private BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,3 @@
+-- Delivered tasks are deleted from the queue, so the number of recipients
+-- is kept to derive delivery progress. Unknown for issues enqueued before.
+ALTER TABLE newsletter_issues ADD COLUMN n_recipients BIGINT;
@@ -0,0 +1,35 @@
+-- Delivery of a published issue can be paused & resumed, or cancelled.
+ALTER TABLE newsletter_issues
+    ADD COLUMN delivery_status TEXT NOT NULL DEFAULT 'active'
+        CHECK (delivery_status IN ('active', 'paused', 'cancelled'));
+
+-- Tasks of cancelled deliveries are kept for the record instead of deleted.
+ALTER TABLE issue_delivery_queue
+    ADD COLUMN cancelled BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE OR REPLACE FUNCTION get_available_issue_delivery_queue(timestamptz)
+RETURNS setof issue_delivery_queue AS
+'
+SELECT newsletter_issue_id, subscriber_email, n_retries, execute_after, enabled, cancelled
+FROM
+(
+    SELECT
+        issue_delivery_queue.newsletter_issue_id,
+        issue_delivery_queue.subscriber_email,
+        issue_delivery_queue.n_retries,
+        issue_delivery_queue.execute_after,
+        issue_delivery_queue.enabled,
+        issue_delivery_queue.cancelled,
+        newsletter_issues.published_at,
+        newsletter_issues.delivery_status
+    FROM newsletter_issues
+    INNER JOIN issue_delivery_queue
+    ON newsletter_issues.newsletter_issue_id 
+        = issue_delivery_queue.newsletter_issue_id
+) AS my_alias
+WHERE enabled = true
+AND cancelled = false
+AND delivery_status = ''active''
+AND published_at <= $1
+AND execute_after >= $1 - published_at;
+' LANGUAGE SQL;
@@ -0,0 +1,2 @@
+-- Lets writers send test copies of an issue to themselves.
+ALTER TABLE newsletter_writers ADD COLUMN email TEXT;
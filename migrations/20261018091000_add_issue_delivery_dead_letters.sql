@@ -0,0 +1,45 @@
+-- Tasks exceeding the maximum number of attempts are moved out of the queue.
+CREATE TABLE issue_delivery_dead_letters (
+    newsletter_issue_id uuid NOT NULL
+    REFERENCES newsletter_issues (newsletter_issue_id),
+    subscriber_email TEXT NOT NULL,
+    n_attempts INTEGER NOT NULL,
+    last_error TEXT NOT NULL,
+    dead_lettered_at timestamptz NOT NULL,
+    PRIMARY KEY(newsletter_issue_id, subscriber_email)
+);
+
+-- `execute_after` is now the delay since publication after which a task is
+-- due, set per task on retry. New tasks are due right away.
+ALTER TABLE issue_delivery_queue
+    ALTER COLUMN execute_after SET DEFAULT INTERVAL '0';
+
+UPDATE issue_delivery_queue
+SET execute_after = INTERVAL '0';
+
+CREATE OR REPLACE FUNCTION get_available_issue_delivery_queue(timestamptz)
+RETURNS setof issue_delivery_queue AS
+'
+SELECT newsletter_issue_id, subscriber_email, n_retries, execute_after, enabled, cancelled
+FROM
+(
+    SELECT
+        issue_delivery_queue.newsletter_issue_id,
+        issue_delivery_queue.subscriber_email,
+        issue_delivery_queue.n_retries,
+        issue_delivery_queue.execute_after,
+        issue_delivery_queue.enabled,
+        issue_delivery_queue.cancelled,
+        newsletter_issues.published_at,
+        newsletter_issues.delivery_status
+    FROM newsletter_issues
+    INNER JOIN issue_delivery_queue
+    ON newsletter_issues.newsletter_issue_id
+        = issue_delivery_queue.newsletter_issue_id
+) AS my_alias
+WHERE enabled = true
+AND cancelled = false
+AND delivery_status = ''active''
+AND published_at <= $1
+AND published_at + execute_after <= $1;
+' LANGUAGE SQL;
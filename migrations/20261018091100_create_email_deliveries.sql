@@ -0,0 +1,20 @@
+-- Append-only log of every attempt at sending an issue to a recipient, kept
+-- after the task leaves the queue.
+CREATE TABLE email_deliveries (
+    email_delivery_id uuid PRIMARY KEY,
+    newsletter_issue_id uuid NOT NULL
+    REFERENCES newsletter_issues (newsletter_issue_id),
+    subscriber_email TEXT NOT NULL,
+    attempt INTEGER NOT NULL,
+    status TEXT NOT NULL CHECK (status IN ('sent', 'failed')),
+    http_status INTEGER,
+    provider_message_id TEXT,
+    error TEXT,
+    attempted_at timestamptz NOT NULL
+);
+
+CREATE INDEX email_deliveries_newsletter_issue_id_idx
+    ON email_deliveries (newsletter_issue_id);
+
+CREATE INDEX email_deliveries_subscriber_email_idx
+    ON email_deliveries (subscriber_email);
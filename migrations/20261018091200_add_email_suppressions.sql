@@ -0,0 +1,20 @@
+-- Bounces & complaints reported by the email provider.
+CREATE TABLE email_events (
+    email_event_id uuid PRIMARY KEY,
+    record_type TEXT NOT NULL,
+    email TEXT NOT NULL,
+    provider_message_id TEXT,
+    bounce_type TEXT NOT NULL,
+    description TEXT,
+    occurred_at timestamptz NOT NULL,
+    received_at timestamptz NOT NULL
+);
+
+CREATE INDEX email_events_email_idx ON email_events (email);
+
+-- Addresses never to be emailed again, subscribed or not.
+CREATE TABLE email_suppressions (
+    email TEXT PRIMARY KEY,
+    reason TEXT NOT NULL CHECK (reason IN ('bounced', 'complained')),
+    suppressed_at timestamptz NOT NULL
+);
@@ -0,0 +1,16 @@
+-- Confirmation emails written along with their subscription, sent by a
+-- background worker once committed. Rows hold the raw token of the link,
+-- only stored hashed elsewhere, until sent.
+CREATE TABLE confirmation_email_outbox (
+    confirmation_email_id uuid PRIMARY KEY,
+    subscriber_id uuid NOT NULL
+    REFERENCES subscriptions (id) ON DELETE CASCADE,
+    subscriber_email TEXT NOT NULL,
+    confirmation_link TEXT NOT NULL,
+    n_retries INTEGER NOT NULL DEFAULT 0,
+    execute_after timestamptz NOT NULL,
+    created_at timestamptz NOT NULL
+);
+
+CREATE INDEX confirmation_email_outbox_execute_after_idx
+    ON confirmation_email_outbox (execute_after);
@@ -0,0 +1,5 @@
+-- Cancelled scheduled issues are kept for the record instead of deleted.
+ALTER TABLE newsletter_issues
+    DROP CONSTRAINT newsletter_issues_status_check,
+    ADD CONSTRAINT newsletter_issues_status_check
+        CHECK (status IN ('draft', 'published', 'cancelled'));
@@ -0,0 +1,7 @@
+-- Whether each recipient of an issue was ever sent it, out of every attempt.
+CREATE VIEW email_delivery_outcomes AS
+SELECT newsletter_issue_id,
+    subscriber_email,
+    bool_or(status = 'sent') AS is_sent
+FROM email_deliveries
+GROUP BY newsletter_issue_id, subscriber_email;
@@ -0,0 +1,15 @@
+-- Emails missing from the results of an accepted batch may have been sent.
+ALTER TABLE email_deliveries
+    DROP CONSTRAINT email_deliveries_status_check;
+
+ALTER TABLE email_deliveries
+    ADD CONSTRAINT email_deliveries_status_check
+    CHECK (status IN ('sent', 'failed', 'unknown'));
+
+-- Unknown outcomes are not sent again, so they count as sent.
+CREATE OR REPLACE VIEW email_delivery_outcomes AS
+SELECT newsletter_issue_id,
+    subscriber_email,
+    bool_or(status <> 'failed') AS is_sent
+FROM email_deliveries
+GROUP BY newsletter_issue_id, subscriber_email;
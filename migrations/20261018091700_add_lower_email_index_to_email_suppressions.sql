@@ -0,0 +1,3 @@
+-- Suppressed addresses are compared case-insensitively.
+CREATE INDEX email_suppressions_lower_email_idx
+    ON email_suppressions (lower(email));
@@ -0,0 +1,17 @@
+-- Links are built when sending, with a token stored then, so the outbox no
+-- longer holds raw tokens.
+ALTER TABLE confirmation_email_outbox
+    DROP COLUMN confirmation_link;
+
+-- Confirmation emails exceeding the maximum number of attempts are moved out
+-- of the outbox.
+CREATE TABLE confirmation_email_dead_letters (
+    confirmation_email_id uuid PRIMARY KEY,
+    subscriber_id uuid NOT NULL
+    REFERENCES subscriptions (id) ON DELETE CASCADE,
+    subscriber_email TEXT NOT NULL,
+    n_attempts INTEGER NOT NULL,
+    last_error TEXT NOT NULL,
+    created_at timestamptz NOT NULL,
+    dead_lettered_at timestamptz NOT NULL
+);
@@ -10,10 +10,16 @@ use tracing_log::log;
 use crate::domain::{
     SubscriberEmail, SubscriberEmailParseError,
 };
-use crate::email_client::EmailClient;
+use crate::email_client::{
+    CircuitBreaker, CircuitBreakerPolicy, DomainSendRate,
+    EmailClient, EmailTransport,
+    FileEmailSender, MemoryEmailSender, PostmarkEmailSender,
+    RateLimiter, SendRate, SmtpEmailSender,
+};
 use crate::hkt::{
     HKT1Unsized, K1, RefHKT, SharedPointerHKT,
 };
+use crate::startup::GlobalSharedPointer;
 use crate::utils::Pipe;
 
 const APP_ENVIRONMENT: &str = name_of!(APP_ENVIRONMENT);
@@ -26,6 +32,8 @@ pub struct Settings<P: HKT1Unsized> {
     pub database: K1<P, DatabaseSettings<P>>,
     pub application: K1<P, ApplicationSettings<P>>,
     pub email_client: K1<P, EmailClientSettings<P>>,
+    pub issue_delivery: K1<P, IssueDeliverySettings>,
+    pub confirmation_email: K1<P, ConfirmationEmailSettings>,
 }
 
 impl<P: SharedPointerHKT> Clone for Settings<P> {
@@ -34,6 +42,8 @@ impl<P: SharedPointerHKT> Clone for Settings<P> {
             database: self.database.clone(),
             application: self.application.clone(),
             email_client: self.email_client.clone(),
+            issue_delivery: self.issue_delivery.clone(),
+            confirmation_email: self.confirmation_email.clone(),
         }
     }
 }
@@ -78,6 +88,20 @@ pub struct ApplicationSettings<P: HKT1Unsized> {
     pub host: K1<P, str>,
     pub base_url: K1<P, str>,
     pub hmac_secret: K1<P, HmacSecret<P>>,
+    /// Signs unsubscribe links, apart from the cookie key `hmac_secret`:
+    /// rotating either one leaves the other's signatures valid.
+    pub unsubscribe_secret: K1<P, UnsubscribeSecret<P>>,
+    pub confirmation_token_ttl_seconds: u64,
+}
+
+impl<P: HKT1Unsized> ApplicationSettings<P> {
+    pub fn confirmation_token_ttl(
+        &self,
+    ) -> std::time::Duration {
+        std::time::Duration::from_secs(
+            self.confirmation_token_ttl_seconds,
+        )
+    }
 }
 
 impl<P: SharedPointerHKT> Clone for ApplicationSettings<P> {
@@ -87,18 +111,84 @@ impl<P: SharedPointerHKT> Clone for ApplicationSettings<P> {
             host: self.host.clone(),
             base_url: self.base_url.clone(),
             hmac_secret: self.hmac_secret.clone(),
+            unsubscribe_secret: self.unsubscribe_secret.clone(),
+            confirmation_token_ttl_seconds: self
+                .confirmation_token_ttl_seconds,
         }
     }
 }
 
 #[derive(serde::Deserialize)]
-#[derive(derive_more::Constructor)]
 #[serde(bound(deserialize = "P: RefHKT"))]
 pub struct EmailClientSettings<P: HKT1Unsized> {
     pub base_url: K1<P, str>,
     pub sender_email: K1<P, str>,
+    /// Shown by mail clients instead of `sender_email`.
+    #[serde(default)]
+    pub sender_name: Option<K1<P, str>>,
+    /// Where replies go, if not to `sender_email`.
+    #[serde(default)]
+    pub reply_to_email: Option<K1<P, str>>,
+    /// Postmark stream of newsletter issues, kept apart from the
+    /// transactional emails; the default stream if unset.
+    #[serde(default)]
+    pub broadcast_message_stream: Option<K1<P, str>>,
     pub authorization_token: K1<P, str>,
     pub timeout_milliseconds: u64,
+    /// Shared by every email sent by the application.
+    #[serde(default)]
+    pub send_rate: SendRate,
+    /// Applied on top of `send_rate` for recipients of these domains.
+    #[serde(default)]
+    pub send_rate_per_domain: Vec<DomainSendRate>,
+    pub webhook: WebhookSettings,
+    /// Defaults to Postmark, reached at `base_url`.
+    #[serde(default)]
+    pub transport: EmailTransportSettings,
+    /// Sent through while the circuit breaker of `transport` is open, e.g.
+    /// an SMTP relay of another provider.
+    #[serde(default)]
+    pub fallback_transport: Option<EmailTransportSettings>,
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerPolicy,
+}
+
+/// Where emails are handed to, chosen by `kind`.
+#[derive(serde::Deserialize, Clone, Default)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EmailTransportSettings {
+    #[default]
+    Postmark,
+    Smtp(SmtpSettings),
+    /// Writes emails to `.eml` files instead of sending them.
+    File { directory: String },
+    /// Keeps emails in memory instead of sending them, to be read in the
+    /// mailbox of local environments.
+    Memory,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct SmtpSettings {
+    pub host: String,
+    /// Submission port, upgraded with STARTTLS.
+    #[serde(
+        default = "default_smtp_port",
+        deserialize_with = "deserialize_number_from_string"
+    )]
+    pub port: u16,
+    pub username: String,
+    pub password: SecretString,
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+/// Basic auth credentials the email provider calls our webhooks with.
+#[derive(serde::Deserialize, Clone)]
+pub struct WebhookSettings {
+    pub username: String,
+    pub password: SecretString,
 }
 
 impl<P: HKT1Unsized> EmailClientSettings<P> {
@@ -117,16 +207,116 @@ impl<P: SharedPointerHKT> EmailClientSettings<P> {
         SubscriberEmail::try_from(self.sender_email.clone())
     }
 
-    pub fn client(self) -> EmailClient<P> {
+    pub fn reply_to(
+        &self,
+    ) -> Result<
+        Option<SubscriberEmail<P>>,
+        SubscriberEmailParseError,
+    > {
+        self.reply_to_email
+            .clone()
+            .map(SubscriberEmail::try_from)
+            .transpose()
+    }
+
+    /// Sends through the email sender, e.g. the one of `transport()`, and
+    /// through the fallback one, e.g. of `fallback_transport()`, while the
+    /// former is failing.
+    pub fn client<S>(
+        self,
+        email_sender: GlobalSharedPointer<S>,
+        fallback_email_sender: Option<GlobalSharedPointer<S>>,
+    ) -> EmailClient<P, S> {
         let sender = self.sender().expect("Valid email");
-        let timeout = self.timeout();
+        let reply_to = self.reply_to().expect("Valid email");
 
-        EmailClient::new(
-            self.base_url,
-            sender,
-            self.authorization_token,
-            timeout,
-        )
+        let mut email_client = EmailClient::new(email_sender, sender)
+            .with_rate_limiter(RateLimiter::new(
+                self.send_rate,
+                &self.send_rate_per_domain,
+            ))
+            .with_circuit_breaker(CircuitBreaker::new(
+                self.circuit_breaker,
+            ));
+
+        if let Some(fallback_email_sender) = fallback_email_sender {
+            email_client =
+                email_client.with_fallback(fallback_email_sender);
+        }
+        if let Some(sender_name) = self.sender_name {
+            email_client = email_client.with_sender_name(sender_name);
+        }
+        if let Some(reply_to) = reply_to {
+            email_client = email_client.with_reply_to(reply_to);
+        }
+        if let Some(message_stream) = self.broadcast_message_stream {
+            email_client = email_client
+                .with_broadcast_message_stream(message_stream);
+        }
+
+        email_client
+    }
+
+    pub fn transport(
+        &self,
+    ) -> Result<EmailTransport, eyre::Report> {
+        self.build_transport(&self.transport)
+    }
+
+    pub fn fallback_transport(
+        &self,
+    ) -> Result<Option<EmailTransport>, eyre::Report> {
+        self.fallback_transport
+            .as_ref()
+            .map(|i| self.build_transport(i))
+            .transpose()
+    }
+
+    /// Postmark is reached at `base_url` with `authorization_token`. Emails
+    /// are only kept in memory or files in local environments.
+    fn build_transport(
+        &self,
+        settings: &EmailTransportSettings,
+    ) -> Result<EmailTransport, eyre::Report> {
+        if let EmailTransportSettings::File { .. } | EmailTransportSettings::Memory = settings
+            && Environment::current() != Environment::Local
+        {
+            eyre::bail!(
+                "The file and memory email transports are only available in local environments."
+            );
+        }
+
+        let transport = match settings {
+            EmailTransportSettings::Postmark => {
+                PostmarkEmailSender::new(
+                    self.base_url.to_string(),
+                    self.authorization_token
+                        .to_string()
+                        .into(),
+                    self.timeout(),
+                )
+                .pipe(EmailTransport::Postmark)
+            }
+            EmailTransportSettings::Smtp(smtp) => {
+                SmtpEmailSender::new(
+                    &smtp.host,
+                    smtp.port,
+                    smtp.username.clone(),
+                    &smtp.password,
+                    self.timeout(),
+                )?
+                .pipe(EmailTransport::Smtp)
+            }
+            EmailTransportSettings::File { directory } => {
+                FileEmailSender::new(directory)?
+                    .pipe(EmailTransport::File)
+            }
+            EmailTransportSettings::Memory => {
+                EmailTransport::Memory(MemoryEmailSender::new())
+            }
+        };
+
+        Ok(transport)
     }
 }
 
@@ -135,14 +325,117 @@ impl<P: SharedPointerHKT> Clone for EmailClientSettings<P> {
         Self {
             base_url: self.base_url.clone(),
             sender_email: self.sender_email.clone(),
+            sender_name: self.sender_name.clone(),
+            reply_to_email: self.reply_to_email.clone(),
+            broadcast_message_stream: self
+                .broadcast_message_stream
+                .clone(),
             authorization_token: self
                 .authorization_token
                 .clone(),
             timeout_milliseconds: self.timeout_milliseconds,
+            send_rate: self.send_rate,
+            send_rate_per_domain: self
+                .send_rate_per_domain
+                .clone(),
+            webhook: self.webhook.clone(),
+            transport: self.transport.clone(),
+            fallback_transport: self.fallback_transport.clone(),
+            circuit_breaker: self.circuit_breaker,
         }
     }
 }
 
+#[derive(serde::Deserialize, Clone)]
+#[derive(derive_more::Constructor)]
+pub struct IssueDeliverySettings {
+    pub max_attempts: u32,
+    pub retry_base_delay_milliseconds: u64,
+    pub retry_max_delay_milliseconds: u64,
+    /// Number of batches sent at once, each holding a connection of the
+    /// database pool.
+    pub concurrency: usize,
+    /// Recipients sent to per request to the email provider, capped to
+    /// `email_client::MAX_BATCH_SIZE`.
+    pub batch_size: usize,
+    /// Fallback polling of idle workers, in case a notification of
+    /// enqueued tasks is missed.
+    pub idle_delay_milliseconds: u64,
+    pub error_delay_milliseconds: u64,
+}
+
+impl IssueDeliverySettings {
+    #[must_use]
+    pub fn retry_base_delay(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(
+            self.retry_base_delay_milliseconds,
+        )
+    }
+
+    #[must_use]
+    pub fn retry_max_delay(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(
+            self.retry_max_delay_milliseconds,
+        )
+    }
+
+    #[must_use]
+    pub fn idle_delay(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(
+            self.idle_delay_milliseconds,
+        )
+    }
+
+    #[must_use]
+    pub fn error_delay(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(
+            self.error_delay_milliseconds,
+        )
+    }
+}
+
+#[derive(serde::Deserialize, Clone)]
+#[derive(derive_more::Constructor)]
+pub struct ConfirmationEmailSettings {
+    pub max_attempts: u32,
+    pub retry_base_delay_milliseconds: u64,
+    pub retry_max_delay_milliseconds: u64,
+    /// Fallback polling of the idle worker, in case a notification of
+    /// written emails is missed.
+    pub idle_delay_milliseconds: u64,
+    pub error_delay_milliseconds: u64,
+}
+
+impl ConfirmationEmailSettings {
+    #[must_use]
+    pub fn retry_base_delay(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(
+            self.retry_base_delay_milliseconds,
+        )
+    }
+
+    #[must_use]
+    pub fn retry_max_delay(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(
+            self.retry_max_delay_milliseconds,
+        )
+    }
+
+    #[must_use]
+    pub fn idle_delay(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(
+            self.idle_delay_milliseconds,
+        )
+    }
+
+    #[must_use]
+    pub fn error_delay(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(
+            self.error_delay_milliseconds,
+        )
+    }
+}
+
 #[derive(serde::Deserialize)]
 #[derive(
     derive_more::Deref,
@@ -161,11 +454,42 @@ impl<P: SharedPointerHKT> Clone for HmacSecret<P> {
     }
 }
 
+#[derive(serde::Deserialize)]
+#[derive(
+    derive_more::Deref,
+    derive_more::AsRef,
+    derive_more::From,
+    derive_more::Into,
+)]
+#[serde(bound(deserialize = "P: RefHKT"))]
+pub struct UnsubscribeSecret<P: HKT1Unsized>(
+    pub K1<P, SecretString>,
+);
+
+impl<P: SharedPointerHKT> Clone for UnsubscribeSecret<P> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Environment {
     Local,
     Production,
 }
 impl Environment {
+    /// Read from `APP_ENVIRONMENT`, local by default.
+    ///
+    /// # Panics
+    /// If `APP_ENVIRONMENT` is neither `local` nor `production`.
+    #[must_use]
+    pub fn current() -> Self {
+        std::env::var(APP_ENVIRONMENT)
+            .unwrap_or_else(|_| "local".to_string())
+            .pipe(Environment::try_from)
+            .expect("Parse env var failed.")
+    }
+
     #[must_use]
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -207,15 +531,8 @@ pub fn get_configuration<P: RefHKT>()
         )
         .add_source(
             config::File::from(
-                configuration_directory.join(
-                    std::env::var(APP_ENVIRONMENT)
-                        .unwrap_or_else(|_| {
-                            "local".to_string()
-                        })
-                        .pipe(Environment::try_from)
-                        .expect("Parse env var failed.")
-                        .as_str(),
-                ),
+                configuration_directory
+                    .join(Environment::current().as_str()),
             )
             .required(true),
         )
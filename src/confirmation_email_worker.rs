@@ -0,0 +1,312 @@
+use std::time::Duration;
+
+use eyre::Context;
+use uuid::Uuid;
+
+use crate::{
+    configuration::Settings,
+    database::transactional::{
+        notifications::{
+            ConfirmationEmailOutboxChannel,
+            NotificationListener,
+        },
+        subscriptions::{
+            ConfirmationEmail, SubscriptionsRepository,
+        },
+        unit_of_work::{BeginUnitOfWork, UnitOfWork as _},
+    },
+    domain::{SubscriberEmail, SubscriptionToken},
+    email_client::{
+        EmailClient, EmailSender, SendEmailError,
+        SendEmailErrorKind,
+    },
+    hkt::{SendHKT, SharedPointerHKT, SyncHKT},
+    routes::{confirmation_link, send_confirmation_email},
+    startup::GlobalSharedPointer,
+    utils::Pipe as _,
+    worker::{RetryPolicy, wait_for_notification},
+};
+
+pub enum ConfirmationEmailTaskResult {
+    Completed,
+    NothingFound,
+}
+
+pub async fn run_worker_until_stopped<
+    P: SharedPointerHKT + SendHKT + SyncHKT,
+    B: BeginUnitOfWork,
+    S: SubscriptionsRepository<UnitOfWork = B::UnitOfWork>,
+    L: NotificationListener<ConfirmationEmailOutboxChannel>,
+    E: EmailSender,
+>(
+    begin_unit_of_work: GlobalSharedPointer<B>,
+    subscriptions_repository: GlobalSharedPointer<S>,
+    confirmation_email_outbox_listener: GlobalSharedPointer<
+        L,
+    >,
+    // Shared with the application, along with its send rate limits.
+    email_client: EmailClient<P, E>,
+    configuration: Settings<P>,
+) -> Result<(), eyre::Report> {
+    let settings =
+        configuration.confirmation_email.as_ref();
+    let retry_policy = RetryPolicy::from(settings);
+    let mut subscription = None;
+
+    loop {
+        // Emails are left in the outbox while the provider is failing,
+        // rather than retried without being sent.
+        if let Some(retry_in) =
+            email_client.unavailable_for()
+        {
+            tracing::debug!(
+                ?retry_in,
+                "Waiting for the circuit breaker of the email provider."
+            );
+            tokio::time::sleep(retry_in).await;
+            continue;
+        }
+
+        match send_next_confirmation_email(
+            &*begin_unit_of_work,
+            &*subscriptions_repository,
+            &email_client,
+            &configuration.application.base_url,
+            &retry_policy,
+        )
+        .await
+        {
+            Ok(ConfirmationEmailTaskResult::Completed) => {}
+            Ok(
+                ConfirmationEmailTaskResult::NothingFound,
+            ) => {
+                wait_for_notification(
+                    &*confirmation_email_outbox_listener,
+                    &mut subscription,
+                    settings.idle_delay(),
+                )
+                .await;
+            }
+            Err(e) => {
+                tracing::error!(
+                    error.cause_chain = ?e,
+                    error.message = %e,
+                    "Failed to send confirmation email."
+                );
+                tokio::time::sleep(settings.error_delay())
+                    .await;
+            }
+        }
+    }
+}
+
+/// Sends a due email of the outbox, which leaves it once sent, rejected or
+/// out of attempts. Its link gets a token of its own, committed before the
+/// email is sent, so the link stays valid even if storing the outcome fails.
+#[tracing::instrument(
+    name = "Send next confirmation email of the outbox.",
+    skip_all
+)]
+pub async fn send_next_confirmation_email<
+    P: SharedPointerHKT + SendHKT + SyncHKT,
+    B: BeginUnitOfWork,
+    S: SubscriptionsRepository<UnitOfWork = B::UnitOfWork>,
+    E: EmailSender,
+>(
+    begin_unit_of_work: &B,
+    subscriptions_repository: &S,
+    email_client: &EmailClient<P, E>,
+    application_base_url: &str,
+    retry_policy: &RetryPolicy,
+) -> Result<ConfirmationEmailTaskResult, eyre::Report> {
+    let mut unit_of_work = begin_unit_of_work
+        .begin()
+        .await
+        .context("Failed to begin unit of work.")?;
+
+    let Some(confirmation_email) = subscriptions_repository
+        .acquire_confirmation_email(&mut unit_of_work)
+        .await
+        .context("Failed to acquire confirmation email.")?
+    else {
+        return Ok(
+            ConfirmationEmailTaskResult::NothingFound,
+        );
+    };
+
+    let result = match confirmation_email
+        .subscriber_email
+        .clone()
+        .pipe(P::from_string)
+        .pipe(SubscriberEmail::try_from)
+    {
+        Ok(recipient) => {
+            let token =
+                store_confirmation_token::<P, _, _>(
+                    begin_unit_of_work,
+                    subscriptions_repository,
+                    &confirmation_email.subscriber_id,
+                )
+                .await?;
+
+            send_confirmation_email(
+                email_client,
+                recipient,
+                &confirmation_link(
+                    application_base_url,
+                    token.to_string().as_str(),
+                ),
+            )
+            .await
+        }
+        Err(e) => {
+            // Emails are validated on subscription, so this is not retried.
+            tracing::warn!(
+                "Found confirmation email to invalid address: '{}'\n
+                Error: '{e}'",
+                confirmation_email.subscriber_email
+            );
+            Ok(())
+        }
+    };
+
+    // Never reached the provider, so the email is left in the outbox
+    // without counting an attempt. Its unsent token expires unused.
+    if let Err(SendEmailError::CircuitOpen) = result {
+        tracing::debug!(
+            "Circuit breaker of the email provider is open, leaving the confirmation email to '{}' in the outbox.",
+            confirmation_email.subscriber_email
+        );
+        return Ok(ConfirmationEmailTaskResult::Completed);
+    }
+
+    let retry_delay = settle_confirmation_email(
+        &mut unit_of_work,
+        subscriptions_repository,
+        &confirmation_email,
+        &result,
+        retry_policy,
+    )
+    .await?;
+
+    unit_of_work
+        .commit()
+        .await
+        .context("Failed to commit unit of work.")?;
+
+    // Only failures to be retried are errors, so the worker backs off from
+    // the provider.
+    match result {
+        Err(e) if retry_delay.is_some() => {
+            eyre::Report::new(e)
+                .wrap_err(format!(
+                    "Failed to send confirmation email to: '{}'",
+                    confirmation_email.subscriber_email
+                ))
+                .pipe(Err)
+        }
+        _ => Ok(ConfirmationEmailTaskResult::Completed),
+    }
+}
+
+/// In a unit of work of its own, so the token outlives the one of the email
+/// being sent.
+async fn store_confirmation_token<
+    P: SharedPointerHKT,
+    B: BeginUnitOfWork,
+    S: SubscriptionsRepository<UnitOfWork = B::UnitOfWork>,
+>(
+    begin_unit_of_work: &B,
+    subscriptions_repository: &S,
+    subscriber_id: &Uuid,
+) -> Result<SubscriptionToken, eyre::Report> {
+    let mut unit_of_work = begin_unit_of_work
+        .begin()
+        .await
+        .context("Failed to begin unit of work.")?;
+
+    let token = subscriptions_repository
+        .store_token::<P>(&mut unit_of_work, subscriber_id)
+        .await
+        .context("Failed to store confirmation token.")?;
+
+    unit_of_work
+        .commit()
+        .await
+        .context("Failed to commit confirmation token.")?;
+
+    Ok(token)
+}
+
+/// Schedules a retry of a failed email, returning its delay. Otherwise the
+/// email leaves the outbox: dead lettered once out of attempts, deleted once
+/// sent or rejected.
+async fn settle_confirmation_email<
+    S: SubscriptionsRepository,
+>(
+    unit_of_work: &mut S::UnitOfWork,
+    subscriptions_repository: &S,
+    confirmation_email: &ConfirmationEmail,
+    result: &Result<(), SendEmailError>,
+    retry_policy: &RetryPolicy,
+) -> Result<Option<Duration>, eyre::Report> {
+    let ConfirmationEmail {
+        confirmation_email_id,
+        subscriber_email,
+        n_retries,
+        ..
+    } = confirmation_email;
+
+    let retry_delay = match result {
+        Ok(()) => None,
+        Err(e)
+            if e.kind()
+                == SendEmailErrorKind::Permanent =>
+        {
+            tracing::warn!(
+                error.cause_chain = ?e,
+                "Email provider rejected '{subscriber_email}', dropping their confirmation email."
+            );
+            None
+        }
+        Err(_) => retry_policy.retry_delay(*n_retries),
+    };
+
+    match (result, retry_delay) {
+        (_, Some(retry_delay)) => subscriptions_repository
+            .schedule_confirmation_email_retry(
+                unit_of_work,
+                *confirmation_email_id,
+                retry_delay,
+            )
+            .await
+            .context("Failed to schedule confirmation email retry.")?,
+        // Kept for inspection rather than silently dropped.
+        (Err(e), None)
+            if e.kind()
+                == SendEmailErrorKind::Transient =>
+        {
+            tracing::error!(
+                error.cause_chain = ?e,
+                "Confirmation email to '{subscriber_email}' ran out of attempts, dead lettering it."
+            );
+            subscriptions_repository
+                .dead_letter_confirmation_email(
+                    unit_of_work,
+                    *confirmation_email_id,
+                    &e.to_string(),
+                )
+                .await
+                .context("Failed to dead letter confirmation email.")?;
+        }
+        (_, None) => subscriptions_repository
+            .delete_confirmation_email(
+                unit_of_work,
+                *confirmation_email_id,
+            )
+            .await
+            .context("Failed to delete confirmation email.")?,
+    }
+
+    Ok(retry_delay)
+}
@@ -1,6 +1,8 @@
 use std::marker::PhantomData;
 
+use chrono::{DateTime, Utc};
 use secrecy::{ExposeSecret as _, SecretString};
+use sqlx::postgres::types::PgInterval;
 use uuid::Uuid;
 
 use crate::{
@@ -10,23 +12,56 @@ use crate::{
             GetHashedCredentialsError, HashedCredentials,
         },
         issue_delivery_queue::{
-            DisableTaskError, EnqueueDeliveryTaskResult,
+            DeadLetter, DeadLetterTaskError,
+            DisableTaskError, EmailDelivery,
+            EmailDeliveryFilter, EmailDeliveryStatus,
+            EnqueueDeliveryTaskResult,
             FinalizeNewsletterTaskError,
+            GetDeadLettersError, GetDeliveryProgressError,
+            GetEmailDeliveriesError, IssueDeliveryProgress,
             IssueDeliveryQueueRepository,
-            ScheduleTaskRetryError,
+            IssueDeliveryStatus, NewEmailDelivery,
+            RecordEmailDeliveryError,
+            RequeueDeadLetterError, ScheduleTaskRetryError,
+            UpdateIssueDeliveryStatusError,
         },
         newsletters::{
-            GetNewsletterContentError, NewsletterContent,
-            NewslettersRepository,
+            CancelScheduledNewsletterIssueError,
+            GetNewsletterContentError,
+            GetNewsletterDraftsError,
+            GetPublishedNewsletterIssuesError,
+            GetScheduledNewsletterIssuesError,
+            NewsletterContent, NewsletterDraft,
+            NewsletterDraftError, NewsletterDraftSummary,
+            NewslettersRepository, PublicNewsletterIssue,
+            PublicNewsletterIssueError,
+            PublishedNewsletterIssue,
+            ScheduledNewsletterIssue,
+        },
+        notifications::{
+            Channel, ConfirmationEmailOutboxChannel,
+            IssueDeliveryQueueChannel, ListenError,
+            NotificationListener, NotificationSubscription,
+            WaitForNotificationError,
         },
         persistence::{
             GetSavedResponseBodyError, HeaderPairRecord,
             PersistenceRepository, SaveResponseBodyError,
             SavedResponseBody,
         },
-        subscriptions::SubscriptionsRepository,
+        subscriptions::{
+            AcquireConfirmationEmailError,
+            ConfirmationEmail,
+            DeadLetterConfirmationEmailError,
+            DeleteConfirmationEmailError, EmailEvent,
+            EnqueueConfirmationEmailError,
+            RecordEmailEventError,
+            ScheduleConfirmationEmailRetryError,
+            SubscriptionsRepository, SuppressEmailError,
+            SuppressionReason,
+        },
         subscriptions_confirm::{
-            GetSubscriberIdOfConfirmationTokenError,
+            ConsumeConfirmationTokenError,
             SubscriptionsConfirmRepository,
             UpdateConfirmationStatusOfSubscriberIdError,
         },
@@ -35,7 +70,7 @@ use crate::{
             UnitOfWorkRepository,
         },
     },
-    domain::NewSubscriber,
+    domain::{NewSubscriber, SubscriptionToken},
     hkt::{SendHKT, SharedPointerHKT, SyncHKT},
     idempotency::IdempotencyKey,
     issue_delivery_worker::IssueDeliveryRecord,
@@ -45,15 +80,18 @@ use crate::{
 };
 
 use super::transactional::{
-    authentication::UpdatePasswordError,
+    authentication::{
+        UpdateEmailError, UpdatePasswordError,
+    },
     issue_delivery_queue::{
         AcquireNewsletterTaskError,
-        AcquireNewsletterTaskFromIssueError,
+        AcquireNewsletterTasksFromIssueError,
         EnqueueDeliveryTaskError,
     },
     newsletters::InsertNewsletterIssueError,
     subscriptions::{
         InsertSubscriberError, StoreTokenError,
+        UnsubscribeError, UpsertedSubscriber,
     },
     unit_of_work::CommitError,
 };
@@ -145,15 +183,31 @@ impl<D: PgRepositoryDependencies>
         &self,
         unit_of_work: &mut Self::UnitOfWork,
         record: &IssueDeliveryRecord,
+        retry_delay: std::time::Duration,
     ) -> Result<(), ScheduleTaskRetryError> {
         sqlx::query!(
             "--sql
             UPDATE issue_delivery_queue
             SET n_retries = n_retries + 1,
-                execute_after = execute_after * 1.5
-            WHERE newsletter_issue_id = $1
+                execute_after = $3 - newsletter_issues.published_at + $4
+            FROM newsletter_issues
+            WHERE issue_delivery_queue.newsletter_issue_id = $1
+            AND issue_delivery_queue.subscriber_email = $2
+            AND newsletter_issues.newsletter_issue_id
+                = issue_delivery_queue.newsletter_issue_id
             ",
-            &record.newsletter_issue_id
+            &record.newsletter_issue_id,
+            &record.subscriber_email,
+            self.clock.now(),
+            // Postgres intervals are precise to the microsecond only.
+            PgInterval {
+                months: 0,
+                days: 0,
+                microseconds: i64::try_from(
+                    retry_delay.as_micros()
+                )
+                .map_err(eyre::Report::new)?,
+            },
         )
         .execute(&mut **unit_of_work)
         .await
@@ -163,6 +217,252 @@ impl<D: PgRepositoryDependencies>
         Ok(())
     }
 
+    async fn dead_letter_task(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        record: &IssueDeliveryRecord,
+        last_error: &str,
+    ) -> Result<(), DeadLetterTaskError> {
+        sqlx::query!(
+            "--sql
+            WITH dead_task AS (
+                DELETE FROM issue_delivery_queue
+                WHERE newsletter_issue_id = $1
+                AND subscriber_email = $2
+                RETURNING newsletter_issue_id,
+                    subscriber_email,
+                    n_retries
+            )
+            INSERT INTO issue_delivery_dead_letters (
+                newsletter_issue_id,
+                subscriber_email,
+                n_attempts,
+                last_error,
+                dead_lettered_at
+            )
+            SELECT newsletter_issue_id,
+                subscriber_email,
+                n_retries + 1,
+                $3,
+                $4
+            FROM dead_task
+            ON CONFLICT (newsletter_issue_id, subscriber_email)
+            DO UPDATE SET n_attempts = EXCLUDED.n_attempts,
+                last_error = EXCLUDED.last_error,
+                dead_lettered_at = EXCLUDED.dead_lettered_at
+            ",
+            &record.newsletter_issue_id,
+            &record.subscriber_email,
+            last_error,
+            self.clock.now(),
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        Ok(())
+    }
+
+    async fn get_dead_letters(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+    ) -> Result<Vec<DeadLetter>, GetDeadLettersError> {
+        sqlx::query_as!(
+            DeadLetter,
+            "--sql
+            SELECT issue_delivery_dead_letters.newsletter_issue_id,
+                newsletter_issues.title,
+                issue_delivery_dead_letters.subscriber_email,
+                issue_delivery_dead_letters.n_attempts,
+                issue_delivery_dead_letters.last_error,
+                issue_delivery_dead_letters.dead_lettered_at
+            FROM issue_delivery_dead_letters
+            INNER JOIN newsletter_issues
+            ON newsletter_issues.newsletter_issue_id
+                = issue_delivery_dead_letters.newsletter_issue_id
+            ORDER BY issue_delivery_dead_letters.dead_lettered_at DESC
+            "
+        )
+        .fetch_all(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?
+        .pipe(Ok)
+    }
+
+    async fn requeue_dead_letter(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        newsletter_issue_id: Uuid,
+        subscriber_email: &str,
+    ) -> Result<(), RequeueDeadLetterError> {
+        let dead_letter = sqlx::query!(
+            "--sql
+            SELECT newsletter_issue_id
+            FROM issue_delivery_dead_letters
+            WHERE newsletter_issue_id = $1
+            AND subscriber_email = $2
+            FOR UPDATE
+            ",
+            newsletter_issue_id,
+            subscriber_email,
+        )
+        .fetch_optional(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        if dead_letter.is_none() {
+            return Err(RequeueDeadLetterError::NotFound(
+                newsletter_issue_id,
+                subscriber_email.to_owned(),
+            ));
+        }
+
+        // The dead letter is kept while its recipient is still queued.
+        let result = sqlx::query!(
+            "--sql
+            INSERT INTO issue_delivery_queue (
+                newsletter_issue_id,
+                subscriber_email
+            )
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            ",
+            newsletter_issue_id,
+            subscriber_email,
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        if result.rows_affected() == 0 {
+            return Err(
+                RequeueDeadLetterError::AlreadyQueued(
+                    newsletter_issue_id,
+                    subscriber_email.to_owned(),
+                ),
+            );
+        }
+
+        sqlx::query!(
+            "--sql
+            DELETE FROM issue_delivery_dead_letters
+            WHERE newsletter_issue_id = $1
+            AND subscriber_email = $2
+            ",
+            newsletter_issue_id,
+            subscriber_email,
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        notify(
+            unit_of_work,
+            IssueDeliveryQueueChannel::NAME,
+            &newsletter_issue_id.to_string(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn record_email_delivery(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        email_delivery: &NewEmailDelivery,
+    ) -> Result<(), RecordEmailDeliveryError> {
+        sqlx::query!(
+            "--sql
+            INSERT INTO email_deliveries (
+                email_delivery_id,
+                newsletter_issue_id,
+                subscriber_email,
+                attempt,
+                status,
+                http_status,
+                provider_message_id,
+                error,
+                attempted_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ",
+            self.uuid_generator.generate_uuid(),
+            email_delivery.newsletter_issue_id,
+            email_delivery.subscriber_email,
+            email_delivery.attempt,
+            email_delivery.status.to_string(),
+            email_delivery.http_status.map(i32::from),
+            email_delivery.provider_message_id,
+            email_delivery.error,
+            self.clock.now(),
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn get_email_deliveries(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        filter: &EmailDeliveryFilter,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<EmailDelivery>, GetEmailDeliveriesError>
+    {
+        sqlx::query!(
+            "--sql
+            SELECT email_deliveries.newsletter_issue_id,
+                newsletter_issues.title,
+                email_deliveries.subscriber_email,
+                email_deliveries.attempt,
+                email_deliveries.status,
+                email_deliveries.http_status,
+                email_deliveries.provider_message_id,
+                email_deliveries.error,
+                email_deliveries.attempted_at
+            FROM email_deliveries
+            INNER JOIN newsletter_issues
+            ON newsletter_issues.newsletter_issue_id
+                = email_deliveries.newsletter_issue_id
+            WHERE ($1::uuid IS NULL
+                OR email_deliveries.newsletter_issue_id = $1)
+            AND ($2::text IS NULL
+                OR email_deliveries.subscriber_email = $2)
+            ORDER BY email_deliveries.attempted_at DESC,
+                email_deliveries.email_delivery_id
+            LIMIT $3
+            OFFSET $4
+            ",
+            filter.newsletter_issue_id,
+            filter.subscriber_email,
+            i64::try_from(limit).unwrap_or(i64::MAX),
+            i64::try_from(offset).unwrap_or(i64::MAX),
+        )
+        .fetch_all(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?
+        .into_iter()
+        .map(|r| {
+            EmailDelivery {
+                newsletter_issue_id: r.newsletter_issue_id,
+                title: r.title,
+                subscriber_email: r.subscriber_email,
+                attempt: r.attempt,
+                status: parse_email_delivery_status(&r.status)?,
+                http_status: r.http_status,
+                provider_message_id: r.provider_message_id,
+                error: r.error,
+                attempted_at: r.attempted_at,
+            }
+            .pipe(Ok)
+        })
+        .collect()
+    }
+
     async fn disable_task(
         &self,
         unit_of_work: &mut Self::UnitOfWork,
@@ -185,32 +485,49 @@ impl<D: PgRepositoryDependencies>
     }
 
     #[tracing::instrument(
-        name = "Get and uniquely lock a task in the issue delivery queue from a specific issue.",
+        name = "Get and uniquely lock a batch of tasks in the issue delivery queue from a specific issue.",
         skip_all
     )]
-    async fn acquire_newsletter_task_from_issue(
+    async fn acquire_newsletter_tasks_from_issue(
         &self,
         unit_of_work: &mut Self::UnitOfWork,
         newsletter_issue_id: uuid::Uuid,
+        limit: usize,
     ) -> Result<
-        Option<IssueDeliveryRecord>,
-        AcquireNewsletterTaskFromIssueError,
+        Vec<IssueDeliveryRecord>,
+        AcquireNewsletterTasksFromIssueError,
     > {
+        // Rows returned by a function are not locked, those of the queue are.
         sqlx::query_as!(
             IssueDeliveryRecord,
             r#"--sql
-            SELECT newsletter_issue_id as "newsletter_issue_id!",
-                subscriber_email as "subscriber_email!"
-            FROM get_available_issue_delivery_queue($2)
-            WHERE newsletter_issue_id = $1
-            FOR UPDATE
+            SELECT issue_delivery_queue.newsletter_issue_id,
+                issue_delivery_queue.subscriber_email,
+                issue_delivery_queue.n_retries,
+                subscriptions.id as subscriber_id,
+                subscriptions.name as subscriber_name,
+                subscriptions.confirmed_at
+            FROM issue_delivery_queue
+            INNER JOIN subscriptions
+            ON subscriptions.email
+                = issue_delivery_queue.subscriber_email
+            WHERE (
+                issue_delivery_queue.newsletter_issue_id,
+                issue_delivery_queue.subscriber_email
+            ) IN (
+                SELECT newsletter_issue_id, subscriber_email
+                FROM get_available_issue_delivery_queue($2)
+                WHERE newsletter_issue_id = $1
+            )
+            FOR UPDATE OF issue_delivery_queue
             SKIP LOCKED
-            LIMIT 1
+            LIMIT $3
         "#,
             newsletter_issue_id,
-            self.clock.now()
+            self.clock.now(),
+            i64::try_from(limit).unwrap_or(i64::MAX),
         )
-        .fetch_optional(&mut **unit_of_work)
+        .fetch_all(&mut **unit_of_work)
         .await
         .map_err(eyre::Report::new)?
         .pipe(Ok)
@@ -227,8 +544,12 @@ impl<D: PgRepositoryDependencies>
     {
         sqlx::query!(
             "--sql
-            SELECT newsletter_issue_id as \"newsletter_issue_id!\"
-            FROM get_available_issue_delivery_queue($1)
+            SELECT newsletter_issue_id
+            FROM issue_delivery_queue
+            WHERE (newsletter_issue_id, subscriber_email) IN (
+                SELECT newsletter_issue_id, subscriber_email
+                FROM get_available_issue_delivery_queue($1)
+            )
             FOR UPDATE
             SKIP LOCKED
             LIMIT 1
@@ -285,6 +606,11 @@ impl<D: PgRepositoryDependencies>
             SELECT $1, email
             FROM subscriptions
             WHERE status = 'confirmed'
+            AND NOT EXISTS (
+                SELECT 1 FROM email_suppressions
+                WHERE lower(email_suppressions.email)
+                    = lower(subscriptions.email)
+            )
             ",
             newsletter_issue_id,
         )
@@ -298,113 +624,578 @@ impl<D: PgRepositoryDependencies>
             );
         }
 
+        sqlx::query!(
+            "--sql
+            UPDATE newsletter_issues
+            SET n_recipients = $2
+            WHERE newsletter_issue_id = $1
+            ",
+            newsletter_issue_id,
+            i64::try_from(result.rows_affected())
+                .map_err(eyre::Report::new)?,
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        notify(
+            unit_of_work,
+            IssueDeliveryQueueChannel::NAME,
+            &newsletter_issue_id.to_string(),
+        )
+        .await?;
+
         Ok(EnqueueDeliveryTaskResult::Enqueued)
     }
-}
 
-impl<D: PgPoolDependencies> AuthenticationRepository
-    for PgPool<D>
-{
-    async fn get_hashed_credentials_from_username(
+    async fn get_delivery_progress(
         &self,
-        username: &str,
+        unit_of_work: &mut Self::UnitOfWork,
     ) -> Result<
-        Option<HashedCredentials>,
-        GetHashedCredentialsError,
+        Vec<IssueDeliveryProgress>,
+        GetDeliveryProgressError,
     > {
-        sqlx::query_as!(
-            HashedCredentials,
-            "--sql
-            SELECT user_id, username, salted_password
-            FROM newsletter_writers
-            WHERE username = $1",
-            &username
+        sqlx::query!(
+            r#"--sql
+            SELECT newsletter_issues.newsletter_issue_id,
+                newsletter_issues.delivery_status,
+                newsletter_issues.n_recipients,
+                COUNT(issue_delivery_queue.subscriber_email)
+                    FILTER (
+                        WHERE issue_delivery_queue.enabled
+                        AND NOT issue_delivery_queue.cancelled
+                        AND issue_delivery_queue.n_retries = 0
+                    ) AS "pending!",
+                COUNT(issue_delivery_queue.subscriber_email)
+                    FILTER (
+                        WHERE issue_delivery_queue.enabled
+                        AND NOT issue_delivery_queue.cancelled
+                        AND issue_delivery_queue.n_retries > 0
+                    ) AS "retrying!",
+                COUNT(issue_delivery_queue.subscriber_email)
+                    FILTER (
+                        WHERE NOT issue_delivery_queue.enabled
+                        AND (
+                            issue_delivery_queue.newsletter_issue_id,
+                            issue_delivery_queue.subscriber_email
+                        ) NOT IN (
+                            SELECT newsletter_issue_id, subscriber_email
+                            FROM email_deliveries
+                        )
+                    ) AS "skipped!",
+                COUNT(issue_delivery_queue.subscriber_email)
+                    FILTER (
+                        WHERE issue_delivery_queue.enabled
+                        AND issue_delivery_queue.cancelled
+                    ) AS "cancelled!",
+                (
+                    SELECT COUNT(*)
+                    FROM issue_delivery_dead_letters
+                    WHERE issue_delivery_dead_letters.newsletter_issue_id
+                        = newsletter_issues.newsletter_issue_id
+                ) AS "dead_lettered!",
+                (
+                    SELECT COUNT(*)
+                    FROM email_delivery_outcomes
+                    WHERE email_delivery_outcomes.newsletter_issue_id
+                        = newsletter_issues.newsletter_issue_id
+                    AND email_delivery_outcomes.is_sent
+                ) AS "delivered!",
+                -- Given up on after a failure, without running out of
+                -- attempts, e.g. rejected & suppressed.
+                (
+                    SELECT COUNT(*)
+                    FROM email_delivery_outcomes AS failed
+                    WHERE failed.newsletter_issue_id
+                        = newsletter_issues.newsletter_issue_id
+                    AND NOT failed.is_sent
+                    AND (failed.newsletter_issue_id, failed.subscriber_email)
+                    NOT IN (
+                        SELECT newsletter_issue_id, subscriber_email
+                        FROM issue_delivery_queue
+                        WHERE enabled
+                        UNION ALL
+                        SELECT newsletter_issue_id, subscriber_email
+                        FROM issue_delivery_dead_letters
+                    )
+                ) AS "failed!"
+            FROM newsletter_issues
+            LEFT JOIN issue_delivery_queue
+            ON newsletter_issues.newsletter_issue_id
+                = issue_delivery_queue.newsletter_issue_id
+            WHERE newsletter_issues.status = 'published'
+            AND newsletter_issues.published_at <= $1
+            GROUP BY newsletter_issues.newsletter_issue_id
+            ORDER BY newsletter_issues.published_at DESC
+            "#,
+            self.clock.now()
         )
-        .fetch_optional(&self.0)
+        .fetch_all(&mut **unit_of_work)
         .await
-        .map_err(|e| {
-            GetHashedCredentialsError::Unexpected(
-                e.pipe(eyre::Report::new),
+        .map_err(eyre::Report::new)?
+        .into_iter()
+        .map(|r| {
+            IssueDeliveryProgress::new(
+                r.newsletter_issue_id,
+                parse_issue_delivery_status(
+                    &r.delivery_status,
+                )?,
+                r.n_recipients,
+                r.pending,
+                r.retrying,
+                r.failed,
+                r.skipped,
+                r.cancelled,
+                r.dead_lettered,
+                r.delivered,
             )
+            .pipe(Ok)
         })
+        .collect()
     }
 
-    async fn get_hashed_credentials_from_user_id(
+    async fn pause_issue_delivery(
         &self,
-        user_id: Uuid,
-    ) -> Result<
-        Option<HashedCredentials>,
-        GetHashedCredentialsError,
-    > {
-        sqlx::query_as!(
-            HashedCredentials,
+        unit_of_work: &mut Self::UnitOfWork,
+        newsletter_issue_id: Uuid,
+    ) -> Result<(), UpdateIssueDeliveryStatusError> {
+        let result = sqlx::query!(
             "--sql
-            SELECT user_id, username, salted_password
-            FROM newsletter_writers
-            WHERE user_id = $1",
-            &user_id
+            UPDATE newsletter_issues
+            SET delivery_status = 'paused'
+            WHERE newsletter_issue_id = $1
+            AND status = 'published'
+            AND delivery_status = 'active'
+            ",
+            newsletter_issue_id,
         )
-        .fetch_optional(&self.0)
+        .execute(&mut **unit_of_work)
         .await
-        .map_err(|e| {
-            GetHashedCredentialsError::Unexpected(
-                e.pipe(eyre::Report::new),
-            )
-        })?
-        .pipe(Ok)
+        .map_err(eyre::Report::new)?;
+
+        if result.rows_affected() == 0 {
+            return Err(
+                UpdateIssueDeliveryStatusError::NotFound(
+                    newsletter_issue_id,
+                ),
+            );
+        }
+
+        Ok(())
     }
 
-    async fn update_password(
+    async fn resume_issue_delivery(
         &self,
-        user_id: Uuid,
-        new_salted_password: &SecretString,
-    ) -> Result<(), UpdatePasswordError> {
-        sqlx::query!(
+        unit_of_work: &mut Self::UnitOfWork,
+        newsletter_issue_id: Uuid,
+    ) -> Result<(), UpdateIssueDeliveryStatusError> {
+        let result = sqlx::query!(
             "--sql
-        UPDATE newsletter_writers SET
-        salted_password = $1
-        WHERE user_id = $2
-        ",
-            &new_salted_password.expose_secret(),
-            &user_id
+            UPDATE newsletter_issues
+            SET delivery_status = 'active'
+            WHERE newsletter_issue_id = $1
+            AND status = 'published'
+            AND delivery_status = 'paused'
+            ",
+            newsletter_issue_id,
         )
-        .execute(&self.0)
+        .execute(&mut **unit_of_work)
         .await
-        .map_err(|e| {
-            UpdatePasswordError::Unexpected(
-                e.pipe(eyre::Report::new),
-            )
-        })?;
+        .map_err(eyre::Report::new)?;
+
+        if result.rows_affected() == 0 {
+            return Err(
+                UpdateIssueDeliveryStatusError::NotFound(
+                    newsletter_issue_id,
+                ),
+            );
+        }
+
+        notify(
+            unit_of_work,
+            IssueDeliveryQueueChannel::NAME,
+            &newsletter_issue_id.to_string(),
+        )
+        .await?;
 
         Ok(())
     }
-}
 
-#[derive(Debug, sqlx::Type)]
-#[sqlx(type_name = "header_pair")]
-pub struct SqlxHeaderPairRecord {
-    name: String,
-    value: Vec<u8>,
-}
-
-impl<D: PgRepositoryDependencies> PersistenceRepository
-    for PgRepository<D>
-{
-    async fn get_saved_response_body<'a>(
+    async fn cancel_issue_delivery(
         &self,
-        unit_of_work: &'a mut Self::UnitOfWork,
-        idempotency_key: &'a IdempotencyKey<'a>,
-        user_id: Uuid,
-    ) -> Result<
-        Option<SavedResponseBody>,
-        GetSavedResponseBodyError<'a>,
-    > {
-        sqlx::query!(
-            r#"--sql
-            SELECT
-            response_status_code,
-            response_headers as "response_headers: Vec<SqlxHeaderPairRecord>",
-            response_body
+        unit_of_work: &mut Self::UnitOfWork,
+        newsletter_issue_id: Uuid,
+    ) -> Result<(), UpdateIssueDeliveryStatusError> {
+        let result = sqlx::query!(
+            "--sql
+            UPDATE newsletter_issues
+            SET delivery_status = 'cancelled'
+            WHERE newsletter_issue_id = $1
+            AND status = 'published'
+            AND delivery_status IN ('active', 'paused')
+            ",
+            newsletter_issue_id,
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        if result.rows_affected() == 0 {
+            return Err(
+                UpdateIssueDeliveryStatusError::NotFound(
+                    newsletter_issue_id,
+                ),
+            );
+        }
+
+        sqlx::query!(
+            "--sql
+            UPDATE issue_delivery_queue
+            SET cancelled = true
+            WHERE newsletter_issue_id = $1
+            AND enabled
+            ",
+            newsletter_issue_id,
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        Ok(())
+    }
+}
+
+/// Delivered to the listening workers once the unit of work commits,
+/// if ever.
+async fn notify(
+    unit_of_work: &mut PgTransaction,
+    channel: &str,
+    payload: &str,
+) -> Result<(), eyre::Report> {
+    sqlx::query!(
+        "--sql
+        SELECT pg_notify($1, $2)
+        ",
+        channel,
+        payload,
+    )
+    .execute(&mut **unit_of_work)
+    .await
+    .map_err(eyre::Report::new)?;
+
+    Ok(())
+}
+
+/// Holds a connection of its own, outside of the pool.
+async fn listen_to(
+    pool: &sqlx::PgPool,
+    channel: &str,
+) -> Result<sqlx::postgres::PgListener, eyre::Report> {
+    let mut listener =
+        sqlx::postgres::PgListener::connect_with(pool)
+            .await
+            .map_err(eyre::Report::new)?;
+
+    listener
+        .listen(channel)
+        .await
+        .map_err(eyre::Report::new)?;
+
+    Ok(listener)
+}
+
+/// A single connection listening to a channel, opened by the first
+/// subscription & fanning its notifications out to all of them.
+pub struct PgNotificationListener<C: Channel> {
+    pool: sqlx::PgPool,
+    sender: tokio::sync::OnceCell<
+        tokio::sync::broadcast::Sender<()>,
+    >,
+    channel: PhantomData<C>,
+}
+
+impl<C: Channel> PgNotificationListener<C> {
+    #[must_use]
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self {
+            pool,
+            sender: tokio::sync::OnceCell::new(),
+            channel: PhantomData,
+        }
+    }
+
+    async fn subscribe(
+        &self,
+    ) -> Result<
+        tokio::sync::broadcast::Receiver<()>,
+        eyre::Report,
+    > {
+        self.sender
+            .get_or_try_init(async || {
+                let listener =
+                    listen_to(&self.pool, C::NAME).await?;
+                // Notifications only wake subscriptions up, so missing
+                // some while lagging behind is harmless.
+                let (sender, _) =
+                    tokio::sync::broadcast::channel(1);
+
+                tokio::spawn(fan_out_notifications(
+                    listener,
+                    sender.clone(),
+                ));
+
+                Ok(sender)
+            })
+            .await
+            .map(tokio::sync::broadcast::Sender::subscribe)
+    }
+}
+
+/// The listener reconnects on its own, losing the notifications sent in the
+/// meantime, which subscriptions poll for.
+async fn fan_out_notifications(
+    mut listener: sqlx::postgres::PgListener,
+    sender: tokio::sync::broadcast::Sender<()>,
+) {
+    loop {
+        match listener.recv().await {
+            // Fails only while nobody is subscribed.
+            Ok(_) => drop(sender.send(())),
+            Err(e) => {
+                tracing::warn!(
+                    error.cause_chain = ?e,
+                    "Failed to receive notification, reconnecting."
+                );
+                tokio::time::sleep(
+                    std::time::Duration::from_secs(1),
+                )
+                .await;
+            }
+        }
+    }
+}
+
+pub struct PgNotificationSubscription(
+    tokio::sync::broadcast::Receiver<()>,
+);
+
+impl<C: Channel> NotificationListener<C>
+    for PgNotificationListener<C>
+{
+    type Subscription = PgNotificationSubscription;
+
+    async fn listen(
+        &self,
+    ) -> Result<PgNotificationSubscription, ListenError>
+    {
+        self.subscribe()
+            .await
+            .map(PgNotificationSubscription)
+            .map_err(ListenError::from)
+    }
+}
+
+impl NotificationSubscription
+    for PgNotificationSubscription
+{
+    async fn wait_for_notification(
+        &mut self,
+        timeout: std::time::Duration,
+    ) -> Result<(), WaitForNotificationError> {
+        use tokio::sync::broadcast::error::RecvError;
+
+        // Timing out is how polling falls back.
+        match tokio::time::timeout(timeout, self.0.recv())
+            .await
+        {
+            Ok(Ok(()) | Err(RecvError::Lagged(_)))
+            | Err(_) => Ok(()),
+            Ok(Err(e @ RecvError::Closed)) => {
+                Err(eyre::Report::new(e).into())
+            }
+        }
+    }
+}
+
+fn parse_issue_delivery_status(
+    delivery_status: &str,
+) -> Result<IssueDeliveryStatus, eyre::Report> {
+    match delivery_status {
+        "active" => Ok(IssueDeliveryStatus::Active),
+        "paused" => Ok(IssueDeliveryStatus::Paused),
+        "cancelled" => Ok(IssueDeliveryStatus::Cancelled),
+        status => Err(eyre::eyre!(
+            "Unknown issue delivery status '{status}'."
+        )),
+    }
+}
+
+fn parse_email_delivery_status(
+    status: &str,
+) -> Result<EmailDeliveryStatus, eyre::Report> {
+    match status {
+        "sent" => Ok(EmailDeliveryStatus::Sent),
+        "failed" => Ok(EmailDeliveryStatus::Failed),
+        "unknown" => Ok(EmailDeliveryStatus::Unknown),
+        status => Err(eyre::eyre!(
+            "Unknown email delivery status '{status}'."
+        )),
+    }
+}
+
+impl<D: PgPoolDependencies> AuthenticationRepository
+    for PgPool<D>
+{
+    async fn get_hashed_credentials_from_username(
+        &self,
+        username: &str,
+    ) -> Result<
+        Option<HashedCredentials>,
+        GetHashedCredentialsError,
+    > {
+        sqlx::query_as!(
+            HashedCredentials,
+            "--sql
+            SELECT user_id, username, salted_password
+            FROM newsletter_writers
+            WHERE username = $1",
+            &username
+        )
+        .fetch_optional(&self.0)
+        .await
+        .map_err(|e| {
+            GetHashedCredentialsError::Unexpected(
+                e.pipe(eyre::Report::new),
+            )
+        })
+    }
+
+    async fn get_hashed_credentials_from_user_id(
+        &self,
+        user_id: Uuid,
+    ) -> Result<
+        Option<HashedCredentials>,
+        GetHashedCredentialsError,
+    > {
+        sqlx::query_as!(
+            HashedCredentials,
+            "--sql
+            SELECT user_id, username, salted_password
+            FROM newsletter_writers
+            WHERE user_id = $1",
+            &user_id
+        )
+        .fetch_optional(&self.0)
+        .await
+        .map_err(|e| {
+            GetHashedCredentialsError::Unexpected(
+                e.pipe(eyre::Report::new),
+            )
+        })?
+        .pipe(Ok)
+    }
+
+    async fn get_email_from_user_id(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Option<String>, GetHashedCredentialsError>
+    {
+        sqlx::query!(
+            "--sql
+            SELECT email
+            FROM newsletter_writers
+            WHERE user_id = $1",
+            &user_id
+        )
+        .fetch_optional(&self.0)
+        .await
+        .map_err(|e| {
+            GetHashedCredentialsError::Unexpected(
+                e.pipe(eyre::Report::new),
+            )
+        })?
+        .and_then(|r| r.email)
+        .pipe(Ok)
+    }
+
+    async fn update_email(
+        &self,
+        user_id: Uuid,
+        email: Option<&str>,
+    ) -> Result<(), UpdateEmailError> {
+        let result = sqlx::query!(
+            "--sql
+            UPDATE newsletter_writers
+            SET email = $1
+            WHERE user_id = $2
+            ",
+            email,
+            &user_id
+        )
+        .execute(&self.0)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        if result.rows_affected() == 0 {
+            return Err(UpdateEmailError::UserNotFound(
+                user_id.to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn update_password(
+        &self,
+        user_id: Uuid,
+        new_salted_password: &SecretString,
+    ) -> Result<(), UpdatePasswordError> {
+        sqlx::query!(
+            "--sql
+        UPDATE newsletter_writers SET
+        salted_password = $1
+        WHERE user_id = $2
+        ",
+            &new_salted_password.expose_secret(),
+            &user_id
+        )
+        .execute(&self.0)
+        .await
+        .map_err(|e| {
+            UpdatePasswordError::Unexpected(
+                e.pipe(eyre::Report::new),
+            )
+        })?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, sqlx::Type)]
+#[sqlx(type_name = "header_pair")]
+pub struct SqlxHeaderPairRecord {
+    name: String,
+    value: Vec<u8>,
+}
+
+impl<D: PgRepositoryDependencies> PersistenceRepository
+    for PgRepository<D>
+{
+    async fn get_saved_response_body<'a>(
+        &self,
+        unit_of_work: &'a mut Self::UnitOfWork,
+        idempotency_key: &'a IdempotencyKey<'a>,
+        user_id: Uuid,
+    ) -> Result<
+        Option<SavedResponseBody>,
+        GetSavedResponseBodyError<'a>,
+    > {
+        sqlx::query!(
+            r#"--sql
+            SELECT
+            response_status_code,
+            response_headers as "response_headers: Vec<SqlxHeaderPairRecord>",
+            response_body
             FROM idempotency
             WHERE
             user_id = $1 AND
@@ -465,11 +1256,12 @@ impl<D: PgRepositoryDependencies> PersistenceRepository
     }
 }
 
-impl<D: PgPoolDependencies> SubscriptionsConfirmRepository
-    for PgPool<D>
+impl<D: PgRepositoryDependencies>
+    SubscriptionsConfirmRepository for PgRepository<D>
 {
     async fn update_status_of_subscriber_id_to_confirmed(
         &self,
+        unit_of_work: &mut Self::UnitOfWork,
         subscriber_id: Uuid,
     ) -> Result<
         (),
@@ -478,11 +1270,16 @@ impl<D: PgPoolDependencies> SubscriptionsConfirmRepository
         sqlx::query!(
             "--sql
             UPDATE subscriptions
-            SET status = 'confirmed'
+            SET status = CASE
+                    WHEN status IN ('bounced', 'complained') THEN status
+                    ELSE 'confirmed'
+                END,
+                confirmed_at = $2
             WHERE id = $1",
-            &subscriber_id
+            &subscriber_id,
+            self.clock.now()
         )
-        .execute(&self.0)
+        .execute(&mut **unit_of_work)
         .await
         .pipe(|i| {
             use UpdateConfirmationStatusOfSubscriberIdError as E;
@@ -500,25 +1297,52 @@ impl<D: PgPoolDependencies> SubscriptionsConfirmRepository
         })
     }
 
-    async fn get_subscriber_id_of_confirmation_token(
+    async fn consume_confirmation_token(
         &self,
-        subscription_token: Uuid,
-    ) -> Result<Uuid, GetSubscriberIdOfConfirmationTokenError>
-    {
-        sqlx::query!(
+        unit_of_work: &mut Self::UnitOfWork,
+        subscription_token: &SubscriptionToken,
+        time_to_live: std::time::Duration,
+    ) -> Result<Uuid, ConsumeConfirmationTokenError> {
+        let token_hash = subscription_token.hash();
+
+        let record = sqlx::query!(
             "--sql
-            SELECT subscriber_id
+            SELECT subscriber_id, created_at
             FROM subscription_tokens
-            WHERE id = $1",
-            &subscription_token
+            WHERE token_hash = $1
+            FOR UPDATE",
+            &token_hash
         )
-        .fetch_one(&self.0)
+        .fetch_optional(&mut **unit_of_work)
         .await
-        .map_err(|e| match e {
-            sqlx::Error::RowNotFound => GetSubscriberIdOfConfirmationTokenError::TokenNotFound { subscription_token },
-            _ => GetSubscriberIdOfConfirmationTokenError::Unexpected(e.pipe(eyre::Report::new)),
-        })
-        .map(|r| r.subscriber_id)
+        .map_err(eyre::Report::new)?
+        .ok_or(
+            ConsumeConfirmationTokenError::TokenNotFound,
+        )?;
+
+        let time_to_live =
+            chrono::Duration::from_std(time_to_live)
+                .map_err(eyre::Report::new)?;
+
+        if record.created_at + time_to_live
+            < self.clock.now()
+        {
+            return Err(
+                ConsumeConfirmationTokenError::TokenExpired,
+            );
+        }
+
+        sqlx::query!(
+            "--sql
+            DELETE FROM subscription_tokens
+            WHERE token_hash = $1",
+            &token_hash
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        Ok(record.subscriber_id)
     }
 }
 
@@ -534,7 +1358,7 @@ impl<D: PgRepositoryDependencies> NewslettersRepository
         sqlx::query_as!(
             NewsletterContent,
             "--sql
-            SELECT title, text_content, html_content
+            SELECT title, text_content, html_content, is_private
             FROM newsletter_issues
             WHERE newsletter_issue_id = $1
             ",
@@ -558,9 +1382,15 @@ impl<D: PgRepositoryDependencies> NewslettersRepository
         title: &str,
         text_content: &str,
         html_content: &str,
+        markdown_content: Option<&str>,
+        scheduled_for: Option<DateTime<Utc>>,
     ) -> Result<Uuid, InsertNewsletterIssueError> {
         let newsletter_issue_id =
             self.uuid_generator.generate_uuid();
+        let now = self.clock.now();
+        // Times already passed are sent right away.
+        let scheduled_for =
+            scheduled_for.filter(|i| *i > now);
         sqlx::query!(
             "--sql
             INSERT INTO newsletter_issues (
@@ -568,15 +1398,21 @@ impl<D: PgRepositoryDependencies> NewslettersRepository
                 title,
                 text_content,
                 html_content,
-                published_at
+                markdown_content,
+                published_at,
+                scheduled_for,
+                updated_at
             )
-            VALUES ($1, $2, $3, $4, $5)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
             ",
             newsletter_issue_id,
             title,
             text_content,
             html_content,
-            self.clock.now(),
+            markdown_content,
+            scheduled_for.unwrap_or(now),
+            scheduled_for,
+            now,
         )
         .execute(&mut **unit_of_work)
         .await
@@ -584,34 +1420,502 @@ impl<D: PgRepositoryDependencies> NewslettersRepository
 
         Ok(newsletter_issue_id)
     }
-}
+
+    async fn insert_newsletter_draft(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        title: &str,
+        text_content: &str,
+        html_content: &str,
+        markdown_content: Option<&str>,
+    ) -> Result<Uuid, InsertNewsletterIssueError> {
+        let newsletter_issue_id =
+            self.uuid_generator.generate_uuid();
+        sqlx::query!(
+            "--sql
+            INSERT INTO newsletter_issues (
+                newsletter_issue_id,
+                title,
+                text_content,
+                html_content,
+                markdown_content,
+                status,
+                updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, 'draft', $6)
+            ",
+            newsletter_issue_id,
+            title,
+            text_content,
+            html_content,
+            markdown_content,
+            self.clock.now(),
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        Ok(newsletter_issue_id)
+    }
+
+    async fn update_newsletter_draft(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        newsletter_issue_id: Uuid,
+        title: &str,
+        text_content: &str,
+        html_content: &str,
+        markdown_content: Option<&str>,
+    ) -> Result<(), NewsletterDraftError> {
+        let result = sqlx::query!(
+            "--sql
+            UPDATE newsletter_issues
+            SET title = $2,
+                text_content = $3,
+                html_content = $4,
+                markdown_content = $5,
+                updated_at = $6
+            WHERE newsletter_issue_id = $1
+            AND status = 'draft'
+            ",
+            newsletter_issue_id,
+            title,
+            text_content,
+            html_content,
+            markdown_content,
+            self.clock.now(),
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        if result.rows_affected() == 0 {
+            return Err(NewsletterDraftError::NotFound(
+                newsletter_issue_id,
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn get_newsletter_drafts(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+    ) -> Result<
+        Vec<NewsletterDraftSummary>,
+        GetNewsletterDraftsError,
+    > {
+        sqlx::query_as!(
+            NewsletterDraftSummary,
+            "--sql
+            SELECT newsletter_issue_id, title, updated_at
+            FROM newsletter_issues
+            WHERE status = 'draft'
+            ORDER BY updated_at DESC
+            "
+        )
+        .fetch_all(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?
+        .pipe(Ok)
+    }
+
+    async fn get_newsletter_draft(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        newsletter_issue_id: Uuid,
+    ) -> Result<NewsletterDraft, NewsletterDraftError> {
+        sqlx::query_as!(
+            NewsletterDraft,
+            "--sql
+            SELECT newsletter_issue_id,
+                title,
+                text_content,
+                html_content,
+                markdown_content
+            FROM newsletter_issues
+            WHERE newsletter_issue_id = $1
+            AND status = 'draft'
+            ",
+            newsletter_issue_id
+        )
+        .fetch_optional(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?
+        .ok_or(NewsletterDraftError::NotFound(
+            newsletter_issue_id,
+        ))
+    }
+
+    async fn delete_newsletter_draft(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        newsletter_issue_id: Uuid,
+    ) -> Result<(), NewsletterDraftError> {
+        let result = sqlx::query!(
+            "--sql
+            DELETE FROM newsletter_issues
+            WHERE newsletter_issue_id = $1
+            AND status = 'draft'
+            ",
+            newsletter_issue_id
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        if result.rows_affected() == 0 {
+            return Err(NewsletterDraftError::NotFound(
+                newsletter_issue_id,
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn publish_newsletter_draft(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        newsletter_issue_id: Uuid,
+        scheduled_for: Option<DateTime<Utc>>,
+    ) -> Result<(), NewsletterDraftError> {
+        let now = self.clock.now();
+        // Times already passed are sent right away.
+        let scheduled_for =
+            scheduled_for.filter(|i| *i > now);
+        let result = sqlx::query!(
+            "--sql
+            UPDATE newsletter_issues
+            SET status = 'published',
+                published_at = $2,
+                scheduled_for = $3,
+                updated_at = $4
+            WHERE newsletter_issue_id = $1
+            AND status = 'draft'
+            ",
+            newsletter_issue_id,
+            scheduled_for.unwrap_or(now),
+            scheduled_for,
+            now,
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        if result.rows_affected() == 0 {
+            return Err(NewsletterDraftError::NotFound(
+                newsletter_issue_id,
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn get_scheduled_newsletter_issues(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+    ) -> Result<
+        Vec<ScheduledNewsletterIssue>,
+        GetScheduledNewsletterIssuesError,
+    > {
+        sqlx::query_as!(
+            ScheduledNewsletterIssue,
+            r#"--sql
+            SELECT newsletter_issue_id,
+                title,
+                scheduled_for as "scheduled_for!"
+            FROM newsletter_issues
+            WHERE status = 'published'
+            AND scheduled_for > $1
+            ORDER BY scheduled_for
+            "#,
+            self.clock.now()
+        )
+        .fetch_all(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?
+        .pipe(Ok)
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn cancel_scheduled_newsletter_issue(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        newsletter_issue_id: Uuid,
+    ) -> Result<(), CancelScheduledNewsletterIssueError>
+    {
+        let scheduled = sqlx::query!(
+            "--sql
+            SELECT newsletter_issue_id
+            FROM newsletter_issues
+            WHERE newsletter_issue_id = $1
+            AND status = 'published'
+            AND scheduled_for > $2
+            FOR UPDATE
+            ",
+            newsletter_issue_id,
+            self.clock.now()
+        )
+        .fetch_optional(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        if scheduled.is_none() {
+            return Err(
+                CancelScheduledNewsletterIssueError::NotFound(
+                    newsletter_issue_id,
+                ),
+            );
+        }
+
+        sqlx::query!(
+            "--sql
+            UPDATE issue_delivery_queue
+            SET cancelled = TRUE
+            WHERE newsletter_issue_id = $1
+            ",
+            newsletter_issue_id
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        sqlx::query!(
+            "--sql
+            UPDATE newsletter_issues
+            SET status = 'cancelled',
+                delivery_status = 'cancelled',
+                updated_at = $2
+            WHERE newsletter_issue_id = $1
+            ",
+            newsletter_issue_id,
+            self.clock.now()
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        Ok(())
+    }
+
+    async fn get_published_newsletter_issues(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+    ) -> Result<
+        Vec<PublishedNewsletterIssue>,
+        GetPublishedNewsletterIssuesError,
+    > {
+        sqlx::query_as!(
+            PublishedNewsletterIssue,
+            r#"--sql
+            SELECT newsletter_issue_id,
+                title,
+                published_at as "published_at!",
+                is_private
+            FROM newsletter_issues
+            WHERE status = 'published'
+            AND published_at <= $1
+            ORDER BY published_at DESC
+            "#,
+            self.clock.now()
+        )
+        .fetch_all(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?
+        .pipe(Ok)
+    }
+
+    async fn get_public_newsletter_issues(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        limit: usize,
+        offset: usize,
+    ) -> Result<
+        Vec<PublicNewsletterIssue>,
+        GetPublishedNewsletterIssuesError,
+    > {
+        sqlx::query_as!(
+            PublicNewsletterIssue,
+            r#"--sql
+            SELECT newsletter_issue_id,
+                title,
+                text_content,
+                html_content,
+                published_at as "published_at!"
+            FROM newsletter_issues
+            WHERE status = 'published'
+            AND NOT is_private
+            AND published_at <= $1
+            ORDER BY published_at DESC, newsletter_issue_id
+            LIMIT $2
+            OFFSET $3
+            "#,
+            self.clock.now(),
+            i64::try_from(limit).unwrap_or(i64::MAX),
+            i64::try_from(offset).unwrap_or(i64::MAX),
+        )
+        .fetch_all(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?
+        .pipe(Ok)
+    }
+
+    async fn get_public_newsletter_issue(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        newsletter_issue_id: Uuid,
+    ) -> Result<
+        PublicNewsletterIssue,
+        PublicNewsletterIssueError,
+    > {
+        sqlx::query_as!(
+            PublicNewsletterIssue,
+            r#"--sql
+            SELECT newsletter_issue_id,
+                title,
+                text_content,
+                html_content,
+                published_at as "published_at!"
+            FROM newsletter_issues
+            WHERE newsletter_issue_id = $1
+            AND status = 'published'
+            AND NOT is_private
+            AND published_at <= $2
+            "#,
+            newsletter_issue_id,
+            self.clock.now()
+        )
+        .fetch_optional(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?
+        .ok_or(
+            PublicNewsletterIssueError::NotFound(
+                newsletter_issue_id,
+            ),
+        )
+    }
+
+    async fn set_newsletter_issue_private(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        newsletter_issue_id: Uuid,
+        is_private: bool,
+    ) -> Result<(), PublicNewsletterIssueError> {
+        let result = sqlx::query!(
+            "--sql
+            UPDATE newsletter_issues
+            SET is_private = $2
+            WHERE newsletter_issue_id = $1
+            AND status = 'published'
+            ",
+            newsletter_issue_id,
+            is_private
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        if result.rows_affected() == 0 {
+            return Err(
+                PublicNewsletterIssueError::NotFound(
+                    newsletter_issue_id,
+                ),
+            );
+        }
+
+        Ok(())
+    }
+}
 
 impl<D: PgRepositoryDependencies> SubscriptionsRepository
     for PgRepository<D>
 {
-    async fn insert_subscriber<
+    #[tracing::instrument(skip_all)]
+    async fn upsert_subscriber<
         P: SharedPointerHKT + SendHKT + SyncHKT,
     >(
         &self,
         unit_of_work: &mut Self::UnitOfWork,
         form: &NewSubscriber<P>,
-    ) -> Result<Uuid, InsertSubscriberError> {
-        let subscriber_id =
+    ) -> Result<UpsertedSubscriber, InsertSubscriberError>
+    {
+        let new_subscriber_id =
             self.uuid_generator.generate_uuid();
-        sqlx::query!(
+        let now = self.clock.now();
+
+        // The update locks the existing row & makes it RETURNed.
+        // Suppressed addresses get the reason as status, existing or not.
+        let record = sqlx::query!(
             r#"--sql
             INSERT INTO subscriptions (id, email, name, subscribed_at, status)
-            VALUES ($1, $2, $3, $4, 'pending_confirmation')
+            VALUES (
+                $1,
+                $2,
+                $3,
+                $4,
+                COALESCE(
+                    (SELECT reason FROM email_suppressions WHERE lower(email) = lower($2)),
+                    'pending_confirmation'
+                )
+            )
+            ON CONFLICT (email) DO UPDATE SET status = COALESCE(
+                (SELECT reason FROM email_suppressions WHERE lower(email) = lower($2)),
+                subscriptions.status
+            )
+            RETURNING id, status
             "#,
-            subscriber_id,
+            new_subscriber_id,
             &*form.email,
             &*form.name,
-            self.clock.now()
+            now
         )
-        .execute(&mut **unit_of_work)
+        .fetch_one(&mut **unit_of_work)
         .await
-        .map_err(eyre::Report::new)
-        .map(|_| subscriber_id)?
+        .map_err(eyre::Report::new)?;
+
+        match record.status.as_str() {
+            "bounced" | "complained" => {
+                UpsertedSubscriber::Suppressed(record.id)
+            }
+            _ if record.id == new_subscriber_id => {
+                UpsertedSubscriber::Inserted(record.id)
+            }
+            "pending_confirmation" => {
+                UpsertedSubscriber::PendingConfirmation(
+                    record.id,
+                )
+            }
+            "confirmed" => {
+                UpsertedSubscriber::Confirmed(record.id)
+            }
+            "unsubscribed" => {
+                sqlx::query!(
+                    r#"--sql
+                    UPDATE subscriptions
+                    SET status = 'pending_confirmation',
+                        subscribed_at = $2,
+                        unsubscribed_at = NULL
+                    WHERE id = $1
+                    AND status NOT IN ('bounced', 'complained')
+                    "#,
+                    record.id,
+                    now
+                )
+                .execute(&mut **unit_of_work)
+                .await
+                .map_err(eyre::Report::new)?;
+
+                UpsertedSubscriber::Resubscribed(record.id)
+            }
+            status => {
+                return eyre::eyre!(
+                    "Unknown subscription status '{status}'."
+                )
+                .pipe(InsertSubscriberError::from)
+                .pipe(Err);
+            }
+        }
         .pipe(Ok)
     }
 
@@ -619,15 +1923,19 @@ impl<D: PgRepositoryDependencies> SubscriptionsRepository
         &self,
         unit_of_work: &mut Self::UnitOfWork,
         subscriber_id: &Uuid,
-    ) -> Result<Uuid, StoreTokenError> {
-        let token = self.uuid_generator.generate_uuid();
+    ) -> Result<SubscriptionToken, StoreTokenError> {
+        let token = self
+            .uuid_generator
+            .generate_uuid()
+            .pipe(SubscriptionToken::new);
         sqlx::query!(
             r#"--sql
-            INSERT INTO subscription_tokens (id, subscriber_id)
-            VALUES ($1, $2)
+            INSERT INTO subscription_tokens (token_hash, subscriber_id, created_at)
+            VALUES ($1, $2, $3)
             "#,
-            token,
+            &token.hash(),
             subscriber_id,
+            self.clock.now()
         )
         .execute(&mut **unit_of_work)
         .await
@@ -635,4 +1943,315 @@ impl<D: PgRepositoryDependencies> SubscriptionsRepository
 
         Ok(token)
     }
+
+    #[tracing::instrument(skip_all)]
+    async fn unsubscribe(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        subscriber_id: Uuid,
+    ) -> Result<(), UnsubscribeError> {
+        let email = sqlx::query!(
+            r#"--sql
+            UPDATE subscriptions
+            SET status = CASE
+                    WHEN status IN ('bounced', 'complained') THEN status
+                    ELSE 'unsubscribed'
+                END,
+                unsubscribed_at = COALESCE(unsubscribed_at, $2)
+            WHERE id = $1
+            RETURNING email
+            "#,
+            subscriber_id,
+            self.clock.now()
+        )
+        .fetch_optional(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?
+        .ok_or(UnsubscribeError::NotFound(subscriber_id))?
+        .email;
+
+        sqlx::query!(
+            r#"--sql
+            DELETE FROM issue_delivery_queue
+            WHERE subscriber_email = $1
+            "#,
+            email
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn record_email_event(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        email_event: &EmailEvent,
+    ) -> Result<(), RecordEmailEventError> {
+        sqlx::query!(
+            r#"--sql
+            INSERT INTO email_events (
+                email_event_id,
+                record_type,
+                email,
+                provider_message_id,
+                bounce_type,
+                description,
+                occurred_at,
+                received_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+            self.uuid_generator.generate_uuid(),
+            email_event.record_type,
+            email_event.email,
+            email_event.provider_message_id,
+            email_event.bounce_type,
+            email_event.description,
+            email_event.occurred_at,
+            self.clock.now(),
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn suppress_email(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        email: &str,
+        reason: SuppressionReason,
+    ) -> Result<(), SuppressEmailError> {
+        let reason = reason.to_string();
+
+        // The first reason is kept, whatever the case of the address.
+        sqlx::query!(
+            r#"--sql
+            INSERT INTO email_suppressions (email, reason, suppressed_at)
+            SELECT $1, $2, $3
+            WHERE NOT EXISTS (
+                SELECT 1 FROM email_suppressions
+                WHERE lower(email) = lower($1)
+            )
+            ON CONFLICT (email) DO NOTHING
+            "#,
+            email,
+            reason,
+            self.clock.now()
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        sqlx::query!(
+            r#"--sql
+            UPDATE subscriptions
+            SET status = $2
+            WHERE lower(email) = lower($1)
+            AND status NOT IN ('bounced', 'complained')
+            "#,
+            email,
+            reason
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        sqlx::query!(
+            r#"--sql
+            DELETE FROM issue_delivery_queue
+            WHERE lower(subscriber_email) = lower($1)
+            "#,
+            email
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        sqlx::query!(
+            r#"--sql
+            DELETE FROM confirmation_email_outbox
+            WHERE lower(subscriber_email) = lower($1)
+            "#,
+            email
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn enqueue_confirmation_email(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        subscriber_id: Uuid,
+        subscriber_email: &str,
+    ) -> Result<(), EnqueueConfirmationEmailError> {
+        let now = self.clock.now();
+        let confirmation_email_id =
+            self.uuid_generator.generate_uuid();
+
+        sqlx::query!(
+            r#"--sql
+            INSERT INTO confirmation_email_outbox (
+                confirmation_email_id,
+                subscriber_id,
+                subscriber_email,
+                execute_after,
+                created_at
+            )
+            VALUES ($1, $2, $3, $4, $4)
+            "#,
+            confirmation_email_id,
+            subscriber_id,
+            subscriber_email,
+            now
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        notify(
+            unit_of_work,
+            ConfirmationEmailOutboxChannel::NAME,
+            &confirmation_email_id.to_string(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Get and uniquely lock a due email in the confirmation email outbox.",
+        skip_all
+    )]
+    async fn acquire_confirmation_email(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+    ) -> Result<
+        Option<ConfirmationEmail>,
+        AcquireConfirmationEmailError,
+    > {
+        sqlx::query_as!(
+            ConfirmationEmail,
+            r#"--sql
+            SELECT confirmation_email_id,
+                subscriber_id,
+                subscriber_email,
+                n_retries
+            FROM confirmation_email_outbox
+            WHERE execute_after <= $1
+            ORDER BY execute_after
+            FOR UPDATE
+            SKIP LOCKED
+            LIMIT 1
+            "#,
+            self.clock.now()
+        )
+        .fetch_optional(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)
+        .map_err(AcquireConfirmationEmailError::from)
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn delete_confirmation_email(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        confirmation_email_id: Uuid,
+    ) -> Result<(), DeleteConfirmationEmailError> {
+        sqlx::query!(
+            r#"--sql
+            DELETE FROM confirmation_email_outbox
+            WHERE confirmation_email_id = $1
+            "#,
+            confirmation_email_id
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn schedule_confirmation_email_retry(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        confirmation_email_id: Uuid,
+        retry_delay: std::time::Duration,
+    ) -> Result<(), ScheduleConfirmationEmailRetryError>
+    {
+        sqlx::query!(
+            r#"--sql
+            UPDATE confirmation_email_outbox
+            SET n_retries = n_retries + 1,
+                execute_after = $2
+            WHERE confirmation_email_id = $1
+            "#,
+            confirmation_email_id,
+            self.clock.now()
+                + chrono::Duration::from_std(retry_delay)
+                    .map_err(eyre::Report::new)?
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn dead_letter_confirmation_email(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        confirmation_email_id: Uuid,
+        last_error: &str,
+    ) -> Result<(), DeadLetterConfirmationEmailError> {
+        sqlx::query!(
+            r#"--sql
+            WITH dead_email AS (
+                DELETE FROM confirmation_email_outbox
+                WHERE confirmation_email_id = $1
+                RETURNING confirmation_email_id,
+                    subscriber_id,
+                    subscriber_email,
+                    n_retries,
+                    created_at
+            )
+            INSERT INTO confirmation_email_dead_letters (
+                confirmation_email_id,
+                subscriber_id,
+                subscriber_email,
+                n_attempts,
+                last_error,
+                created_at,
+                dead_lettered_at
+            )
+            SELECT confirmation_email_id,
+                subscriber_id,
+                subscriber_email,
+                n_retries + 1,
+                $2,
+                created_at,
+                $3
+            FROM dead_email
+            "#,
+            confirmation_email_id,
+            last_error,
+            self.clock.now()
+        )
+        .execute(&mut **unit_of_work)
+        .await
+        .map_err(eyre::Report::new)?;
+
+        Ok(())
+    }
 }
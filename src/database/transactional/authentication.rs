@@ -30,6 +30,24 @@ pub trait AuthenticationRepository: SendSyncStatic {
         >,
     > + Send;
 
+    /// Address writers receive test copies of issues at, if they set one.
+    fn get_email_from_user_id(
+        &self,
+        user_id: Uuid,
+    ) -> impl Future<
+        Output = Result<
+            Option<String>,
+            GetHashedCredentialsError,
+        >,
+    > + Send;
+
+    /// Clears the address when `None`.
+    fn update_email(
+        &self,
+        user_id: Uuid,
+        email: Option<&str>,
+    ) -> impl Future<Output = Result<(), UpdateEmailError>> + Send;
+
     fn update_password(
         &self,
         user_id: Uuid,
@@ -45,6 +63,14 @@ pub enum GetHashedCredentialsError {
     Unexpected(#[from] eyre::Report),
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum UpdateEmailError {
+    #[error("User not found with ID: {0}")]
+    UserNotFound(String),
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum UpdatePasswordError {
     #[error("User not found with ID: {0}")]
@@ -1,3 +1,6 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use crate::{
@@ -8,6 +11,8 @@ use crate::{
 pub trait IssueDeliveryQueueRepository:
     UnitOfWorkRepository
 {
+    /// Recipients are the confirmed subscribers as of now, even for a
+    /// scheduled issue: those subscribing before it is sent do not get it.
     fn enqueue_delivery_tasks(
         &self,
         unit_of_work: &mut Self::UnitOfWork,
@@ -19,28 +24,87 @@ pub trait IssueDeliveryQueueRepository:
         >,
     > + Send;
 
+    /// Only the task of the given recipient is delayed, from now on.
     fn schedule_task_retry(
         &self,
         unit_of_work: &mut Self::UnitOfWork,
         record: &IssueDeliveryRecord,
+        retry_delay: Duration,
     ) -> impl Future<
         Output = Result<(), ScheduleTaskRetryError>,
     > + Send;
 
+    /// Moves a task out of the queue once it ran out of attempts.
+    fn dead_letter_task(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        record: &IssueDeliveryRecord,
+        last_error: &str,
+    ) -> impl Future<
+        Output = Result<(), DeadLetterTaskError>,
+    > + Send;
+
+    /// Dead letters of every issue, newest first.
+    fn get_dead_letters(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+    ) -> impl Future<
+        Output = Result<
+            Vec<DeadLetter>,
+            GetDeadLettersError,
+        >,
+    > + Send;
+
+    /// Puts a dead letter back in the queue with a fresh count of attempts.
+    fn requeue_dead_letter(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        newsletter_issue_id: Uuid,
+        subscriber_email: &str,
+    ) -> impl Future<
+        Output = Result<(), RequeueDeadLetterError>,
+    > + Send;
+
+    /// Appends an attempt at sending to a recipient to the delivery log.
+    fn record_email_delivery(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        email_delivery: &NewEmailDelivery,
+    ) -> impl Future<
+        Output = Result<(), RecordEmailDeliveryError>,
+    > + Send;
+
+    /// Up to `limit` logged attempts matching every given filter, newest
+    /// first, skipping the first `offset` of them.
+    fn get_email_deliveries(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        filter: &EmailDeliveryFilter,
+        limit: usize,
+        offset: usize,
+    ) -> impl Future<
+        Output = Result<
+            Vec<EmailDelivery>,
+            GetEmailDeliveriesError,
+        >,
+    > + Send;
+
     fn disable_task(
         &self,
         unit_of_work: &mut Self::UnitOfWork,
         record: &IssueDeliveryRecord,
     ) -> impl Future<Output = Result<(), DisableTaskError>> + Send;
 
-    fn acquire_newsletter_task_from_issue(
+    /// Locks up to `limit` tasks of an issue, to be sent as a batch.
+    fn acquire_newsletter_tasks_from_issue(
         &self,
         unit_of_work: &mut Self::UnitOfWork,
         newsletter_issue_id: Uuid,
+        limit: usize,
     ) -> impl Future<
         Output = Result<
-            Option<IssueDeliveryRecord>,
-            AcquireNewsletterTaskFromIssueError,
+            Vec<IssueDeliveryRecord>,
+            AcquireNewsletterTasksFromIssueError,
         >,
     > + Send;
 
@@ -61,6 +125,193 @@ pub trait IssueDeliveryQueueRepository:
     ) -> impl Future<
         Output = Result<(), FinalizeNewsletterTaskError>,
     > + Send;
+
+    /// Progress of every published issue, newest first.
+    fn get_delivery_progress(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+    ) -> impl Future<
+        Output = Result<
+            Vec<IssueDeliveryProgress>,
+            GetDeliveryProgressError,
+        >,
+    > + Send;
+
+    /// Stops the worker from picking tasks of an issue until resumed.
+    fn pause_issue_delivery(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        newsletter_issue_id: Uuid,
+    ) -> impl Future<
+        Output = Result<(), UpdateIssueDeliveryStatusError>,
+    > + Send;
+
+    fn resume_issue_delivery(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        newsletter_issue_id: Uuid,
+    ) -> impl Future<
+        Output = Result<(), UpdateIssueDeliveryStatusError>,
+    > + Send;
+
+    /// Marks the remaining tasks of an issue as cancelled, for good.
+    fn cancel_issue_delivery(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        newsletter_issue_id: Uuid,
+    ) -> impl Future<
+        Output = Result<(), UpdateIssueDeliveryStatusError>,
+    > + Send;
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    derive_more::Display,
+    serde::Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueDeliveryStatus {
+    #[display("active")]
+    Active,
+    #[display("paused")]
+    Paused,
+    #[display("cancelled")]
+    Cancelled,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct IssueDeliveryProgress {
+    pub newsletter_issue_id: Uuid,
+    pub delivery_status: IssueDeliveryStatus,
+    pub total_recipients: i64,
+    /// Not attempted yet.
+    pub pending: i64,
+    /// Failed at least once, still to be retried.
+    pub retrying: i64,
+    /// Rejected for good by the email provider.
+    pub failed: i64,
+    /// Never sent, e.g. to an invalid address.
+    pub skipped: i64,
+    pub cancelled: i64,
+    /// Ran out of attempts.
+    pub dead_lettered: i64,
+    /// Accepted by the email provider, as logged by the worker.
+    pub delivered: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct DeadLetter {
+    pub newsletter_issue_id: Uuid,
+    pub title: String,
+    pub subscriber_email: String,
+    pub n_attempts: i32,
+    pub last_error: String,
+    pub dead_lettered_at: DateTime<Utc>,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    derive_more::Display,
+    serde::Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum EmailDeliveryStatus {
+    #[display("sent")]
+    Sent,
+    #[display("failed")]
+    Failed,
+    /// Missing from the results of an accepted batch, so possibly sent.
+    #[display("unknown")]
+    Unknown,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewEmailDelivery {
+    pub newsletter_issue_id: Uuid,
+    pub subscriber_email: String,
+    /// Starts at 1 for the first attempt.
+    pub attempt: i32,
+    pub status: EmailDeliveryStatus,
+    pub http_status: Option<u16>,
+    pub provider_message_id: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct EmailDelivery {
+    pub newsletter_issue_id: Uuid,
+    pub title: String,
+    pub subscriber_email: String,
+    pub attempt: i32,
+    pub status: EmailDeliveryStatus,
+    pub http_status: Option<i32>,
+    pub provider_message_id: Option<String>,
+    pub error: Option<String>,
+    pub attempted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct EmailDeliveryFilter {
+    pub newsletter_issue_id: Option<Uuid>,
+    pub subscriber_email: Option<String>,
+}
+
+impl EmailDeliveryFilter {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.newsletter_issue_id.is_none()
+            && self.subscriber_email.is_none()
+    }
+}
+
+impl IssueDeliveryProgress {
+    /// Issues enqueued before recipients were counted report the recipients
+    /// accounted for.
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        newsletter_issue_id: Uuid,
+        delivery_status: IssueDeliveryStatus,
+        n_recipients: Option<i64>,
+        pending: i64,
+        retrying: i64,
+        failed: i64,
+        skipped: i64,
+        cancelled: i64,
+        dead_lettered: i64,
+        delivered: i64,
+    ) -> Self {
+        let total_recipients = n_recipients.unwrap_or(
+            pending
+                + retrying
+                + failed
+                + skipped
+                + cancelled
+                + dead_lettered
+                + delivered,
+        );
+
+        Self {
+            newsletter_issue_id,
+            delivery_status,
+            total_recipients,
+            pending,
+            retrying,
+            failed,
+            skipped,
+            cancelled,
+            dead_lettered,
+            delivered,
+        }
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -69,6 +320,44 @@ pub enum ScheduleTaskRetryError {
     Unexpected(#[from] eyre::Report),
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum DeadLetterTaskError {
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GetDeadLettersError {
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RequeueDeadLetterError {
+    #[error(
+        "No dead letter of newsletter issue with uuid '{0}' found for '{1}'."
+    )]
+    NotFound(Uuid, String),
+    #[error(
+        "Newsletter issue with uuid '{0}' is already queued for '{1}'."
+    )]
+    AlreadyQueued(Uuid, String),
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RecordEmailDeliveryError {
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GetEmailDeliveriesError {
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum DisableTaskError {
     #[error(transparent)]
@@ -76,7 +365,7 @@ pub enum DisableTaskError {
 }
 
 #[derive(Debug, thiserror::Error)]
-pub enum AcquireNewsletterTaskFromIssueError {
+pub enum AcquireNewsletterTasksFromIssueError {
     #[error(transparent)]
     Unexpected(#[from] eyre::Report),
 }
@@ -93,6 +382,22 @@ pub enum FinalizeNewsletterTaskError {
     Unexpected(#[from] eyre::Report),
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum GetDeliveryProgressError {
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UpdateIssueDeliveryStatusError {
+    #[error(
+        "No published newsletter with uuid '{0}' found whose delivery can be changed this way."
+    )]
+    NotFound(Uuid),
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
+
 #[derive(Debug, derive_more::Display)]
 pub enum EnqueueDeliveryTaskResult {
     Enqueued,
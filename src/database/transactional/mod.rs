@@ -1,6 +1,7 @@
 pub mod authentication;
 pub mod issue_delivery_queue;
 pub mod newsletters;
+pub mod notifications;
 pub mod persistence;
 pub mod subscriptions;
 pub mod subscriptions_confirm;
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use crate::database::transactional::unit_of_work::UnitOfWorkRepository;
@@ -22,15 +23,185 @@ pub trait NewslettersRepository:
         title: &str,
         text_content: &str,
         html_content: &str,
+        markdown_content: Option<&str>,
+        scheduled_for: Option<DateTime<Utc>>,
     ) -> impl std::future::Future<
         Output = Result<Uuid, InsertNewsletterIssueError>,
     > + Send;
+
+    fn insert_newsletter_draft(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        title: &str,
+        text_content: &str,
+        html_content: &str,
+        markdown_content: Option<&str>,
+    ) -> impl Future<
+        Output = Result<Uuid, InsertNewsletterIssueError>,
+    > + Send;
+
+    fn update_newsletter_draft(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        newsletter_issue_id: Uuid,
+        title: &str,
+        text_content: &str,
+        html_content: &str,
+        markdown_content: Option<&str>,
+    ) -> impl Future<
+        Output = Result<(), NewsletterDraftError>,
+    > + Send;
+
+    fn get_newsletter_drafts(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+    ) -> impl Future<
+        Output = Result<
+            Vec<NewsletterDraftSummary>,
+            GetNewsletterDraftsError,
+        >,
+    > + Send;
+
+    fn get_newsletter_draft(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        newsletter_issue_id: Uuid,
+    ) -> impl Future<
+        Output = Result<
+            NewsletterDraft,
+            NewsletterDraftError,
+        >,
+    > + Send;
+
+    fn delete_newsletter_draft(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        newsletter_issue_id: Uuid,
+    ) -> impl Future<
+        Output = Result<(), NewsletterDraftError>,
+    > + Send;
+
+    /// Turns a draft into an issue, same as `insert_newsletter_issue`.
+    fn publish_newsletter_draft(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        newsletter_issue_id: Uuid,
+        scheduled_for: Option<DateTime<Utc>>,
+    ) -> impl Future<
+        Output = Result<(), NewsletterDraftError>,
+    > + Send;
+
+    /// Issues whose scheduled time has not been reached yet.
+    fn get_scheduled_newsletter_issues(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+    ) -> impl Future<
+        Output = Result<
+            Vec<ScheduledNewsletterIssue>,
+            GetScheduledNewsletterIssuesError,
+        >,
+    > + Send;
+
+    /// Cancels a scheduled issue & its pending deliveries before it fires,
+    /// keeping both for the record.
+    fn cancel_scheduled_newsletter_issue(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        newsletter_issue_id: Uuid,
+    ) -> impl Future<
+        Output = Result<
+            (),
+            CancelScheduledNewsletterIssueError,
+        >,
+    > + Send;
+
+    /// Issues already sent or being sent, newest first, private ones
+    /// included.
+    fn get_published_newsletter_issues(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+    ) -> impl Future<
+        Output = Result<
+            Vec<PublishedNewsletterIssue>,
+            GetPublishedNewsletterIssuesError,
+        >,
+    > + Send;
+
+    /// Up to `limit` published issues that are not private, newest first,
+    /// skipping the first `offset` of them.
+    fn get_public_newsletter_issues(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        limit: usize,
+        offset: usize,
+    ) -> impl Future<
+        Output = Result<
+            Vec<PublicNewsletterIssue>,
+            GetPublishedNewsletterIssuesError,
+        >,
+    > + Send;
+
+    fn get_public_newsletter_issue(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        newsletter_issue_id: Uuid,
+    ) -> impl Future<
+        Output = Result<
+            PublicNewsletterIssue,
+            PublicNewsletterIssueError,
+        >,
+    > + Send;
+
+    fn set_newsletter_issue_private(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        newsletter_issue_id: Uuid,
+        is_private: bool,
+    ) -> impl Future<
+        Output = Result<(), PublicNewsletterIssueError>,
+    > + Send;
+}
+
+pub struct PublishedNewsletterIssue {
+    pub newsletter_issue_id: Uuid,
+    pub title: String,
+    pub published_at: DateTime<Utc>,
+    pub is_private: bool,
+}
+
+pub struct PublicNewsletterIssue {
+    pub newsletter_issue_id: Uuid,
+    pub title: String,
+    pub text_content: String,
+    pub html_content: String,
+    pub published_at: DateTime<Utc>,
+}
+
+pub struct NewsletterDraftSummary {
+    pub newsletter_issue_id: Uuid,
+    pub title: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+pub struct NewsletterDraft {
+    pub newsletter_issue_id: Uuid,
+    pub title: String,
+    pub text_content: String,
+    pub html_content: String,
+    pub markdown_content: Option<String>,
+}
+
+pub struct ScheduledNewsletterIssue {
+    pub newsletter_issue_id: Uuid,
+    pub title: String,
+    pub scheduled_for: DateTime<Utc>,
 }
 
 pub struct NewsletterContent {
     pub title: String,
     pub text_content: String,
     pub html_content: String,
+    pub is_private: bool,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -46,3 +217,49 @@ pub enum InsertNewsletterIssueError {
     #[error(transparent)]
     Unexpected(#[from] eyre::Report),
 }
+
+#[derive(Debug, thiserror::Error)]
+pub enum GetScheduledNewsletterIssuesError {
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CancelScheduledNewsletterIssueError {
+    #[error(
+        "No scheduled newsletter with uuid '{0}' waiting to be sent."
+    )]
+    NotFound(Uuid),
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GetPublishedNewsletterIssuesError {
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PublicNewsletterIssueError {
+    #[error(
+        "No published newsletter with uuid '{0}' found."
+    )]
+    NotFound(Uuid),
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GetNewsletterDraftsError {
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum NewsletterDraftError {
+    #[error("No newsletter draft with uuid '{0}' found.")]
+    NotFound(Uuid),
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use crate::dependency_injection::app_state::SendSyncStatic;
+
+/// What units of work notify listeners of once they commit.
+pub trait Channel: SendSyncStatic {
+    const NAME: &'static str;
+}
+
+/// Delivery tasks became available in the issue delivery queue.
+pub struct IssueDeliveryQueueChannel;
+
+impl Channel for IssueDeliveryQueueChannel {
+    const NAME: &'static str = "issue_delivery_queue";
+}
+
+/// Emails were written to the confirmation email outbox.
+pub struct ConfirmationEmailOutboxChannel;
+
+impl Channel for ConfirmationEmailOutboxChannel {
+    const NAME: &'static str = "confirmation_email_outbox";
+}
+
+/// Wakes idle workers as soon as there is work on the channel, instead of
+/// them polling for it.
+pub trait NotificationListener<C: Channel>:
+    Send + Sync
+{
+    type Subscription: NotificationSubscription;
+
+    fn listen(
+        &self,
+    ) -> impl Future<
+        Output = Result<Self::Subscription, ListenError>,
+    > + Send;
+}
+
+pub trait NotificationSubscription: Send {
+    /// Resolves once notified, or after `timeout` since notifications may be
+    /// missed while reconnecting.
+    fn wait_for_notification(
+        &mut self,
+        timeout: Duration,
+    ) -> impl Future<
+        Output = Result<(), WaitForNotificationError>,
+    > + Send;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ListenError {
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WaitForNotificationError {
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
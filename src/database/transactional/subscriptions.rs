@@ -1,22 +1,31 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use crate::{
     database::transactional::unit_of_work::UnitOfWorkRepository,
-    domain::NewSubscriber,
+    domain::{NewSubscriber, SubscriptionToken},
     hkt::{SendHKT, SharedPointerHKT, SyncHKT},
 };
 
 pub trait SubscriptionsRepository:
     UnitOfWorkRepository
 {
-    fn insert_subscriber<
+    /// Inserts a new subscriber or looks up the one already using
+    /// the email. Unsubscribed subscribers go back to pending confirmation,
+    /// suppressed addresses stay suppressed.
+    fn upsert_subscriber<
         P: SharedPointerHKT + SendHKT + SyncHKT,
     >(
         &self,
         unit_of_work: &mut Self::UnitOfWork,
         form: &NewSubscriber<P>,
     ) -> impl std::future::Future<
-        Output = Result<Uuid, InsertSubscriberError>,
+        Output = Result<
+            UpsertedSubscriber,
+            InsertSubscriberError,
+        >,
     > + Send;
 
     fn store_token<P: SharedPointerHKT>(
@@ -24,8 +33,151 @@ pub trait SubscriptionsRepository:
         unit_of_work: &mut Self::UnitOfWork,
         subscriber_id: &Uuid,
     ) -> impl std::future::Future<
-        Output = Result<Uuid, StoreTokenError>,
+        Output = Result<SubscriptionToken, StoreTokenError>,
+    > + Send;
+
+    /// Marks the subscriber as unsubscribed & drops their pending deliveries.
+    fn unsubscribe(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        subscriber_id: Uuid,
+    ) -> impl std::future::Future<
+        Output = Result<(), UnsubscribeError>,
+    > + Send;
+
+    /// Records a bounce or complaint reported by the email provider.
+    fn record_email_event(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        email_event: &EmailEvent,
+    ) -> impl std::future::Future<
+        Output = Result<(), RecordEmailEventError>,
+    > + Send;
+
+    /// Puts the address on the suppression list, marks its subscriber with
+    /// the reason & drops their pending deliveries and confirmation emails.
+    fn suppress_email(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        email: &str,
+        reason: SuppressionReason,
+    ) -> impl std::future::Future<
+        Output = Result<(), SuppressEmailError>,
+    > + Send;
+
+    /// Writes the confirmation email to the outbox, to be sent once the unit
+    /// of work commits. Its link is only built when sending.
+    fn enqueue_confirmation_email(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        subscriber_id: Uuid,
+        subscriber_email: &str,
+    ) -> impl std::future::Future<
+        Output = Result<(), EnqueueConfirmationEmailError>,
+    > + Send;
+
+    /// Locks a due confirmation email of the outbox, if any.
+    fn acquire_confirmation_email(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+    ) -> impl std::future::Future<
+        Output = Result<
+            Option<ConfirmationEmail>,
+            AcquireConfirmationEmailError,
+        >,
+    > + Send;
+
+    /// Removes the confirmation email from the outbox, sent or not.
+    fn delete_confirmation_email(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        confirmation_email_id: Uuid,
+    ) -> impl std::future::Future<
+        Output = Result<(), DeleteConfirmationEmailError>,
     > + Send;
+
+    fn schedule_confirmation_email_retry(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        confirmation_email_id: Uuid,
+        retry_delay: Duration,
+    ) -> impl std::future::Future<
+        Output = Result<
+            (),
+            ScheduleConfirmationEmailRetryError,
+        >,
+    > + Send;
+
+    /// Moves a confirmation email out of the outbox once it ran out of
+    /// attempts.
+    fn dead_letter_confirmation_email(
+        &self,
+        unit_of_work: &mut Self::UnitOfWork,
+        confirmation_email_id: Uuid,
+        last_error: &str,
+    ) -> impl std::future::Future<
+        Output = Result<
+            (),
+            DeadLetterConfirmationEmailError,
+        >,
+    > + Send;
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfirmationEmail {
+    pub confirmation_email_id: Uuid,
+    pub subscriber_id: Uuid,
+    pub subscriber_email: String,
+    pub n_retries: i32,
+}
+
+#[derive(Debug, Clone)]
+pub struct EmailEvent {
+    /// `Bounce` or `SpamComplaint`.
+    pub record_type: String,
+    pub email: String,
+    pub provider_message_id: Option<String>,
+    /// e.g. `HardBounce`, `SoftBounce` or `SpamComplaint`.
+    pub bounce_type: String,
+    pub description: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Also the status given to the subscriber of a suppressed address.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, derive_more::Display,
+)]
+pub enum SuppressionReason {
+    #[display("bounced")]
+    Bounced,
+    #[display("complained")]
+    Complained,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpsertedSubscriber {
+    Inserted(Uuid),
+    PendingConfirmation(Uuid),
+    Confirmed(Uuid),
+    Resubscribed(Uuid),
+    /// The address bounced or complained, so it is never emailed again.
+    Suppressed(Uuid),
+}
+
+impl UpsertedSubscriber {
+    /// Id of the subscriber if a confirmation email has to be sent.
+    #[must_use]
+    pub fn awaiting_confirmation(self) -> Option<Uuid> {
+        match self {
+            UpsertedSubscriber::Inserted(id)
+            | UpsertedSubscriber::PendingConfirmation(id)
+            | UpsertedSubscriber::Resubscribed(id) => {
+                Some(id)
+            }
+            UpsertedSubscriber::Confirmed(_)
+            | UpsertedSubscriber::Suppressed(_) => None,
+        }
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -45,3 +197,53 @@ pub enum StoreTokenError {
     #[error(transparent)]
     Unexpected(#[from] eyre::Report),
 }
+
+#[derive(Debug, thiserror::Error)]
+pub enum UnsubscribeError {
+    #[error("No subscriber with uuid '{0}' found.")]
+    NotFound(Uuid),
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RecordEmailEventError {
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SuppressEmailError {
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum EnqueueConfirmationEmailError {
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AcquireConfirmationEmailError {
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeleteConfirmationEmailError {
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ScheduleConfirmationEmailRetryError {
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeadLetterConfirmationEmailError {
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
@@ -1,22 +1,32 @@
+use std::time::Duration;
+
 use uuid::Uuid;
 
-use crate::dependency_injection::app_state::SendSyncStatic;
+use crate::{
+    database::transactional::unit_of_work::UnitOfWorkRepository,
+    domain::SubscriptionToken,
+};
 
 pub trait SubscriptionsConfirmRepository:
-    SendSyncStatic
+    UnitOfWorkRepository
 {
-    fn get_subscriber_id_of_confirmation_token(
+    /// Deletes the token so it cannot be used twice.
+    /// Expired tokens are kept, so they keep being reported as expired.
+    fn consume_confirmation_token(
         &self,
-        subscription_token: Uuid,
+        unit_of_work: &mut Self::UnitOfWork,
+        subscription_token: &SubscriptionToken,
+        time_to_live: Duration,
     ) -> impl std::future::Future<
         Output = Result<
             Uuid,
-            GetSubscriberIdOfConfirmationTokenError,
+            ConsumeConfirmationTokenError,
         >,
     > + Send;
 
     fn update_status_of_subscriber_id_to_confirmed(
         &self,
+        unit_of_work: &mut Self::UnitOfWork,
         subscriber_id: Uuid,
     ) -> impl std::future::Future<
         Output = Result<
@@ -27,9 +37,11 @@ pub trait SubscriptionsConfirmRepository:
 }
 
 #[derive(Debug, thiserror::Error)]
-pub enum GetSubscriberIdOfConfirmationTokenError {
+pub enum ConsumeConfirmationTokenError {
     #[error("Subscription token not found.")]
-    TokenNotFound { subscription_token: Uuid },
+    TokenNotFound,
+    #[error("Subscription token expired.")]
+    TokenExpired,
     #[error(transparent)]
     Unexpected(#[from] eyre::Report),
 }
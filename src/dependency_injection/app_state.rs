@@ -3,24 +3,32 @@ use std::{
 };
 
 use actix_web::web;
+use eyre::Context as _;
 
 use crate::{
     configuration::{DatabaseSettings, Settings},
     database::{
         postgres::{
-            PgPool, PgPoolDependencies, PgRepository,
+            PgNotificationListener, PgPool,
+            PgPoolDependencies, PgRepository,
             PgRepositoryDependencies, PgTransaction,
         },
         transactional::{
             authentication::AuthenticationRepository,
             issue_delivery_queue::IssueDeliveryQueueRepository,
             newsletters::NewslettersRepository,
+            notifications::{
+                ConfirmationEmailOutboxChannel,
+                IssueDeliveryQueueChannel,
+                NotificationListener,
+            },
             persistence::PersistenceRepository,
             subscriptions::SubscriptionsRepository,
             subscriptions_confirm::SubscriptionsConfirmRepository,
             unit_of_work::{BeginUnitOfWork, UnitOfWork},
         },
     },
+    email_client::{EmailSender, EmailTransport},
     hkt::{RefHKT, SendHKT, SharedPointerHKT, SyncHKT},
     issue_delivery_worker::IssueDeliveryWorkerDependencyAlias,
     services::{
@@ -28,6 +36,7 @@ use crate::{
         uuid::{DefaultUuidGenerator, UuidGenerator},
     },
     startup::GlobalSharedPointer,
+    utils::Pipe,
 };
 
 pub trait AppStateTypes: Marker {
@@ -38,9 +47,16 @@ pub trait AppStateTypes: Marker {
     type BeginUnitOfWork: BeginUnitOfWork<
         UnitOfWork = Self::UnitOfWork,
     >;
+    /// Shared by every idle issue delivery worker.
+    type IssueDeliveryQueueListener: NotificationListener<
+        IssueDeliveryQueueChannel,
+    >;
+    type ConfirmationEmailOutboxListener: NotificationListener<ConfirmationEmailOutboxChannel>;
 
     type AuthenticationRepository: AuthenticationRepository;
-    type SubscriptionsConfirmRepository: SubscriptionsConfirmRepository;
+    type SubscriptionsConfirmRepository: SubscriptionsConfirmRepository<
+        UnitOfWork = Self::UnitOfWork,
+    >;
 
     type IssueDeliveryQueueRepository: IssueDeliveryQueueRepository<UnitOfWork = Self::UnitOfWork>;
     type NewslettersRepository: NewslettersRepository<
@@ -52,6 +68,8 @@ pub trait AppStateTypes: Marker {
     type SubscriptionsRepository: SubscriptionsRepository<
         UnitOfWork = Self::UnitOfWork,
     >;
+
+    type EmailSender: EmailSender;
 }
 
 pub struct DefaultAppStateTypes;
@@ -64,22 +82,31 @@ impl AppStateTypes for DefaultAppStateTypes {
 
     type UnitOfWork = PgTransaction;
     type BeginUnitOfWork = PgPoolConcrete;
+    type IssueDeliveryQueueListener =
+        PgNotificationListener<IssueDeliveryQueueChannel>;
+    type ConfirmationEmailOutboxListener =
+        PgNotificationListener<
+            ConfirmationEmailOutboxChannel,
+        >;
 
     type AuthenticationRepository = PgPoolConcrete;
-    type SubscriptionsConfirmRepository = PgPoolConcrete;
+    type SubscriptionsConfirmRepository =
+        PgRepositoryConcrete;
 
     type IssueDeliveryQueueRepository =
         PgRepositoryConcrete;
     type NewslettersRepository = PgRepositoryConcrete;
     type PersistenceRepository = PgRepositoryConcrete;
     type SubscriptionsRepository = PgRepositoryConcrete;
+
+    type EmailSender = EmailTransport;
 }
 
 pub trait AppStateFactory: Marker {
     type AppStateTypes: AppStateTypes;
     fn build<P: SharedPointerHKT>(
         configuration: &Settings<P>,
-    ) -> AppState<Self::AppStateTypes>;
+    ) -> Result<AppState<Self::AppStateTypes>, eyre::Report>;
 }
 
 pub struct PgRepositoryDependencyTypes;
@@ -110,6 +137,12 @@ pub struct AppState<A: AppStateTypes> {
 
     pub begin_unit_of_work:
         GlobalSharedPointer<A::BeginUnitOfWork>,
+    pub issue_delivery_queue_listener:
+        GlobalSharedPointer<A::IssueDeliveryQueueListener>,
+    pub confirmation_email_outbox_listener:
+        GlobalSharedPointer<
+            A::ConfirmationEmailOutboxListener,
+        >,
 
     pub authentication_repository:
         GlobalSharedPointer<A::AuthenticationRepository>,
@@ -128,6 +161,10 @@ pub struct AppState<A: AppStateTypes> {
         GlobalSharedPointer<A::PersistenceRepository>,
     pub subscriptions_repository:
         GlobalSharedPointer<A::SubscriptionsRepository>,
+
+    pub email_sender: GlobalSharedPointer<A::EmailSender>,
+    pub fallback_email_sender:
+        Option<GlobalSharedPointer<A::EmailSender>>,
 }
 
 impl<A: AppStateTypes> Clone for AppState<A> {
@@ -138,6 +175,12 @@ impl<A: AppStateTypes> Clone for AppState<A> {
             begin_unit_of_work: self
                 .begin_unit_of_work
                 .clone(),
+            issue_delivery_queue_listener: self
+                .issue_delivery_queue_listener
+                .clone(),
+            confirmation_email_outbox_listener: self
+                .confirmation_email_outbox_listener
+                .clone(),
             authentication_repository: self
                 .authentication_repository
                 .clone(),
@@ -156,11 +199,17 @@ impl<A: AppStateTypes> Clone for AppState<A> {
             subscriptions_repository: self
                 .subscriptions_repository
                 .clone(),
+            email_sender: self.email_sender.clone(),
+            fallback_email_sender: self
+                .fallback_email_sender
+                .clone(),
         }
     }
 }
 
 impl<A: AppStateTypes> AppState<A> {
+    /// The email senders are left out, as they are only reached through the
+    /// `EmailClient` limiting their send rate.
     #[must_use]
     #[allow(clippy::type_complexity)]
     pub fn into_tuple(
@@ -203,7 +252,8 @@ impl AppStateFactory for DefaultAppStateFactory {
 
     fn build<P: SharedPointerHKT>(
         configuration: &Settings<P>,
-    ) -> AppState<Self::AppStateTypes> {
+    ) -> Result<AppState<Self::AppStateTypes>, eyre::Report>
+    {
         let uuid_generator =
             GlobalSharedPointer::new(DefaultUuidGenerator);
         let clock = GlobalSharedPointer::new(SystemClock);
@@ -221,9 +271,20 @@ impl AppStateFactory for DefaultAppStateFactory {
 
         let begin_unit_of_work = get_pool_arc();
 
+        let issue_delivery_queue_listener =
+            GlobalSharedPointer::new(
+                PgNotificationListener::new(
+                    connection_pool.clone(),
+                ),
+            );
+        let confirmation_email_outbox_listener =
+            GlobalSharedPointer::new(
+                PgNotificationListener::new(
+                    connection_pool.clone(),
+                ),
+            );
+
         let authentication_repository = get_pool_arc();
-        let subscriptions_confirm_repository =
-            get_pool_arc();
 
         let repository =
             GlobalSharedPointer::new(PgRepository::<
@@ -236,20 +297,41 @@ impl AppStateFactory for DefaultAppStateFactory {
         let issue_delivery_queue_repository =
             repository.clone();
         let newsletters_repository = repository.clone();
+        let subscriptions_confirm_repository =
+            repository.clone();
         let persistence_repository = repository.clone();
         let subscriptions_repository = repository.clone();
 
-        AppState {
+        let email_sender = configuration
+            .email_client
+            .transport()
+            .context(
+                "Failed to build the email transport.",
+            )?
+            .pipe(GlobalSharedPointer::new);
+        let fallback_email_sender = configuration
+            .email_client
+            .fallback_transport()
+            .context(
+                "Failed to build the fallback email transport.",
+            )?
+            .map(GlobalSharedPointer::new);
+
+        Ok(AppState {
             uuid_generator,
             clock,
             begin_unit_of_work,
+            issue_delivery_queue_listener,
+            confirmation_email_outbox_listener,
             authentication_repository,
             subscriptions_confirm_repository,
             issue_delivery_queue_repository,
             newsletters_repository,
             persistence_repository,
             subscriptions_repository,
-        }
+            email_sender,
+            fallback_email_sender,
+        })
     }
 }
 
@@ -291,6 +373,12 @@ impl<
     type N = A::NewslettersRepository;
 
     type I = A::IssueDeliveryQueueRepository;
+
+    type S = A::SubscriptionsRepository;
+
+    type L = A::IssueDeliveryQueueListener;
+
+    type E = A::EmailSender;
 }
 
 pub fn get_connection_pool<P: RefHKT>(
@@ -1,14 +1,34 @@
 mod macros;
 mod new_subsriber;
+mod newsletter_html;
+mod newsletter_markdown;
+mod newsletter_template;
 mod subscriber_email;
 mod subscriber_name;
+mod subscription_token;
+mod unsubscribe_token;
 
 pub use new_subsriber::{
     NewSubscriber, NewSubscriberParseError,
 };
+pub use newsletter_html::{
+    HtmlViolation, NewsletterHtml,
+    NewsletterHtmlValidationError,
+};
+pub use newsletter_markdown::NewsletterMarkdown;
+pub use newsletter_template::{
+    NewsletterTemplate, NewsletterTemplateParseError,
+    Placeholder, TemplateContext,
+};
 pub use subscriber_email::{
     SubscriberEmail, SubscriberEmailParseError,
 };
 pub use subscriber_name::{
     SubscriberName, SubscriberNameParseError,
 };
+pub use subscription_token::{
+    SubscriptionToken, SubscriptionTokenParseError,
+};
+pub use unsubscribe_token::{
+    UnsubscribeToken, UnsubscribeTokenError,
+};
@@ -0,0 +1,453 @@
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    sync::LazyLock,
+};
+
+use html5ever::{
+    buffer_queue::BufferQueue,
+    tendril::StrTendril,
+    tokenizer::{
+        Tag, TagKind, Token, TokenSink, TokenSinkResult,
+        Tokenizer, TokenizerOpts,
+    },
+};
+
+use crate::domain::macros::define_enum_derived;
+
+/// Tags rendered consistently by mail clients.
+const ALLOWED_TAGS: &[&str] = &[
+    "a",
+    "abbr",
+    "b",
+    "blockquote",
+    "br",
+    "caption",
+    "center",
+    "code",
+    "del",
+    "div",
+    "em",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "i",
+    "img",
+    "li",
+    "ol",
+    "p",
+    "pre",
+    "s",
+    "small",
+    "span",
+    "strong",
+    "sub",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "tr",
+    "u",
+    "ul",
+];
+
+const GENERIC_ATTRIBUTES: &[&str] = &["title"];
+
+const TAG_ATTRIBUTES: &[(&str, &[&str])] = &[
+    ("a", &["href"]),
+    ("img", &["src", "alt", "width", "height"]),
+    ("ol", &["start"]),
+    // Language of fenced code blocks generated from Markdown.
+    ("code", &["class"]),
+    (
+        "table",
+        &[
+            "width",
+            "border",
+            "cellpadding",
+            "cellspacing",
+            "align",
+        ],
+    ),
+    (
+        "td",
+        &["colspan", "rowspan", "width", "align", "valign"],
+    ),
+    (
+        "th",
+        &["colspan", "rowspan", "width", "align", "valign"],
+    ),
+];
+
+const URL_ATTRIBUTES: &[&str] = &["href", "src"];
+
+const URL_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+static SANITISER: LazyLock<ammonia::Builder<'static>> =
+    LazyLock::new(|| {
+        let mut builder = ammonia::Builder::empty();
+        builder
+            .tags(ALLOWED_TAGS.iter().copied().collect())
+            .generic_attributes(
+                GENERIC_ATTRIBUTES
+                    .iter()
+                    .copied()
+                    .collect(),
+            )
+            .tag_attributes(
+                TAG_ATTRIBUTES
+                    .iter()
+                    .map(|(tag, attributes)| {
+                        (
+                            *tag,
+                            attributes
+                                .iter()
+                                .copied()
+                                .collect(),
+                        )
+                    })
+                    .collect::<HashMap<_, HashSet<_>>>(),
+            )
+            .url_schemes(
+                URL_SCHEMES.iter().copied().collect(),
+            )
+            .url_relative(ammonia::UrlRelative::Custom(
+                Box::new(keep_allowed_relative_url),
+            ))
+            .clean_content_tags(["script", "style"].into())
+            .link_rel(None);
+        builder
+    });
+
+/// Issue html restricted to an email-safe subset of tags & attributes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewsletterHtml(String);
+
+impl NewsletterHtml {
+    /// Rejects html from which anything would have to be stripped, listing
+    /// every offending tag, attribute or url.
+    pub fn parse(
+        html: &str,
+    ) -> Result<Self, NewsletterHtmlValidationError> {
+        let violations = find_violations(html);
+
+        if !violations.is_empty() {
+            return Err(NewsletterHtmlValidationError {
+                violations,
+            });
+        }
+
+        Ok(Self::sanitise(html))
+    }
+
+    /// Silently strips anything outside of the allowed subset.
+    #[must_use]
+    pub fn sanitise(html: &str) -> Self {
+        Self(SANITISER.clean(html).to_string())
+    }
+}
+
+impl AsRef<str> for NewsletterHtml {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<NewsletterHtml> for String {
+    fn from(value: NewsletterHtml) -> Self {
+        value.0
+    }
+}
+
+define_enum_derived! {
+    pub enum HtmlViolation {
+        #[error("Tag <{0}> is not allowed.")]
+        Tag(String),
+        #[error("Attribute '{attribute}' is not allowed on <{tag}>.")]
+        Attribute { tag: String, attribute: String },
+        #[error("Url '{url}' of <{tag}> is neither http, https or mailto nor a path of the application.")]
+        UrlScheme { tag: String, url: String },
+        #[error("Comments are not allowed.")]
+        Comment,
+    }
+}
+
+define_enum_derived! {
+    #[error("Newsletter html contains content that is not allowed in emails: {}", display_violations(.violations))]
+    pub struct NewsletterHtmlValidationError {
+        pub violations: Vec<HtmlViolation>,
+    }
+}
+
+fn display_violations(
+    violations: &[HtmlViolation],
+) -> String {
+    violations
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn is_allowed_attribute(
+    tag: &str,
+    attribute: &str,
+) -> bool {
+    GENERIC_ATTRIBUTES.contains(&attribute)
+        || TAG_ATTRIBUTES.iter().any(|(t, attributes)| {
+            *t == tag && attributes.contains(&attribute)
+        })
+}
+
+fn is_allowed_url(url: &str) -> bool {
+    if url.contains("{{") {
+        return is_unsubscribe_url_placeholder(url);
+    }
+
+    match ammonia::Url::parse(url) {
+        Ok(url) => URL_SCHEMES.contains(&url.scheme()),
+        Err(ammonia::url::ParseError::RelativeUrlWithoutBase) => {
+            is_allowed_relative_url(url)
+        }
+        Err(_) => false,
+    }
+}
+
+fn keep_allowed_relative_url(
+    url: &str,
+) -> Option<Cow<'_, str>> {
+    is_allowed_relative_url(url)
+        .then_some(Cow::Borrowed(url))
+}
+
+/// The `{{ unsubscribe_url }}` placeholder, rendered as an absolute url of
+/// the application, & its paths or fragments. Other relative urls do not
+/// resolve from a mail client, & `//host` ones leave the origin.
+fn is_allowed_relative_url(url: &str) -> bool {
+    if url.contains("{{") {
+        return is_unsubscribe_url_placeholder(url);
+    }
+
+    let url = url.trim_start();
+
+    url.is_empty()
+        || url.starts_with('#')
+        || (url.starts_with('/')
+            && !url[1..].starts_with(['/', '\\']))
+}
+
+/// Other placeholders are chosen by subscribers, e.g. their name, so they
+/// would pick where links of their email lead.
+fn is_unsubscribe_url_placeholder(url: &str) -> bool {
+    url.trim()
+        .strip_prefix("{{")
+        .and_then(|i| i.strip_suffix("}}"))
+        .is_some_and(|i| i.trim() == "unsubscribe_url")
+}
+
+/// Collects what `SANITISER` would strip, in order of appearance.
+#[derive(Default)]
+struct ViolationsSink {
+    violations: RefCell<Vec<HtmlViolation>>,
+}
+
+impl ViolationsSink {
+    fn push(&self, violation: HtmlViolation) {
+        let mut violations = self.violations.borrow_mut();
+        if !violations.contains(&violation) {
+            violations.push(violation);
+        }
+    }
+
+    fn check_start_tag(&self, tag: &Tag) {
+        let name = &*tag.name;
+
+        if !ALLOWED_TAGS.contains(&name) {
+            self.push(HtmlViolation::Tag(name.to_owned()));
+            return;
+        }
+
+        for attribute in &tag.attrs {
+            let attribute_name = &*attribute.name.local;
+
+            if !is_allowed_attribute(name, attribute_name) {
+                self.push(HtmlViolation::Attribute {
+                    tag: name.to_owned(),
+                    attribute: attribute_name.to_owned(),
+                });
+            } else if URL_ATTRIBUTES
+                .contains(&attribute_name)
+                && !is_allowed_url(&attribute.value)
+            {
+                self.push(HtmlViolation::UrlScheme {
+                    tag: name.to_owned(),
+                    url: attribute.value.to_string(),
+                });
+            }
+        }
+    }
+}
+
+impl TokenSink for ViolationsSink {
+    type Handle = ();
+
+    fn process_token(
+        &self,
+        token: Token,
+        _line_number: u64,
+    ) -> TokenSinkResult<()> {
+        match token {
+            Token::TagToken(tag)
+                if tag.kind == TagKind::StartTag =>
+            {
+                self.check_start_tag(&tag);
+            }
+            Token::CommentToken(_) => {
+                self.push(HtmlViolation::Comment);
+            }
+            _ => {}
+        }
+        TokenSinkResult::Continue
+    }
+}
+
+fn find_violations(html: &str) -> Vec<HtmlViolation> {
+    let input = BufferQueue::default();
+    input.push_back(StrTendril::from_slice(html));
+
+    let tokenizer = Tokenizer::new(
+        ViolationsSink::default(),
+        TokenizerOpts::default(),
+    );
+    let _ = tokenizer.feed(&input);
+    tokenizer.end();
+
+    tokenizer.sink.violations.into_inner()
+}
+
+#[cfg(test)]
+mod tests {
+    use claims::{assert_err, assert_ok};
+
+    use super::{HtmlViolation, NewsletterHtml};
+
+    #[test]
+    fn email_safe_html_is_accepted_unchanged() {
+        let html = r#"<h1>Title</h1><p>Some <em>news</em>, <a href="https://example.com">read more</a>.</p><img src="https://example.com/a.png" alt="A">"#;
+
+        let parsed =
+            assert_ok!(NewsletterHtml::parse(html));
+
+        assert_eq!(parsed.as_ref(), html);
+    }
+
+    #[test]
+    fn placeholders_are_accepted_as_link_destinations() {
+        let html = r#"<a href="{{ unsubscribe_url }}">Unsubscribe</a>"#;
+
+        let parsed =
+            assert_ok!(NewsletterHtml::parse(html));
+
+        assert_eq!(parsed.as_ref(), html);
+    }
+
+    #[test]
+    fn other_placeholders_are_rejected_as_link_destinations()
+     {
+        let error = assert_err!(NewsletterHtml::parse(
+            r#"<a href="{{ subscriber.name }}">a</a><a href="/{{ subscriber.name }}">b</a><img src="https://example.com/{{subscriber.name}}">"#
+        ));
+
+        assert_eq!(
+            error.violations,
+            vec![
+                HtmlViolation::UrlScheme {
+                    tag: "a".to_owned(),
+                    url: "{{ subscriber.name }}".to_owned(),
+                },
+                HtmlViolation::UrlScheme {
+                    tag: "a".to_owned(),
+                    url: "/{{ subscriber.name }}".to_owned(),
+                },
+                HtmlViolation::UrlScheme {
+                    tag: "img".to_owned(),
+                    url: "https://example.com/{{subscriber.name}}"
+                        .to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn every_violation_is_listed() {
+        let error = assert_err!(NewsletterHtml::parse(
+            r#"<script>alert(1)</script><p onclick="x()">Hi</p><a href="javascript:alert(1)">x</a><!-- comment --><script></script>"#
+        ));
+
+        assert_eq!(
+            error.violations,
+            vec![
+                HtmlViolation::Tag("script".to_owned()),
+                HtmlViolation::Attribute {
+                    tag: "p".to_owned(),
+                    attribute: "onclick".to_owned(),
+                },
+                HtmlViolation::UrlScheme {
+                    tag: "a".to_owned(),
+                    url: "javascript:alert(1)".to_owned(),
+                },
+                HtmlViolation::Comment,
+            ]
+        );
+    }
+
+    #[test]
+    fn only_same_origin_relative_urls_are_accepted() {
+        let html = r##"<a href="/subscriptions">a</a><a href="#top">b</a>"##;
+
+        let parsed =
+            assert_ok!(NewsletterHtml::parse(html));
+        assert_eq!(parsed.as_ref(), html);
+
+        let error = assert_err!(NewsletterHtml::parse(
+            r#"<a href="//evil.example.com">a</a><a href="page.html">b</a>"#
+        ));
+        assert_eq!(
+            error.violations,
+            vec![
+                HtmlViolation::UrlScheme {
+                    tag: "a".to_owned(),
+                    url: "//evil.example.com".to_owned(),
+                },
+                HtmlViolation::UrlScheme {
+                    tag: "a".to_owned(),
+                    url: "page.html".to_owned(),
+                },
+            ]
+        );
+        assert_eq!(
+            NewsletterHtml::sanitise(
+                r#"<a href="//evil.example.com">a</a>"#
+            )
+            .as_ref(),
+            "<a>a</a>"
+        );
+    }
+
+    #[test]
+    fn sanitise_strips_disallowed_content() {
+        let sanitised = NewsletterHtml::sanitise(
+            r#"<p onclick="x()">Hi<script>alert(1)</script></p>"#,
+        );
+
+        assert_eq!(sanitised.as_ref(), "<p>Hi</p>");
+    }
+}
@@ -0,0 +1,494 @@
+use std::fmt::Write as _;
+
+use pulldown_cmark::{
+    CowStr, Event, HeadingLevel, LinkType, Options, Parser,
+    Tag, TagEnd,
+};
+use pulldown_cmark_escape::{escape_href, escape_html};
+
+/// Schemes of the urls links & images may use, as others such as
+/// `javascript:` run in the reader's mail client.
+const URL_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// Issue body authored as Markdown, from which both the html & text
+/// versions are generated so they cannot drift apart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewsletterMarkdown {
+    pub html: String,
+    pub text: String,
+}
+
+impl NewsletterMarkdown {
+    #[must_use]
+    pub fn render(markdown: &str) -> Self {
+        Self {
+            html: render_html(markdown),
+            text: render_text(markdown),
+        }
+    }
+}
+
+fn parser(markdown: &str) -> Parser<'_> {
+    Parser::new_ext(markdown, Options::ENABLE_STRIKETHROUGH)
+}
+
+/// Relative urls are allowed, e.g. `{{unsubscribe_url}}` placeholders.
+fn is_allowed_url(url: &str) -> bool {
+    match url.split_once(':') {
+        Some((scheme, _))
+            if !scheme.contains(['/', '?', '#']) =>
+        {
+            URL_SCHEMES
+                .contains(&&*scheme.to_ascii_lowercase())
+        }
+        _ => true,
+    }
+}
+
+/// Links to disallowed urls are replaced by their text, & images lose their
+/// source.
+fn sanitise_urls<'a>(
+    events: impl Iterator<Item = Event<'a>>,
+) -> impl Iterator<Item = Event<'a>> {
+    // Links cannot be nested.
+    let mut is_in_dropped_link = false;
+
+    events.filter_map(move |event| match event {
+        Event::Start(Tag::Link {
+            ref dest_url, ..
+        }) if !is_allowed_url(dest_url) => {
+            is_in_dropped_link = true;
+            None
+        }
+        Event::End(TagEnd::Link) if is_in_dropped_link => {
+            is_in_dropped_link = false;
+            None
+        }
+        Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) if !is_allowed_url(&dest_url) => {
+            Some(Event::Start(Tag::Image {
+                link_type,
+                dest_url: CowStr::Borrowed(""),
+                title,
+                id,
+            }))
+        }
+        event => Some(event),
+    })
+}
+
+/// Raw html is escaped rather than passed through, so the output only ever
+/// contains tags generated from Markdown.
+fn render_html(markdown: &str) -> String {
+    let events =
+        sanitise_urls(parser(markdown)).map(|event| {
+            match event {
+                Event::Html(html)
+                | Event::InlineHtml(html) => {
+                    Event::Text(html)
+                }
+                Event::Start(Tag::Link {
+                    link_type,
+                    dest_url,
+                    title,
+                    ..
+                }) => Event::InlineHtml(
+                    link_start(
+                        link_type, &dest_url, &title,
+                    )
+                    .into(),
+                ),
+                Event::End(TagEnd::Link) => {
+                    Event::InlineHtml(CowStr::Borrowed(
+                        "</a>",
+                    ))
+                }
+                event => event,
+            }
+        });
+
+    let mut html = String::with_capacity(markdown.len());
+    pulldown_cmark::html::push_html(&mut html, events);
+    html
+}
+
+/// Opening tag of a link, written instead of by `push_html` to keep the
+/// placeholders of its destination.
+fn link_start(
+    link_type: LinkType,
+    dest_url: &str,
+    title: &str,
+) -> String {
+    let mut html = String::from("<a href=\"");
+    if link_type == LinkType::Email {
+        html.push_str("mailto:");
+    }
+    push_href(&mut html, dest_url);
+    if !title.is_empty() {
+        html.push_str("\" title=\"");
+        escape_html(&mut html, title).expect(
+            "Write to string should have been successful.",
+        );
+    }
+    html.push_str("\">");
+    html
+}
+
+/// Link destinations are percent-encoded, which would hide placeholders
+/// such as `[unsubscribe]({{unsubscribe_url}})` from the template.
+fn push_href(html: &mut String, mut dest_url: &str) {
+    while let Some(start) = dest_url.find("{{")
+        && let Some(length) = dest_url[start..].find("}}")
+    {
+        let end = start + length + "}}".len();
+        escape_href(&mut *html, &dest_url[..start])
+            .and_then(|()| {
+                escape_html(
+                    &mut *html,
+                    &dest_url[start..end],
+                )
+            })
+            .expect(
+                "Write to string should have been successful.",
+            );
+        dest_url = &dest_url[end..];
+    }
+    escape_href(html, dest_url).expect(
+        "Write to string should have been successful.",
+    );
+}
+
+fn render_text(markdown: &str) -> String {
+    let mut renderer = TextRenderer::default();
+
+    for event in sanitise_urls(parser(markdown)) {
+        renderer.push(event);
+    }
+
+    renderer.finish()
+}
+
+#[derive(Default)]
+struct TextRenderer {
+    output: String,
+    /// Next number of each nested list, `None` for bullet lists.
+    lists: Vec<Option<u64>>,
+    /// Destination & start of the text of each nested link.
+    links: Vec<(String, usize)>,
+    heading_start: Option<usize>,
+    quote_depth: usize,
+    in_code_block: bool,
+    at_item_marker: bool,
+}
+
+impl TextRenderer {
+    fn push(&mut self, event: Event<'_>) {
+        if !matches!(event, Event::Start(_)) {
+            self.at_item_marker = false;
+        }
+
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) if self.in_code_block => {
+                for line in text.lines() {
+                    self.output.push_str("    ");
+                    self.output.push_str(line);
+                    self.new_line();
+                }
+            }
+            Event::Text(text)
+            | Event::Code(text)
+            | Event::Html(text)
+            | Event::InlineHtml(text)
+            | Event::InlineMath(text)
+            | Event::DisplayMath(text) => {
+                self.output.push_str(&text);
+            }
+            Event::SoftBreak | Event::HardBreak => {
+                self.new_line();
+            }
+            Event::Rule => {
+                self.start_block();
+                self.output.push_str("----------");
+            }
+            Event::TaskListMarker(checked) => {
+                self.output.push_str(if checked {
+                    "[x] "
+                } else {
+                    "[ ] "
+                });
+            }
+            Event::FootnoteReference(name) => {
+                write!(self.output, "[{name}]").expect(
+    "Write to string should have been successful.",
+);
+            }
+        }
+    }
+
+    fn start(&mut self, tag: Tag<'_>) {
+        match tag {
+            // Loose list items start with a paragraph right after their
+            // marker.
+            Tag::Paragraph if self.at_item_marker => {}
+            Tag::Paragraph => self.start_block(),
+            Tag::Heading { .. } => {
+                self.start_block();
+                self.heading_start =
+                    Some(self.output.len());
+            }
+            Tag::BlockQuote(_) => {
+                self.start_block();
+                self.quote_depth += 1;
+                self.output.push_str("> ");
+            }
+            // Lines are indented instead of fenced.
+            Tag::CodeBlock(_) => {
+                self.start_block();
+                self.in_code_block = true;
+            }
+            Tag::List(first_number) => {
+                if self.lists.is_empty() {
+                    self.start_block();
+                }
+                self.lists.push(first_number);
+            }
+            Tag::Item => {
+                if !self.at_line_start() {
+                    self.new_line();
+                }
+                let indentation = "  ".repeat(
+                    self.lists.len().saturating_sub(1),
+                );
+                self.output.push_str(&indentation);
+                match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        write!(self.output, "{number}. ").expect(
+    "Write to string should have been successful.",
+);
+                        *number += 1;
+                    }
+                    _ => self.output.push_str("- "),
+                }
+                self.at_item_marker = true;
+                return;
+            }
+            Tag::Link { dest_url, .. } => {
+                self.links.push((
+                    dest_url.into_string(),
+                    self.output.len(),
+                ));
+            }
+            _ => {}
+        }
+        self.at_item_marker = false;
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Heading(level) => {
+                let Some(start) = self.heading_start.take()
+                else {
+                    return;
+                };
+                let underline = match level {
+                    HeadingLevel::H1 => '=',
+                    HeadingLevel::H2 => '-',
+                    _ => return,
+                };
+                let width =
+                    self.output[start..].chars().count();
+                self.new_line();
+                self.output.extend(std::iter::repeat_n(
+                    underline, width,
+                ));
+            }
+            TagEnd::BlockQuote(_) => {
+                self.quote_depth -= 1;
+            }
+            TagEnd::CodeBlock => {
+                self.in_code_block = false;
+                self.trim_end();
+            }
+            TagEnd::List(_) => {
+                self.lists.pop();
+            }
+            TagEnd::Link => {
+                let Some((dest_url, start)) =
+                    self.links.pop()
+                else {
+                    return;
+                };
+                // Autolinks already show their destination.
+                if self.output[start..] != dest_url {
+                    write!(self.output, " ({dest_url})").expect(
+    "Write to string should have been successful.",
+);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn new_line(&mut self) {
+        self.trim_end();
+        self.output.push('\n');
+        for _ in 0..self.quote_depth {
+            self.output.push_str("> ");
+        }
+    }
+
+    /// Separates blocks by an empty line.
+    fn start_block(&mut self) {
+        if is_blank(&self.output) {
+            return;
+        }
+        if !self.at_line_start() {
+            self.new_line();
+        }
+        let previous_line = self
+            .output
+            .split('\n')
+            .rev()
+            .nth(1)
+            .unwrap_or_default();
+        if !is_blank(previous_line) {
+            self.new_line();
+        }
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.output.rsplit('\n').next().is_none_or(is_blank)
+    }
+
+    fn trim_end(&mut self) {
+        let trimmed =
+            self.output.trim_end_matches(' ').len();
+        self.output.truncate(trimmed);
+    }
+
+    fn finish(mut self) -> String {
+        let trimmed = self.output.trim_end().len();
+        self.output.truncate(trimmed);
+        self.output
+    }
+}
+
+/// Lines holding nothing but block quote markers are blank.
+fn is_blank(line: &str) -> bool {
+    line.chars().all(|c| c == '>' || c.is_whitespace())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NewsletterMarkdown;
+
+    #[test]
+    fn markdown_is_rendered_to_html() {
+        let rendered = NewsletterMarkdown::render(
+            "# Title\n\nSome *emphasis* & a [link](https://example.com).",
+        );
+
+        assert_eq!(
+            rendered.html,
+            "<h1>Title</h1>\n<p>Some <em>emphasis</em> &amp; a \
+             <a href=\"https://example.com\">link</a>.</p>\n"
+        );
+    }
+
+    #[test]
+    fn raw_html_is_escaped() {
+        let rendered = NewsletterMarkdown::render(
+            "<script>alert(1)</script>\n\nHi <b>there</b>",
+        );
+
+        assert!(!rendered.html.contains("<script>"));
+        assert!(!rendered.html.contains("<b>"));
+        assert!(rendered.html.contains("&lt;script&gt;"));
+    }
+
+    #[test]
+    fn markdown_is_rendered_to_readable_text() {
+        let rendered = NewsletterMarkdown::render(
+            "# Title\n\nSome *emphasis* & a [link](https://example.com).\n\n\
+             - first\n- second\n\n1. one\n2. two\n\n> quoted\n> lines",
+        );
+
+        assert_eq!(
+            rendered.text,
+            "Title\n=====\n\n\
+             Some emphasis & a link (https://example.com).\n\n\
+             - first\n- second\n\n\
+             1. one\n2. two\n\n\
+             > quoted\n> lines"
+        );
+    }
+
+    #[test]
+    fn placeholders_survive_rendering() {
+        let rendered = NewsletterMarkdown::render(
+            "Hi {{ subscriber.name }}, since {{ confirm_date }}.",
+        );
+
+        assert_eq!(
+            rendered.html,
+            "<p>Hi {{ subscriber.name }}, since {{ confirm_date }}.</p>\n"
+        );
+        assert_eq!(
+            rendered.text,
+            "Hi {{ subscriber.name }}, since {{ confirm_date }}."
+        );
+    }
+
+    #[test]
+    fn placeholders_survive_in_link_destinations() {
+        let rendered = NewsletterMarkdown::render(
+            "[Unsubscribe]({{unsubscribe_url}})",
+        );
+
+        assert_eq!(
+            rendered.html,
+            "<p><a href=\"{{unsubscribe_url}}\">Unsubscribe</a></p>\n"
+        );
+        assert_eq!(
+            rendered.text,
+            "Unsubscribe ({{unsubscribe_url}})"
+        );
+    }
+
+    #[test]
+    fn encoded_braces_outside_placeholders_are_kept() {
+        let rendered = NewsletterMarkdown::render(
+            "[Search](https://example.com/?q=%7B%7Bx%7D%7D) %7B%7B",
+        );
+
+        assert_eq!(
+            rendered.html,
+            "<p><a href=\"https://example.com/?q=%7B%7Bx%7D%7D\">\
+             Search</a> %7B%7B</p>\n"
+        );
+    }
+
+    #[test]
+    fn links_to_disallowed_schemes_are_dropped() {
+        let rendered = NewsletterMarkdown::render(
+            "[Click](javascript:alert(1)) [mail](mailto:a@example.com) \
+             ![x](JavaScript:alert(1))",
+        );
+
+        assert_eq!(
+            rendered.html,
+            "<p>Click <a href=\"mailto:a@example.com\">mail</a> \
+             <img src=\"\" alt=\"x\" /></p>\n"
+        );
+        assert_eq!(
+            rendered.text,
+            "Click mail (mailto:a@example.com) x"
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use std::borrow::Cow;
+
+use crate::{
+    domain::macros::define_enum_derived, utils::escape_html,
+};
+
+const OPENING_DELIMITER: &str = "{{";
+const CLOSING_DELIMITER: &str = "}}";
+
+/// Newsletter body with `{{ placeholder }}`s rendered per recipient.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewsletterTemplate {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    Placeholder(Placeholder),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placeholder {
+    SubscriberName,
+    UnsubscribeUrl,
+    ConfirmDate,
+}
+
+impl Placeholder {
+    fn parse(
+        name: &str,
+    ) -> Result<Self, NewsletterTemplateParseError> {
+        match name.trim() {
+            "subscriber.name" => Ok(Self::SubscriberName),
+            "unsubscribe_url" => Ok(Self::UnsubscribeUrl),
+            "confirm_date" => Ok(Self::ConfirmDate),
+            other => Err(
+                NewsletterTemplateParseError::UnknownPlaceholder(
+                    other.to_owned(),
+                ),
+            ),
+        }
+    }
+}
+
+/// Values of the placeholders for a single recipient.
+pub struct TemplateContext<'a> {
+    pub subscriber_name: &'a str,
+    pub unsubscribe_url: &'a str,
+    pub confirm_date: &'a str,
+}
+
+impl TemplateContext<'_> {
+    fn value_of(&self, placeholder: Placeholder) -> &str {
+        match placeholder {
+            Placeholder::SubscriberName => {
+                self.subscriber_name
+            }
+            Placeholder::UnsubscribeUrl => {
+                self.unsubscribe_url
+            }
+            Placeholder::ConfirmDate => self.confirm_date,
+        }
+    }
+}
+
+impl NewsletterTemplate {
+    pub fn parse(
+        template: &str,
+    ) -> Result<Self, NewsletterTemplateParseError> {
+        let mut segments = Vec::new();
+        let mut rest = template;
+
+        while let Some(start) = rest.find(OPENING_DELIMITER)
+        {
+            if start > 0 {
+                segments.push(Segment::Text(
+                    rest[..start].to_owned(),
+                ));
+            }
+
+            let after_opening =
+                &rest[start + OPENING_DELIMITER.len()..];
+            let end = after_opening
+                .find(CLOSING_DELIMITER)
+                .ok_or(
+                    NewsletterTemplateParseError::UnclosedPlaceholder,
+                )?;
+
+            segments.push(Segment::Placeholder(
+                Placeholder::parse(&after_opening[..end])?,
+            ));
+
+            rest = &after_opening
+                [end + CLOSING_DELIMITER.len()..];
+        }
+
+        if !rest.is_empty() {
+            segments.push(Segment::Text(rest.to_owned()));
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// Template sending `template` verbatim, for issues written before
+    /// placeholders existed.
+    #[must_use]
+    pub fn literal(template: &str) -> Self {
+        Self {
+            segments: vec![Segment::Text(
+                template.to_owned(),
+            )],
+        }
+    }
+
+    #[must_use]
+    pub fn render_text(
+        &self,
+        context: &TemplateContext<'_>,
+    ) -> String {
+        self.render(context, Cow::Borrowed)
+    }
+
+    /// Placeholder values are escaped, the template itself is not.
+    #[must_use]
+    pub fn render_html(
+        &self,
+        context: &TemplateContext<'_>,
+    ) -> String {
+        self.render(context, escape_html)
+    }
+
+    fn render<'a>(
+        &self,
+        context: &'a TemplateContext<'_>,
+        escape: impl Fn(&'a str) -> Cow<'a, str>,
+    ) -> String {
+        let mut rendered = String::new();
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => {
+                    rendered.push_str(text);
+                }
+                Segment::Placeholder(placeholder) => {
+                    rendered.push_str(&escape(
+                        context.value_of(*placeholder),
+                    ));
+                }
+            }
+        }
+
+        rendered
+    }
+}
+
+define_enum_derived! {
+    pub enum NewsletterTemplateParseError {
+        #[error("Unknown placeholder '{{{{ {0} }}}}'.")]
+        UnknownPlaceholder(String),
+        #[error("Placeholder is missing its closing '}}}}'.")]
+        UnclosedPlaceholder,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use claims::assert_ok;
+
+    use super::{
+        NewsletterTemplate, NewsletterTemplateParseError,
+        TemplateContext,
+    };
+
+    fn context() -> TemplateContext<'static> {
+        TemplateContext {
+            subscriber_name: "Ursula & co",
+            unsubscribe_url: "https://example.com/unsubscribe",
+            confirm_date: "2026-10-18",
+        }
+    }
+
+    #[test]
+    fn placeholders_are_rendered_per_context() {
+        let template = NewsletterTemplate::parse(
+            "Hi {{ subscriber.name }}, since {{confirm_date}}: {{ unsubscribe_url }}",
+        )
+        .unwrap();
+
+        assert_eq!(
+            template.render_text(&context()),
+            "Hi Ursula & co, since 2026-10-18: https://example.com/unsubscribe"
+        );
+    }
+
+    #[test]
+    fn html_rendering_escapes_placeholder_values_only() {
+        let template = NewsletterTemplate::parse(
+            "<p>{{ subscriber.name }}</p>",
+        )
+        .unwrap();
+
+        assert_eq!(
+            template.render_html(&context()),
+            "<p>Ursula &amp; co</p>"
+        );
+    }
+
+    #[test]
+    fn template_without_placeholders_is_unchanged() {
+        let template =
+            assert_ok!(NewsletterTemplate::parse(
+                "No placeholders { here }"
+            ));
+
+        assert_eq!(
+            template.render_text(&context()),
+            "No placeholders { here }"
+        );
+    }
+
+    #[test]
+    fn unknown_placeholder_is_rejected() {
+        assert_eq!(
+            NewsletterTemplate::parse("{{ subscriber.age }}"),
+            Err(NewsletterTemplateParseError::UnknownPlaceholder(
+                "subscriber.age".to_owned()
+            ))
+        );
+    }
+
+    #[test]
+    fn unclosed_placeholder_is_rejected() {
+        assert_eq!(
+            NewsletterTemplate::parse("Hi {{ subscriber.name"),
+            Err(NewsletterTemplateParseError::UnclosedPlaceholder)
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use std::str::FromStr;
+
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{
+    domain::macros::define_enum_derived, utils::Pipe,
+};
+
+/// Confirmation token sent to a pending subscriber.
+/// Only its `hash` is ever persisted.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, derive_more::Display,
+)]
+pub struct SubscriptionToken(Uuid);
+
+impl SubscriptionToken {
+    #[must_use]
+    pub fn new(token: Uuid) -> Self {
+        Self(token)
+    }
+
+    #[must_use]
+    pub fn hash(&self) -> Vec<u8> {
+        Sha256::digest(self.0.to_string().as_bytes())
+            .to_vec()
+    }
+}
+
+impl FromStr for SubscriptionToken {
+    type Err = SubscriptionTokenParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Uuid::from_str(s)
+            .map_err(|_| SubscriptionTokenParseError)?
+            .pipe(Self)
+            .pipe(Ok)
+    }
+}
+
+define_enum_derived! {
+    #[error("Subscription token is not a valid uuid.")]
+    pub struct SubscriptionTokenParseError;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use uuid::Uuid;
+
+    use super::SubscriptionToken;
+
+    #[test]
+    fn hash_does_not_contain_the_token() {
+        let token = SubscriptionToken::new(Uuid::new_v4());
+
+        assert_ne!(
+            token.hash(),
+            token.to_string().as_bytes()
+        );
+        assert_eq!(token.hash().len(), 32);
+    }
+
+    #[test]
+    fn parsed_token_has_the_same_hash() {
+        let token = SubscriptionToken::new(Uuid::new_v4());
+        let parsed =
+            SubscriptionToken::from_str(&token.to_string())
+                .unwrap();
+
+        assert_eq!(token.hash(), parsed.hash());
+    }
+
+    #[test]
+    fn non_uuid_token_is_rejected() {
+        claims::assert_err!(SubscriptionToken::from_str(
+            "not-a-token"
+        ));
+    }
+}
@@ -0,0 +1,148 @@
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use secrecy::{ExposeSecret, SecretString};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::{
+    domain::macros::define_enum_derived, utils::Pipe,
+};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Signature of a subscriber id, allowing one-click unsubscribe links
+/// without storing a token per subscriber.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    derive_more::AsRef,
+    derive_more::Display,
+    derive_more::Into,
+)]
+#[as_ref(forward)]
+pub struct UnsubscribeToken(String);
+
+impl UnsubscribeToken {
+    #[must_use]
+    pub fn sign(
+        hmac_secret: &SecretString,
+        subscriber_id: Uuid,
+    ) -> Self {
+        mac(hmac_secret, subscriber_id)
+            .finalize()
+            .into_bytes()
+            .pipe(|i| {
+                base64::engine::general_purpose::URL_SAFE_NO_PAD
+                    .encode(i)
+            })
+            .pipe(Self)
+    }
+
+    pub fn verify(
+        token: &str,
+        hmac_secret: &SecretString,
+        subscriber_id: Uuid,
+    ) -> Result<Self, UnsubscribeTokenError> {
+        let signature =
+            base64::engine::general_purpose::URL_SAFE_NO_PAD
+                .decode(token)
+                .map_err(|_| UnsubscribeTokenError::Malformed)?;
+
+        mac(hmac_secret, subscriber_id)
+            .verify_slice(&signature)
+            .map_err(|_| UnsubscribeTokenError::Invalid)?;
+
+        token.to_owned().pipe(Self).pipe(Ok)
+    }
+
+    #[must_use]
+    pub fn link(
+        &self,
+        base_url: &str,
+        subscriber_id: Uuid,
+    ) -> String {
+        format!(
+            "{}/subscriptions/unsubscribe?subscriber_id={}&token={}",
+            base_url, subscriber_id, self.0
+        )
+    }
+}
+
+fn mac(
+    hmac_secret: &SecretString,
+    subscriber_id: Uuid,
+) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(
+        hmac_secret.expose_secret().as_bytes(),
+    )
+    .expect("HMAC can take key of any size.");
+    mac.update(subscriber_id.as_bytes());
+    mac
+}
+
+define_enum_derived! {
+    pub enum UnsubscribeTokenError {
+        #[error("Unsubscribe token is not valid base64.")]
+        Malformed,
+        #[error("Unsubscribe token does not match subscriber.")]
+        Invalid,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::SecretString;
+    use uuid::Uuid;
+
+    use super::{UnsubscribeToken, UnsubscribeTokenError};
+
+    fn secret() -> SecretString {
+        SecretString::from("super-secret-hmac-key")
+    }
+
+    #[test]
+    fn signed_token_is_verified() {
+        let subscriber_id = Uuid::new_v4();
+        let token = UnsubscribeToken::sign(
+            &secret(),
+            subscriber_id,
+        );
+
+        claims::assert_ok!(UnsubscribeToken::verify(
+            token.as_ref(),
+            &secret(),
+            subscriber_id
+        ));
+    }
+
+    #[test]
+    fn token_of_another_subscriber_is_rejected() {
+        let token = UnsubscribeToken::sign(
+            &secret(),
+            Uuid::new_v4(),
+        );
+
+        assert_eq!(
+            UnsubscribeToken::verify(
+                token.as_ref(),
+                &secret(),
+                Uuid::new_v4()
+            ),
+            Err(UnsubscribeTokenError::Invalid)
+        );
+    }
+
+    #[test]
+    fn malformed_token_is_rejected() {
+        assert_eq!(
+            UnsubscribeToken::verify(
+                "not base64!",
+                &secret(),
+                Uuid::new_v4()
+            ),
+            Err(UnsubscribeTokenError::Malformed)
+        );
+    }
+}
@@ -0,0 +1,316 @@
+use std::{
+    num::NonZeroU32,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// When the circuit breaker of an email client opens, and for how long.
+#[derive(Debug, Clone, Copy, serde::Deserialize)]
+#[serde(default)]
+pub struct CircuitBreakerPolicy {
+    /// Consecutive failures of the provider opening the circuit.
+    pub failure_threshold: NonZeroU32,
+    /// Time until a single email probes the provider for recovery.
+    pub open_duration_milliseconds: u64,
+}
+
+impl Default for CircuitBreakerPolicy {
+    fn default() -> Self {
+        Self {
+            failure_threshold: NonZeroU32::new(5).unwrap(),
+            open_duration_milliseconds: 30_000,
+        }
+    }
+}
+
+impl CircuitBreakerPolicy {
+    #[must_use]
+    pub fn open_duration(&self) -> Duration {
+        Duration::from_millis(
+            self.open_duration_milliseconds,
+        )
+    }
+}
+
+/// State of a circuit breaker, as reported by health checks.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Emails are sent to the provider.
+    Closed,
+    /// Emails are not sent to the provider, which is failing.
+    Open,
+    /// A single email probes the provider for recovery.
+    HalfOpen,
+}
+
+#[derive(Debug)]
+enum State {
+    Closed { consecutive_failures: u32 },
+    Open { until: Instant },
+    HalfOpen { since: Instant },
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self::Closed {
+            consecutive_failures: 0,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    policy: CircuitBreakerPolicy,
+    state: Mutex<State>,
+}
+
+/// Stops sending to a failing email provider, shared by the clones of a
+/// client & reported by health checks.
+#[derive(Debug, Clone, Default)]
+pub struct CircuitBreaker(Arc<Inner>);
+
+impl CircuitBreaker {
+    #[must_use]
+    pub fn new(policy: CircuitBreakerPolicy) -> Self {
+        Self(Arc::new(Inner {
+            policy,
+            state: Mutex::default(),
+        }))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.0.state.lock().unwrap_or_else(
+            std::sync::PoisonError::into_inner,
+        )
+    }
+
+    #[must_use]
+    pub fn state(&self) -> CircuitState {
+        match *self.lock() {
+            State::Closed { .. } => CircuitState::Closed,
+            State::Open { .. } => CircuitState::Open,
+            State::HalfOpen { .. } => {
+                CircuitState::HalfOpen
+            }
+        }
+    }
+
+    /// Time until the provider may be sent to again, if not right away.
+    #[must_use]
+    pub fn retry_in(&self) -> Option<Duration> {
+        self.retry_in_at(Instant::now())
+    }
+
+    fn retry_in_at(
+        &self,
+        now: Instant,
+    ) -> Option<Duration> {
+        let until = match *self.lock() {
+            State::Closed { .. } => return None,
+            State::Open { until } => until,
+            State::HalfOpen { since } => {
+                since + self.0.policy.open_duration()
+            }
+        };
+
+        Some(until.saturating_duration_since(now))
+            .filter(|i| !i.is_zero())
+    }
+
+    /// Whether an email may be sent to the provider, as the probe if the
+    /// circuit was open long enough.
+    #[must_use]
+    pub fn try_acquire(&self) -> bool {
+        self.try_acquire_at(Instant::now())
+    }
+
+    /// A probe taking longer than the open duration, e.g. cancelled, lets
+    /// another one through.
+    fn try_acquire_at(&self, now: Instant) -> bool {
+        let mut state = self.lock();
+
+        let is_probing = match *state {
+            State::Closed { .. } => return true,
+            State::Open { until } => now >= until,
+            State::HalfOpen { since } => {
+                now >= since + self.0.policy.open_duration()
+            }
+        };
+
+        if is_probing {
+            tracing::info!(
+                email_client.circuit_state = ?CircuitState::HalfOpen,
+                "Probing the email provider for recovery."
+            );
+            *state = State::HalfOpen { since: now };
+        }
+
+        is_probing
+    }
+
+    /// Outcome of an email sent after `try_acquire`.
+    pub fn record(&self, is_provider_failure: bool) {
+        self.record_at(is_provider_failure, Instant::now());
+    }
+
+    fn record_at(
+        &self,
+        is_provider_failure: bool,
+        now: Instant,
+    ) {
+        let mut state = self.lock();
+
+        if !is_provider_failure {
+            if !matches!(*state, State::Closed { .. }) {
+                tracing::info!(
+                    email_client.circuit_state = ?CircuitState::Closed,
+                    "The email provider recovered, closing the circuit."
+                );
+            }
+            *state = State::default();
+            return;
+        }
+
+        let consecutive_failures = match *state {
+            State::Closed {
+                consecutive_failures,
+            } => consecutive_failures.saturating_add(1),
+            // Failing while open are emails acquired before it opened.
+            State::Open { .. } => return,
+            State::HalfOpen { .. } => u32::MAX,
+        };
+
+        if consecutive_failures
+            < self.0.policy.failure_threshold.get()
+        {
+            *state = State::Closed {
+                consecutive_failures,
+            };
+            return;
+        }
+
+        tracing::warn!(
+            email_client.circuit_state = ?CircuitState::Open,
+            open_duration = ?self.0.policy.open_duration(),
+            "The email provider is failing, opening the circuit."
+        );
+        *state = State::Open {
+            until: now + self.0.policy.open_duration(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        num::NonZeroU32,
+        time::{Duration, Instant},
+    };
+
+    use super::{
+        CircuitBreaker, CircuitBreakerPolicy, CircuitState,
+    };
+
+    const OPEN_DURATION: Duration = Duration::from_secs(30);
+
+    fn circuit_breaker(
+        failure_threshold: u32,
+    ) -> CircuitBreaker {
+        CircuitBreaker::new(CircuitBreakerPolicy {
+            failure_threshold: NonZeroU32::new(
+                failure_threshold,
+            )
+            .unwrap(),
+            open_duration_milliseconds: 30_000,
+        })
+    }
+
+    #[test]
+    fn opens_after_consecutive_failures_only() {
+        let circuit_breaker = circuit_breaker(2);
+        let now = Instant::now();
+
+        circuit_breaker.record_at(true, now);
+        circuit_breaker.record_at(false, now);
+        circuit_breaker.record_at(true, now);
+        assert_eq!(
+            circuit_breaker.state(),
+            CircuitState::Closed
+        );
+        assert!(circuit_breaker.try_acquire_at(now));
+
+        circuit_breaker.record_at(true, now);
+        assert_eq!(
+            circuit_breaker.state(),
+            CircuitState::Open
+        );
+        assert!(!circuit_breaker.try_acquire_at(now));
+        assert_eq!(
+            circuit_breaker.retry_in_at(now),
+            Some(OPEN_DURATION)
+        );
+    }
+
+    #[test]
+    fn a_single_probe_is_let_through_once_open_long_enough()
+    {
+        let circuit_breaker = circuit_breaker(1);
+        let now = Instant::now();
+        circuit_breaker.record_at(true, now);
+
+        let later = now + OPEN_DURATION;
+        assert!(circuit_breaker.try_acquire_at(later));
+        assert_eq!(
+            circuit_breaker.state(),
+            CircuitState::HalfOpen
+        );
+        assert!(!circuit_breaker.try_acquire_at(later));
+
+        assert!(
+            circuit_breaker
+                .try_acquire_at(later + OPEN_DURATION)
+        );
+    }
+
+    #[test]
+    fn a_successful_probe_closes_the_circuit() {
+        let circuit_breaker = circuit_breaker(1);
+        let now = Instant::now();
+        circuit_breaker.record_at(true, now);
+        assert!(
+            circuit_breaker
+                .try_acquire_at(now + OPEN_DURATION)
+        );
+
+        circuit_breaker
+            .record_at(false, now + OPEN_DURATION);
+
+        assert_eq!(
+            circuit_breaker.state(),
+            CircuitState::Closed
+        );
+        assert_eq!(circuit_breaker.retry_in_at(now), None);
+    }
+
+    #[test]
+    fn a_failed_probe_opens_the_circuit_again() {
+        let circuit_breaker = circuit_breaker(3);
+        let now = Instant::now();
+        for _ in 0..3 {
+            circuit_breaker.record_at(true, now);
+        }
+        let later = now + OPEN_DURATION;
+        assert!(circuit_breaker.try_acquire_at(later));
+
+        circuit_breaker.record_at(true, later);
+
+        assert_eq!(
+            circuit_breaker.state(),
+            CircuitState::Open
+        );
+        assert!(!circuit_breaker.try_acquire_at(later));
+    }
+}
@@ -0,0 +1,147 @@
+use lettre::{
+    AsyncFileTransport, AsyncTransport, Tokio1Executor,
+};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::message::build_message;
+use super::{
+    EmailSender, OutgoingEmail, SendEmailError, SentEmail,
+};
+use crate::hkt::{SendHKT, SharedPointerHKT, SyncHKT};
+
+/// Writes each email to an `.eml` file of a directory instead of sending
+/// it, so that local development needs no provider.
+#[derive(Debug, Clone)]
+pub struct FileEmailSender {
+    directory: PathBuf,
+    transport: AsyncFileTransport<Tokio1Executor>,
+}
+
+impl FileEmailSender {
+    /// Creates the directory if missing.
+    pub fn new(
+        directory: impl Into<PathBuf>,
+    ) -> std::io::Result<Self> {
+        let directory = directory.into();
+        std::fs::create_dir_all(&directory)?;
+
+        Ok(Self {
+            transport: AsyncFileTransport::new(&directory),
+            directory,
+        })
+    }
+
+    #[must_use]
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+}
+
+impl EmailSender for FileEmailSender {
+    /// The message id is the name of the written file, without its
+    /// extension.
+    async fn send_email<
+        P: SharedPointerHKT + SendHKT + SyncHKT,
+    >(
+        &self,
+        email: OutgoingEmail<P>,
+    ) -> Result<SentEmail, SendEmailError> {
+        let message = build_message(&email)?;
+
+        let message_id =
+            self.transport.send(message).await.map_err(
+                |e| SendEmailError::File(Arc::new(e)),
+            )?;
+
+        Ok(SentEmail {
+            http_status: None,
+            message_id: Some(message_id),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::Fake;
+    use fake::faker::internet::en::SafeEmail;
+    use uuid::Uuid;
+
+    use crate::domain::SubscriberEmail;
+    use crate::email_client::{
+        EmailHeader, EmailSender, FileEmailSender,
+        OutgoingEmail, SenderAddress,
+    };
+    use crate::hkt::{ArcHKT, RefHKT};
+    use crate::utils::Pipe;
+
+    fn email() -> SubscriberEmail<ArcHKT> {
+        SafeEmail()
+            .fake::<String>()
+            .pipe(ArcHKT::from_string)
+            .pipe(SubscriberEmail::try_from)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn send_email_writes_an_eml_file_of_the_email() {
+        // Arrange
+        let directory = std::env::temp_dir()
+            .join(Uuid::new_v4().to_string());
+        let email_sender =
+            FileEmailSender::new(&directory).unwrap();
+        let recipient = email();
+        let reply_to = email();
+
+        // Act
+        let sent_email = email_sender
+            .send_email(OutgoingEmail {
+                from: SenderAddress {
+                    email: email(),
+                    name: Some(ArcHKT::from_str("Sender")),
+                },
+                recipient: recipient.clone(),
+                subject: ArcHKT::from_str("Subject"),
+                html_content: ArcHKT::from_str(
+                    "<p>Html body</p>",
+                ),
+                text_content: ArcHKT::from_str("Text body"),
+                reply_to: Some(reply_to.clone()),
+                headers: vec![EmailHeader::new(
+                    ArcHKT::from_str("List-Unsubscribe"),
+                    ArcHKT::from_str(
+                        "<https://example.com/unsubscribe>",
+                    ),
+                )],
+                tag: None,
+                metadata: Vec::new(),
+                message_stream: None,
+            })
+            .await;
+
+        // Assert
+        let sent_email = claims::assert_ok!(sent_email);
+        let message_id = sent_email.message_id.unwrap();
+        let eml = std::fs::read_to_string(
+            directory.join(format!("{message_id}.eml")),
+        )
+        .unwrap();
+
+        assert!(
+            eml.contains(&format!("To: {}", &*recipient))
+        );
+        assert!(eml.contains("From: Sender <"));
+        assert!(eml.contains(&format!(
+            "Reply-To: {}",
+            &*reply_to
+        )));
+        assert!(eml.contains("Subject: Subject"));
+        assert!(eml.contains(
+            "List-Unsubscribe: <https://example.com/unsubscribe>"
+        ));
+        assert!(eml.contains("Text body"));
+        assert!(eml.contains("<p>Html body</p>"));
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -1,4 +1,10 @@
-use crate::{email_client::SendEmailRequest, hkt::RefHKT};
+use crate::{
+    email_client::{
+        EmailHeader, EmailMetadata,
+        postmark::SendEmailRequest,
+    },
+    hkt::RefHKT,
+};
 
 const _: () = {
     #[allow(
@@ -19,7 +25,37 @@ const _: () = {
                 _serde::Serializer::serialize_struct(
                     __serializer,
                     "SendEmailRequest",
-                    false as usize + 1 + 1 + 1 + 1 + 1,
+                    false as usize
+                        + 1
+                        + 1
+                        + 1
+                        + 1
+                        + 1
+                        + if Option::is_none(&self.reply_to)
+                        {
+                            0
+                        } else {
+                            1
+                        }
+                        + 1
+                        + if Option::is_none(&self.tag) {
+                            0
+                        } else {
+                            1
+                        }
+                        + if <[_]>::is_empty(&self.metadata)
+                        {
+                            0
+                        } else {
+                            1
+                        }
+                        + if Option::is_none(
+                            &self.message_stream,
+                        ) {
+                            0
+                        } else {
+                            1
+                        },
                 )?;
             _serde::ser::SerializeStruct::serialize_field(
                 &mut __serde_state,
@@ -46,7 +82,108 @@ const _: () = {
                 "TextBody",
                 &self.text_body,
             )?;
+            if Option::is_none(&self.reply_to) {
+                _serde::ser::SerializeStruct::skip_field(
+                    &mut __serde_state,
+                    "ReplyTo",
+                )?;
+            } else {
+                _serde::ser::SerializeStruct::serialize_field(
+                    &mut __serde_state,
+                    "ReplyTo",
+                    &self.reply_to,
+                )?;
+            }
+            _serde::ser::SerializeStruct::serialize_field(
+                &mut __serde_state,
+                "Headers",
+                &self.headers,
+            )?;
+            if Option::is_none(&self.tag) {
+                _serde::ser::SerializeStruct::skip_field(
+                    &mut __serde_state,
+                    "Tag",
+                )?;
+            } else {
+                _serde::ser::SerializeStruct::serialize_field(
+                    &mut __serde_state,
+                    "Tag",
+                    &self.tag,
+                )?;
+            }
+            if <[_]>::is_empty(&self.metadata) {
+                _serde::ser::SerializeStruct::skip_field(
+                    &mut __serde_state,
+                    "Metadata",
+                )?;
+            } else {
+                _serde::ser::SerializeStruct::serialize_field(
+                    &mut __serde_state,
+                    "Metadata",
+                    &MetadataMap(&self.metadata),
+                )?;
+            }
+            if Option::is_none(&self.message_stream) {
+                _serde::ser::SerializeStruct::skip_field(
+                    &mut __serde_state,
+                    "MessageStream",
+                )?;
+            } else {
+                _serde::ser::SerializeStruct::serialize_field(
+                    &mut __serde_state,
+                    "MessageStream",
+                    &self.message_stream,
+                )?;
+            }
             _serde::ser::SerializeStruct::end(__serde_state)
         }
     }
+
+    #[automatically_derived]
+    impl<P: RefHKT> _serde::Serialize for EmailHeader<P> {
+        fn serialize<__S>(
+            &self,
+            __serializer: __S,
+        ) -> _serde::__private::Result<__S::Ok, __S::Error>
+        where
+            __S: _serde::Serializer,
+        {
+            let mut __serde_state =
+                _serde::Serializer::serialize_struct(
+                    __serializer,
+                    "EmailHeader",
+                    false as usize + 1 + 1,
+                )?;
+            _serde::ser::SerializeStruct::serialize_field(
+                &mut __serde_state,
+                "Name",
+                &self.name,
+            )?;
+            _serde::ser::SerializeStruct::serialize_field(
+                &mut __serde_state,
+                "Value",
+                &self.value,
+            )?;
+            _serde::ser::SerializeStruct::end(__serde_state)
+        }
+    }
+
+    /// Metadata is an object of its keys, rather than a list.
+    struct MetadataMap<'a, P: RefHKT>(
+        &'a [EmailMetadata<P>],
+    );
+
+    impl<P: RefHKT> _serde::Serialize for MetadataMap<'_, P> {
+        fn serialize<__S>(
+            &self,
+            __serializer: __S,
+        ) -> _serde::__private::Result<__S::Ok, __S::Error>
+        where
+            __S: _serde::Serializer,
+        {
+            __serializer.collect_map(
+                self.0.iter().map(|i| (&i.key, &i.value)),
+            )
+        }
+    }
 };
@@ -0,0 +1,142 @@
+use chrono::{DateTime, Utc};
+use std::collections::VecDeque;
+use std::sync::{Arc, RwLock};
+use uuid::Uuid;
+
+use super::{
+    EmailSender, OutgoingEmail, SendEmailError, SentEmail,
+};
+use crate::hkt::{SendHKT, SharedPointerHKT, SyncHKT};
+
+/// Oldest emails are dropped past this many, so that a long running
+/// application does not grow without bound.
+pub const MAX_CAPTURED_EMAILS: usize = 1000;
+
+/// Keeps emails in its mailbox instead of sending them, for local
+/// development.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryEmailSender {
+    mailbox: Mailbox,
+}
+
+impl MemoryEmailSender {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl EmailSender for MemoryEmailSender {
+    /// The message id is the id of the email in the mailbox.
+    async fn send_email<
+        P: SharedPointerHKT + SendHKT + SyncHKT,
+    >(
+        &self,
+        email: OutgoingEmail<P>,
+    ) -> Result<SentEmail, SendEmailError> {
+        let captured_email = CapturedEmail {
+            id: Uuid::new_v4(),
+            captured_at: Utc::now(),
+            from: email.from.to_string(),
+            to: email.recipient.to_string(),
+            subject: email.subject.to_string(),
+            html_content: email.html_content.to_string(),
+            text_content: email.text_content.to_string(),
+            reply_to: email
+                .reply_to
+                .as_ref()
+                .map(ToString::to_string),
+            headers: email
+                .headers
+                .iter()
+                .map(|i| {
+                    (
+                        i.name.to_string(),
+                        i.value.to_string(),
+                    )
+                })
+                .collect(),
+            tag: email
+                .tag
+                .as_ref()
+                .map(ToString::to_string),
+            metadata: email
+                .metadata
+                .iter()
+                .map(|i| {
+                    (i.key.to_string(), i.value.to_string())
+                })
+                .collect(),
+            message_stream: email
+                .message_stream
+                .as_ref()
+                .map(ToString::to_string),
+        };
+        let message_id = captured_email.id.to_string();
+
+        self.mailbox.push(captured_email);
+
+        Ok(SentEmail {
+            http_status: None,
+            message_id: Some(message_id),
+        })
+    }
+
+    fn mailbox(&self) -> Option<&Mailbox> {
+        Some(&self.mailbox)
+    }
+}
+
+/// Emails captured by a `MemoryEmailSender`, shared by its clones.
+#[derive(Debug, Clone, Default)]
+pub struct Mailbox(Arc<RwLock<VecDeque<CapturedEmail>>>);
+
+impl Mailbox {
+    fn push(&self, email: CapturedEmail) {
+        let mut emails = self.0.write().unwrap();
+
+        if emails.len() == MAX_CAPTURED_EMAILS {
+            emails.pop_front();
+        }
+
+        emails.push_back(email);
+    }
+
+    /// Newest first.
+    #[must_use]
+    pub fn emails(&self) -> Vec<CapturedEmail> {
+        self.0
+            .read()
+            .unwrap()
+            .iter()
+            .rev()
+            .cloned()
+            .collect()
+    }
+
+    #[must_use]
+    pub fn email(&self, id: Uuid) -> Option<CapturedEmail> {
+        self.0
+            .read()
+            .unwrap()
+            .iter()
+            .find(|i| i.id == id)
+            .cloned()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CapturedEmail {
+    pub id: Uuid,
+    pub captured_at: DateTime<Utc>,
+    pub from: String,
+    pub to: String,
+    pub subject: String,
+    pub html_content: String,
+    pub text_content: String,
+    pub reply_to: Option<String>,
+    pub headers: Vec<(String, String)>,
+    pub tag: Option<String>,
+    pub metadata: Vec<(String, String)>,
+    pub message_stream: Option<String>,
+}
@@ -0,0 +1,72 @@
+use lettre::message::header::{HeaderName, HeaderValue};
+use lettre::message::{Mailbox, MultiPart};
+use lettre::{Address, Message};
+use std::sync::Arc;
+
+use super::{OutgoingEmail, SendEmailError};
+use crate::domain::SubscriberEmail;
+use crate::hkt::RefHKT;
+
+/// Builds the RFC 5322 message of an email, for transports sending those
+/// rather than calling an API.
+pub(super) fn build_message<P: RefHKT>(
+    email: &OutgoingEmail<P>,
+) -> Result<Message, SendEmailError> {
+    let from = Mailbox::new(
+        email.from.name.as_deref().map(ToOwned::to_owned),
+        address(&email.from.email)?,
+    );
+    let mut builder = Message::builder()
+        .from(from)
+        .to(address(&email.recipient)?.into())
+        .subject(&*email.subject)
+        .message_id(None);
+
+    if let Some(reply_to) = &email.reply_to {
+        builder =
+            builder.reply_to(address(reply_to)?.into());
+    }
+
+    for header in &email.headers {
+        let name = HeaderName::new_from_ascii(
+            header.name.to_string(),
+        )
+        .map_err(|_| SendEmailError::Header {
+            name: header.name.to_string(),
+        })?;
+
+        builder = builder.raw_header(HeaderValue::new(
+            name,
+            header.value.to_string(),
+        ));
+    }
+
+    builder
+        .multipart(MultiPart::alternative_plain_html(
+            email.text_content.to_string(),
+            email.html_content.to_string(),
+        ))
+        .map_err(|e| SendEmailError::Message(Arc::new(e)))
+}
+
+fn address<P: RefHKT>(
+    email: &SubscriberEmail<P>,
+) -> Result<Address, SendEmailError> {
+    email.parse::<Address>().map_err(|e| {
+        SendEmailError::Address {
+            address: email.to_string(),
+            source: e,
+        }
+    })
+}
+
+/// `Message-ID` of a built message, which identifies it in replies and
+/// bounces.
+pub(super) fn message_id(
+    message: &Message,
+) -> Option<String> {
+    message
+        .headers()
+        .get_raw("Message-ID")
+        .map(ToOwned::to_owned)
+}
@@ -1,119 +1,833 @@
 use crate::domain::SubscriberEmail;
-use crate::hkt::{K1, RefHKT, SharedPointerHKT};
-use kust::ScopeFunctions;
-use reqwest::Client;
+use crate::hkt::{
+    K1, RefHKT, SendHKT, SharedPointerHKT, SyncHKT,
+};
+use crate::startup::GlobalSharedPointer;
+use std::future::Future;
+use std::sync::Arc;
 
+mod circuit_breaker;
+mod file;
 #[allow(clippy::pedantic)]
 pub mod generated;
+mod memory;
+mod message;
+mod postmark;
+mod rate_limiter;
+mod smtp;
 
-#[derive(Debug, derive_more::Into)]
-pub struct EmailClient<P: RefHKT> {
-    http_client: K1<P, Client>,
-    base_url: K1<P, str>,
-    sender: SubscriberEmail<P>,
-    authorization_token: K1<P, str>,
+pub use circuit_breaker::{
+    CircuitBreaker, CircuitBreakerPolicy, CircuitState,
+};
+pub use file::FileEmailSender;
+pub use memory::{
+    CapturedEmail, MAX_CAPTURED_EMAILS, Mailbox,
+    MemoryEmailSender,
+};
+pub use postmark::PostmarkEmailSender;
+pub use rate_limiter::{
+    DomainSendRate, RateLimiter, SendRate,
+};
+pub use smtp::SmtpEmailSender;
+
+/// Sends emails from the configured sender, within the limits of its rate
+/// limiter, through whichever transport the email sender is.
+///
+/// While its circuit breaker is open, emails fail over to the fallback
+/// email sender if any, or fail without reaching the provider.
+pub struct EmailClient<P: RefHKT, S> {
+    email_sender: GlobalSharedPointer<S>,
+    fallback_email_sender: Option<GlobalSharedPointer<S>>,
+    circuit_breaker: CircuitBreaker,
+    sender: SenderAddress<P>,
+    reply_to: Option<SubscriberEmail<P>>,
+    broadcast_message_stream: Option<K1<P, str>>,
+    rate_limiter: K1<P, RateLimiter>,
 }
 
-const X_POSTMARK_SERVER_TOKEN_HEADER: &str =
-    "X-Postmark-Server-Token";
+/// Maximum number of messages accepted by the batch endpoint per request.
+pub const MAX_BATCH_SIZE: usize = 500;
 
-impl<P: SharedPointerHKT> Clone for EmailClient<P> {
+impl<P: SharedPointerHKT, S> Clone for EmailClient<P, S> {
     fn clone(&self) -> Self {
         Self {
-            http_client: self.http_client.clone(),
-            base_url: self.base_url.clone(),
+            email_sender: self.email_sender.clone(),
+            fallback_email_sender: self
+                .fallback_email_sender
+                .clone(),
+            circuit_breaker: self.circuit_breaker.clone(),
             sender: self.sender.clone(),
-            authorization_token: self
-                .authorization_token
+            reply_to: self.reply_to.clone(),
+            broadcast_message_stream: self
+                .broadcast_message_stream
                 .clone(),
+            rate_limiter: self.rate_limiter.clone(),
         }
     }
 }
 
-impl<P: RefHKT> EmailClient<P> {
+impl<P: RefHKT, S> EmailClient<P, S> {
     pub fn new(
-        base_url: K1<P, str>,
+        email_sender: GlobalSharedPointer<S>,
         sender: SubscriberEmail<P>,
-        authorization_token: K1<P, str>,
-        timeout: std::time::Duration,
-    ) -> EmailClient<P> {
+    ) -> EmailClient<P, S> {
         EmailClient {
-            http_client: Client::builder()
-                .timeout(timeout)
-                .build()
-                .unwrap()
-                .using(P::new),
-            base_url,
-            sender,
-            authorization_token,
+            email_sender,
+            fallback_email_sender: None,
+            circuit_breaker: CircuitBreaker::default(),
+            sender: SenderAddress {
+                email: sender,
+                name: None,
+            },
+            reply_to: None,
+            broadcast_message_stream: None,
+            rate_limiter: P::new(RateLimiter::default()),
+        }
+    }
+
+    /// Shown by mail clients instead of the address of the sender.
+    #[must_use]
+    pub fn with_sender_name(
+        self,
+        name: K1<P, str>,
+    ) -> Self {
+        Self {
+            sender: SenderAddress {
+                name: Some(name),
+                ..self.sender
+            },
+            ..self
+        }
+    }
+
+    /// Replies go to this address by default, rather than to the sender.
+    #[must_use]
+    pub fn with_reply_to(
+        self,
+        reply_to: SubscriberEmail<P>,
+    ) -> Self {
+        Self {
+            reply_to: Some(reply_to),
+            ..self
+        }
+    }
+
+    /// Stream of broadcast emails, e.g. newsletters, kept apart from the
+    /// transactional ones by the provider.
+    #[must_use]
+    pub fn with_broadcast_message_stream(
+        self,
+        message_stream: K1<P, str>,
+    ) -> Self {
+        Self {
+            broadcast_message_stream: Some(message_stream),
+            ..self
+        }
+    }
+
+    /// Sends while the circuit breaker is open.
+    #[must_use]
+    pub fn with_fallback(
+        self,
+        fallback_email_sender: GlobalSharedPointer<S>,
+    ) -> Self {
+        Self {
+            fallback_email_sender: Some(
+                fallback_email_sender,
+            ),
+            ..self
+        }
+    }
+
+    /// Clones of the client share the state of the circuit breaker.
+    #[must_use]
+    pub fn with_circuit_breaker(
+        self,
+        circuit_breaker: CircuitBreaker,
+    ) -> Self {
+        Self {
+            circuit_breaker,
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn circuit_breaker(&self) -> &CircuitBreaker {
+        &self.circuit_breaker
+    }
+
+    /// Time until emails may be sent again, if not right away, i.e. while
+    /// the circuit breaker is open without a fallback.
+    #[must_use]
+    pub fn unavailable_for(
+        &self,
+    ) -> Option<std::time::Duration> {
+        if self.fallback_email_sender.is_some() {
+            return None;
+        }
+
+        self.circuit_breaker.retry_in()
+    }
+
+    /// Clones of the client share the limits of the rate limiter.
+    #[must_use]
+    pub fn with_rate_limiter(
+        self,
+        rate_limiter: RateLimiter,
+    ) -> Self {
+        Self {
+            rate_limiter: P::new(rate_limiter),
+            ..self
+        }
+    }
+}
+
+impl<P: SharedPointerHKT, S> EmailClient<P, S> {
+    /// Email from the sender of the client, to be built further.
+    pub fn message(
+        &self,
+        recipient: SubscriberEmail<P>,
+        subject: K1<P, str>,
+        html_content: K1<P, str>,
+        text_content: K1<P, str>,
+    ) -> EmailMessageBuilder<'_, P, S> {
+        EmailMessageBuilder {
+            email_client: self,
+            email: OutgoingEmail {
+                from: self.sender.clone(),
+                recipient,
+                subject,
+                html_content,
+                text_content,
+                reply_to: self.reply_to.clone(),
+                headers: Vec::new(),
+                tag: None,
+                metadata: Vec::new(),
+                message_stream: None,
+            },
         }
     }
 }
-impl<P: SharedPointerHKT> EmailClient<P> {
+
+impl<
+    P: SharedPointerHKT + SendHKT + SyncHKT,
+    S: EmailSender,
+> EmailClient<P, S>
+{
     pub async fn send_email(
         &self,
         recipient: SubscriberEmail<P>,
         subject: K1<P, str>,
         html_content: K1<P, str>,
         text_content: K1<P, str>,
-    ) -> Result<(), reqwest::Error> {
-        let url = format!("{}/email", self.base_url);
+    ) -> Result<(), SendEmailError> {
+        self.message(
+            recipient,
+            subject,
+            html_content,
+            text_content,
+        )
+        .send()
+        .await
+        .map(|_| ())
+    }
 
+    pub async fn send(
+        &self,
+        email: OutgoingEmail<P>,
+    ) -> Result<SentEmail, SendEmailError> {
         if cfg!(test) {
             tracing::debug!(
                 "Print Recipient: {}",
-                recipient.as_ref().to_string()
+                email.recipient.as_ref().to_string()
             );
         }
 
-        let request_body = SendEmailRequest {
-            from: self.base_url.clone(),
-            to: recipient.into(),
-            subject,
-            html_body: html_content,
-            text_body: text_content,
-        };
+        self.rate_limiter
+            .acquire(email.recipient.as_ref())
+            .await;
+
+        if self.circuit_breaker.try_acquire() {
+            let result =
+                self.email_sender.send_email(email).await;
+            self.circuit_breaker.record(
+                result.as_ref().is_err_and(
+                    SendEmailError::is_provider_failure,
+                ),
+            );
+
+            return result;
+        }
+
+        match &self.fallback_email_sender {
+            Some(fallback_email_sender) => {
+                tracing::debug!(
+                    "Sending the email through the fallback email sender."
+                );
+                fallback_email_sender
+                    .send_email(email)
+                    .await
+            }
+            None => Err(SendEmailError::CircuitOpen),
+        }
+    }
+
+    /// Returns the result of each email, in order.
+    pub async fn send_batch(
+        &self,
+        emails: Vec<OutgoingEmail<P>>,
+    ) -> Vec<Result<SentEmail, SendEmailError>> {
+        for email in &emails {
+            self.rate_limiter
+                .acquire(email.recipient.as_ref())
+                .await;
+        }
+
+        if self.circuit_breaker.try_acquire() {
+            let results =
+                self.email_sender.send_batch(emails).await;
+            // Rejections of some recipients tell of a working provider.
+            self.circuit_breaker.record(
+                !results.is_empty()
+                    && results.iter().all(|i| {
+                        i.as_ref().is_err_and(
+                            SendEmailError::is_provider_failure,
+                        )
+                    }),
+            );
+
+            return results;
+        }
+
+        match &self.fallback_email_sender {
+            Some(fallback_email_sender) => {
+                tracing::debug!(
+                    n_emails = emails.len(),
+                    "Sending the batch through the fallback email sender."
+                );
+                fallback_email_sender
+                    .send_batch(emails)
+                    .await
+            }
+            None => emails
+                .iter()
+                .map(|_| Err(SendEmailError::CircuitOpen))
+                .collect(),
+        }
+    }
+}
+
+/// Builds an email of an `EmailClient`, to send it on its own or along with
+/// others in a batch.
+#[must_use]
+pub struct EmailMessageBuilder<'a, P: RefHKT, S> {
+    email_client: &'a EmailClient<P, S>,
+    email: OutgoingEmail<P>,
+}
+
+impl<P: SharedPointerHKT, S> EmailMessageBuilder<'_, P, S> {
+    pub fn reply_to(
+        self,
+        reply_to: SubscriberEmail<P>,
+    ) -> Self {
+        Self {
+            email: OutgoingEmail {
+                reply_to: Some(reply_to),
+                ..self.email
+            },
+            ..self
+        }
+    }
+
+    pub fn header(
+        mut self,
+        header: EmailHeader<P>,
+    ) -> Self {
+        self.email.headers.push(header);
+        self
+    }
+
+    pub fn headers(
+        mut self,
+        headers: impl IntoIterator<Item = EmailHeader<P>>,
+    ) -> Self {
+        self.email.headers.extend(headers);
+        self
+    }
+
+    /// Groups emails in the statistics of the provider, e.g. per issue.
+    pub fn tag(self, tag: K1<P, str>) -> Self {
+        Self {
+            email: OutgoingEmail {
+                tag: Some(tag),
+                ..self.email
+            },
+            ..self
+        }
+    }
+
+    /// Returned by the provider along with the events of the email.
+    pub fn metadata(
+        mut self,
+        key: K1<P, str>,
+        value: K1<P, str>,
+    ) -> Self {
+        self.email
+            .metadata
+            .push(EmailMetadata { key, value });
+        self
+    }
+
+    pub fn message_stream(
+        self,
+        message_stream: K1<P, str>,
+    ) -> Self {
+        Self {
+            email: OutgoingEmail {
+                message_stream: Some(message_stream),
+                ..self.email
+            },
+            ..self
+        }
+    }
+
+    /// Sent through the broadcast message stream of the client, if any.
+    pub fn broadcast(self) -> Self {
+        match self
+            .email_client
+            .broadcast_message_stream
+            .clone()
+        {
+            Some(message_stream) => {
+                self.message_stream(message_stream)
+            }
+            None => self,
+        }
+    }
 
-        self.http_client
-            .post(&url)
-            .header(
-                X_POSTMARK_SERVER_TOKEN_HEADER,
-                &*self.authorization_token,
+    #[must_use]
+    pub fn build(self) -> OutgoingEmail<P> {
+        self.email
+    }
+}
+
+impl<
+    P: SharedPointerHKT + SendHKT + SyncHKT,
+    S: EmailSender,
+> EmailMessageBuilder<'_, P, S>
+{
+    pub async fn send(
+        self,
+    ) -> Result<SentEmail, SendEmailError> {
+        self.email_client.send(self.email).await
+    }
+}
+
+/// Transport emails are handed to, e.g. the API of a provider.
+pub trait EmailSender: Send + Sync + 'static {
+    fn send_email<P: SharedPointerHKT + SendHKT + SyncHKT>(
+        &self,
+        email: OutgoingEmail<P>,
+    ) -> impl Future<
+        Output = Result<SentEmail, SendEmailError>,
+    > + Send;
+
+    /// Returns the result of each email, in order. Emails are sent one at a
+    /// time, unless the transport supports batches.
+    fn send_batch<
+        P: SharedPointerHKT + SendHKT + SyncHKT,
+    >(
+        &self,
+        emails: Vec<OutgoingEmail<P>>,
+    ) -> impl Future<
+        Output = Vec<Result<SentEmail, SendEmailError>>,
+    > + Send {
+        async move {
+            let mut results =
+                Vec::with_capacity(emails.len());
+
+            for email in emails {
+                results.push(self.send_email(email).await);
+            }
+
+            results
+        }
+    }
+
+    /// Mailbox the emails are kept in rather than sent, if any.
+    fn mailbox(&self) -> Option<&Mailbox> {
+        None
+    }
+}
+
+/// Email sender chosen in `EmailClientSettings`.
+#[derive(Debug, Clone)]
+pub enum EmailTransport {
+    Postmark(PostmarkEmailSender),
+    Smtp(SmtpEmailSender),
+    File(FileEmailSender),
+    Memory(MemoryEmailSender),
+}
+
+impl EmailSender for EmailTransport {
+    async fn send_email<
+        P: SharedPointerHKT + SendHKT + SyncHKT,
+    >(
+        &self,
+        email: OutgoingEmail<P>,
+    ) -> Result<SentEmail, SendEmailError> {
+        match self {
+            Self::Postmark(i) => i.send_email(email).await,
+            Self::Smtp(i) => i.send_email(email).await,
+            Self::File(i) => i.send_email(email).await,
+            Self::Memory(i) => i.send_email(email).await,
+        }
+    }
+
+    async fn send_batch<
+        P: SharedPointerHKT + SendHKT + SyncHKT,
+    >(
+        &self,
+        emails: Vec<OutgoingEmail<P>>,
+    ) -> Vec<Result<SentEmail, SendEmailError>> {
+        match self {
+            Self::Postmark(i) => i.send_batch(emails).await,
+            Self::Smtp(i) => i.send_batch(emails).await,
+            Self::File(i) => i.send_batch(emails).await,
+            Self::Memory(i) => i.send_batch(emails).await,
+        }
+    }
+
+    fn mailbox(&self) -> Option<&Mailbox> {
+        match self {
+            Self::Memory(i) => i.mailbox(),
+            _ => None,
+        }
+    }
+}
+
+/// Email accepted by the transport.
+#[derive(Debug, Clone)]
+pub struct SentEmail {
+    /// Only known of transports over HTTP.
+    pub http_status: Option<u16>,
+    /// Identifies the email in the logs and webhooks of the provider.
+    pub message_id: Option<String>,
+}
+
+/// Email to a single recipient, sent on its own or along with others.
+///
+/// Tags, metadata & message streams are only understood by Postmark.
+pub struct OutgoingEmail<P: RefHKT> {
+    pub from: SenderAddress<P>,
+    pub recipient: SubscriberEmail<P>,
+    pub subject: K1<P, str>,
+    pub html_content: K1<P, str>,
+    pub text_content: K1<P, str>,
+    pub reply_to: Option<SubscriberEmail<P>>,
+    pub headers: Vec<EmailHeader<P>>,
+    pub tag: Option<K1<P, str>>,
+    pub metadata: Vec<EmailMetadata<P>>,
+    pub message_stream: Option<K1<P, str>>,
+}
+
+/// Address emails are sent from, along with the name shown for it.
+pub struct SenderAddress<P: RefHKT> {
+    pub email: SubscriberEmail<P>,
+    pub name: Option<K1<P, str>>,
+}
+
+impl<P: RefHKT> std::fmt::Display for SenderAddress<P> {
+    /// `"name" <email>`, quoting the name as RFC 5322 requires.
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        match &self.name {
+            Some(name) => write!(
+                f,
+                "\"{}\" <{}>",
+                name.replace('\\', "\\\\")
+                    .replace('"', "\\\""),
+                &*self.email
+            ),
+            None => f.write_str(&self.email),
+        }
+    }
+}
+
+impl<P: SharedPointerHKT> Clone for SenderAddress<P> {
+    fn clone(&self) -> Self {
+        Self {
+            email: self.email.clone(),
+            name: self.name.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum SendEmailError {
+    #[error("Timed out waiting for the email provider.")]
+    Timeout(#[source] Arc<reqwest::Error>),
+    #[error("Failed to send the email.")]
+    Request(#[source] Arc<reqwest::Error>),
+    /// Rejected along with the whole request, or on its own within a batch.
+    #[error(
+        "Email rejected with status {http_status} & error code {error_code}: {message}"
+    )]
+    Rejected {
+        http_status: u16,
+        error_code: i64,
+        message: String,
+    },
+    #[error(
+        "Email provider responded with status {http_status}."
+    )]
+    Status { http_status: u16 },
+    #[error("No result returned for the email.")]
+    Missing { http_status: u16 },
+    /// The whole batch the email was sent in failed, which blames none of its
+    /// recipients.
+    #[error("Failed to send the batch of the email.")]
+    Batch(#[source] Box<SendEmailError>),
+    #[error(
+        "'{address}' is not a valid address to send emails to."
+    )]
+    Address {
+        address: String,
+        #[source]
+        source: lettre::address::AddressError,
+    },
+    #[error("'{name}' is not a valid header name.")]
+    Header { name: String },
+    #[error("Failed to build the email.")]
+    Message(#[source] Arc<lettre::error::Error>),
+    #[error("Failed to send the email over SMTP.")]
+    Smtp(#[source] Arc<lettre::transport::smtp::Error>),
+    #[error("Failed to write the email to a file.")]
+    File(#[source] Arc<lettre::transport::file::Error>),
+    #[error(
+        "The email provider is failing, its circuit breaker is open."
+    )]
+    CircuitOpen,
+}
+
+/// Whether sending the email again may succeed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendEmailErrorKind {
+    /// e.g. timeouts, throttling or outages of the provider.
+    Transient,
+    /// e.g. invalid or inactive recipients.
+    Permanent,
+}
+
+const TOO_MANY_REQUESTS: u16 = 429;
+const UNAUTHORIZED: u16 = 401;
+
+/// Postmark error codes blaming the recipient of the email, so sending to
+/// them again would fail too.
+const RECIPIENT_ERROR_CODES: [i64; 1] = [
+    406, // Inactive recipient.
+];
+
+/// Postmark error codes of failures of the provider or of the account, which
+/// any email would fail with.
+const PROVIDER_ERROR_CODES: [i64; 5] = [
+    10,  // Bad or missing server token.
+    100, // Maintenance.
+    400, // Sender signature not found.
+    401, // Sender signature not confirmed.
+    405, // Not allowed to send, e.g. out of credits.
+];
+
+impl SendEmailError {
+    /// Status of the request, if a response was received at all.
+    #[must_use]
+    pub fn http_status(&self) -> Option<u16> {
+        match self {
+            Self::Timeout(e) | Self::Request(e) => {
+                e.status().map(|i| i.as_u16())
+            }
+            Self::Rejected { http_status, .. }
+            | Self::Status { http_status }
+            | Self::Missing { http_status } => {
+                Some(*http_status)
+            }
+            Self::Batch(e) => e.http_status(),
+            Self::Address { .. }
+            | Self::Header { .. }
+            | Self::Message(_)
+            | Self::Smtp(_)
+            | Self::File(_)
+            | Self::CircuitOpen => None,
+        }
+    }
+
+    /// Whether the provider failed, rather than the email being invalid or
+    /// rejected, counting towards opening the circuit breaker.
+    #[must_use]
+    pub fn is_provider_failure(&self) -> bool {
+        let is_provider_status = |http_status| {
+            matches!(
+                http_status,
+                UNAUTHORIZED | TOO_MANY_REQUESTS | 500..
             )
-            .json(&request_body)
-            .send()
-            .await
-            .and_then(reqwest::Response::error_for_status)
-            .map(|_| ())
+        };
+
+        match self {
+            Self::Address { .. }
+            | Self::Header { .. }
+            | Self::Message(_)
+            | Self::CircuitOpen => false,
+            Self::Timeout(_)
+            | Self::Request(_)
+            | Self::Missing { .. }
+            | Self::File(_) => true,
+            Self::Smtp(e) => {
+                smtp_error_kind(e)
+                    == SendEmailErrorKind::Transient
+            }
+            Self::Rejected {
+                http_status,
+                error_code,
+                ..
+            } => {
+                is_provider_status(*http_status)
+                    || PROVIDER_ERROR_CODES
+                        .contains(error_code)
+            }
+            Self::Status { http_status } => {
+                is_provider_status(*http_status)
+            }
+            Self::Batch(e) => e.is_provider_failure(),
+        }
+    }
+
+    /// Only errors naming the recipient are permanent. Others, e.g. unknown
+    /// error codes or failures of the whole batch, are retried until dead
+    /// lettered.
+    #[must_use]
+    pub fn kind(&self) -> SendEmailErrorKind {
+        use SendEmailErrorKind as K;
+
+        match self {
+            Self::Address { .. } => K::Permanent,
+            Self::Smtp(e) => smtp_error_kind(e),
+            Self::Rejected { error_code, .. }
+                if RECIPIENT_ERROR_CODES
+                    .contains(error_code) =>
+            {
+                K::Permanent
+            }
+            Self::Timeout(_)
+            | Self::Request(_)
+            | Self::Rejected { .. }
+            | Self::Status { .. }
+            | Self::Missing { .. }
+            | Self::Batch(_)
+            | Self::Header { .. }
+            | Self::Message(_)
+            | Self::File(_)
+            | Self::CircuitOpen => K::Transient,
+        }
+    }
+}
+
+/// Only permanent replies about the mailbox (`55z`), e.g. of unknown users,
+/// blame the recipient. Others, e.g. rejected credentials, would fail for
+/// anyone.
+fn smtp_error_kind(
+    e: &lettre::transport::smtp::Error,
+) -> SendEmailErrorKind {
+    use lettre::transport::smtp::response::{
+        Category, Severity,
+    };
+
+    match e.status() {
+        Some(code)
+            if code.severity
+                == Severity::PermanentNegativeCompletion
+                && code.category
+                    == Category::MailSystem =>
+        {
+            SendEmailErrorKind::Permanent
+        }
+        _ => SendEmailErrorKind::Transient,
+    }
+}
+
+impl From<reqwest::Error> for SendEmailError {
+    fn from(value: reqwest::Error) -> Self {
+        if value.is_timeout() {
+            Self::Timeout(Arc::new(value))
+        } else {
+            Self::Request(Arc::new(value))
+        }
+    }
+}
+
+/// Custom header attached to an outgoing email, e.g. `List-Unsubscribe`.
+pub struct EmailHeader<P: RefHKT> {
+    pub name: K1<P, str>,
+    pub value: K1<P, str>,
+}
+
+impl<P: RefHKT> EmailHeader<P> {
+    pub fn new(
+        name: K1<P, str>,
+        value: K1<P, str>,
+    ) -> Self {
+        Self { name, value }
     }
 }
 
-// Prefer references over RC pointers.
-//#[derive(serde::Serialize)]
-//#[serde(rename_all = "PascalCase")]
-struct SendEmailRequest<P: RefHKT> {
-    from: K1<P, str>,
-    to: K1<P, str>,
-    subject: K1<P, str>,
-    html_body: K1<P, str>,
-    text_body: K1<P, str>,
+impl<P: SharedPointerHKT> Clone for EmailHeader<P> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            value: self.value.clone(),
+        }
+    }
+}
+
+/// Key & value attached to an email, e.g. the id of its subscriber.
+pub struct EmailMetadata<P: RefHKT> {
+    pub key: K1<P, str>,
+    pub value: K1<P, str>,
+}
+
+impl<P: SharedPointerHKT> Clone for EmailMetadata<P> {
+    fn clone(&self) -> Self {
+        Self {
+            key: self.key.clone(),
+            value: self.value.clone(),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
     use crate::domain::SubscriberEmail;
+    use crate::email_client::postmark::X_POSTMARK_SERVER_TOKEN_HEADER;
     use crate::email_client::{
-        EmailClient, X_POSTMARK_SERVER_TOKEN_HEADER,
+        CircuitBreaker, CircuitBreakerPolicy, CircuitState,
+        EmailClient, EmailHeader, PostmarkEmailSender,
+        SendEmailError, SendEmailErrorKind,
+    };
+    use crate::hkt::{
+        ArcHKT, RefHKT, SendHKT, SharedPointerHKT, SyncHKT,
     };
-    use crate::hkt::{K1, RcHKT, RefHKT, SharedPointerHKT};
+    use crate::startup::GlobalSharedPointer;
     use crate::utils::Pipe;
     use fake::Fake;
     use fake::faker::internet::en::SafeEmail;
     use fake::faker::lorem::en::{Paragraph, Sentence};
+    use std::num::NonZeroU32;
     use uuid::Uuid;
     use wiremock::matchers::{
-        any, header, header_exists, method, path,
+        any, body_partial_json, header, header_exists,
+        method, path,
     };
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
@@ -160,33 +874,34 @@ mod tests {
     }
 
     fn email_client<P: RefHKT>(
-        base_url: K1<P, str>,
-    ) -> EmailClient<P> {
+        base_url: String,
+    ) -> EmailClient<P, PostmarkEmailSender> {
         EmailClient::new(
-            base_url,
+            PostmarkEmailSender::new(
+                base_url,
+                //Random alpha-numerical-with-hyphens string
+                Uuid::new_v4().to_string().into(),
+                std::time::Duration::from_millis(200),
+            )
+            .pipe(GlobalSharedPointer::new),
             email(),
-            //Random alpha-numerical-with-hyphens string
-            Uuid::new_v4().to_string().pipe(P::from_string),
-            std::time::Duration::from_millis(200),
         )
     }
 
     #[tokio::test]
     async fn send_email_sends_expected_request() {
-        send_email_sends_expected_request_generic::<RcHKT>(
+        send_email_sends_expected_request_generic::<ArcHKT>(
         )
         .await;
     }
 
     async fn send_email_sends_expected_request_generic<
-        P: SharedPointerHKT,
+        P: SharedPointerHKT + SendHKT + SyncHKT,
     >() {
         // Arrange
         let mock_server = MockServer::start().await;
 
-        let email_client = email_client(
-            mock_server.uri().pipe(P::from_string),
-        );
+        let email_client = email_client(mock_server.uri());
 
         Mock::given(header_exists(
             X_POSTMARK_SERVER_TOKEN_HEADER,
@@ -214,25 +929,325 @@ mod tests {
         claims::assert_ok!(send_result);
     }
 
+    #[tokio::test]
+    async fn message_sends_sender_name_reply_to_tag_and_metadata()
+     {
+        message_sends_sender_name_reply_to_tag_and_metadata_generic::<
+            ArcHKT,
+        >()
+        .await;
+    }
+
+    async fn message_sends_sender_name_reply_to_tag_and_metadata_generic<
+        P: SharedPointerHKT + SendHKT + SyncHKT,
+    >() {
+        // Arrange
+        let mock_server = MockServer::start().await;
+
+        let sender = email::<P>();
+        let reply_to = email::<P>();
+        let email_client = EmailClient::new(
+            PostmarkEmailSender::new(
+                mock_server.uri(),
+                Uuid::new_v4().to_string().into(),
+                std::time::Duration::from_millis(200),
+            )
+            .pipe(GlobalSharedPointer::new),
+            sender.clone(),
+        )
+        .with_sender_name(P::from_str(
+            r#"The "Zero2Prod" team"#,
+        ))
+        .with_reply_to(reply_to.clone())
+        .with_broadcast_message_stream(P::from_str(
+            "broadcast",
+        ));
+
+        Mock::given(path("/email"))
+            .and(method("POST"))
+            .and(body_partial_json(serde_json::json!({
+                "From": format!(
+                    r#""The \"Zero2Prod\" team" <{}>"#,
+                    &*sender
+                ),
+                "ReplyTo": &*reply_to,
+                "Headers": [{
+                    "Name": "List-Unsubscribe",
+                    "Value": "<https://example.com/unsubscribe>"
+                }],
+                "Tag": "issue",
+                "Metadata": { "subscriber_id": "42" },
+                "MessageStream": "broadcast",
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        // Act
+        let send_result = email_client
+            .message(
+                email(),
+                subject().pipe(P::from_string),
+                content().pipe(P::from_string),
+                content().pipe(P::from_string),
+            )
+            .header(EmailHeader::new(
+                P::from_str("List-Unsubscribe"),
+                P::from_str(
+                    "<https://example.com/unsubscribe>",
+                ),
+            ))
+            .tag(P::from_str("issue"))
+            .metadata(
+                P::from_str("subscriber_id"),
+                P::from_str("42"),
+            )
+            .broadcast()
+            .send()
+            .await;
+
+        // Assert
+        claims::assert_ok!(send_result);
+    }
+
+    fn circuit_breaker(
+        failure_threshold: u32,
+    ) -> CircuitBreaker {
+        CircuitBreaker::new(CircuitBreakerPolicy {
+            failure_threshold: NonZeroU32::new(
+                failure_threshold,
+            )
+            .unwrap(),
+            open_duration_milliseconds: 60_000,
+        })
+    }
+
+    #[tokio::test]
+    async fn open_circuit_fails_without_calling_the_provider()
+     {
+        open_circuit_fails_without_calling_the_provider_generic::<
+            ArcHKT,
+        >()
+        .await;
+    }
+
+    async fn open_circuit_fails_without_calling_the_provider_generic<
+        P: SharedPointerHKT + SendHKT + SyncHKT,
+    >() {
+        // Arrange
+        let mock_server = MockServer::start().await;
+
+        let email_client =
+            email_client::<P>(mock_server.uri())
+                .with_circuit_breaker(circuit_breaker(2));
+
+        Mock::given(any())
+            .respond_with(ResponseTemplate::new(503))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        // Act
+        for _ in 0..2 {
+            claims::assert_err!(
+                email_client
+                    .send_email(
+                        email(),
+                        subject().pipe(P::from_string),
+                        content().pipe(P::from_string),
+                        content().pipe(P::from_string),
+                    )
+                    .await
+            );
+        }
+        let send_result = email_client
+            .send_email(
+                email(),
+                subject().pipe(P::from_string),
+                content().pipe(P::from_string),
+                content().pipe(P::from_string),
+            )
+            .await;
+
+        // Assert
+        claims::assert_matches!(
+            send_result,
+            Err(SendEmailError::CircuitOpen)
+        );
+        assert_eq!(
+            email_client.circuit_breaker().state(),
+            CircuitState::Open
+        );
+        assert!(email_client.unavailable_for().is_some());
+    }
+
+    #[tokio::test]
+    async fn open_circuit_fails_over_to_the_fallback() {
+        open_circuit_fails_over_to_the_fallback_generic::<
+            ArcHKT,
+        >()
+        .await;
+    }
+
+    async fn open_circuit_fails_over_to_the_fallback_generic<
+        P: SharedPointerHKT + SendHKT + SyncHKT,
+    >() {
+        // Arrange
+        let mock_server = MockServer::start().await;
+        let fallback_mock_server =
+            MockServer::start().await;
+
+        let email_client =
+            email_client::<P>(mock_server.uri())
+                .with_circuit_breaker(circuit_breaker(1))
+                .with_fallback(
+                    PostmarkEmailSender::new(
+                        fallback_mock_server.uri(),
+                        Uuid::new_v4().to_string().into(),
+                        std::time::Duration::from_millis(
+                            200,
+                        ),
+                    )
+                    .pipe(GlobalSharedPointer::new),
+                );
+
+        Mock::given(any())
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(path("/email/batch"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(
+                    serde_json::json!([
+                        { "ErrorCode": 0, "Message": "OK" },
+                    ]),
+                ),
+            )
+            .expect(1)
+            .mount(&fallback_mock_server)
+            .await;
+
+        let batch = || {
+            vec![
+                email_client
+                    .message(
+                        email(),
+                        subject().pipe(P::from_string),
+                        content().pipe(P::from_string),
+                        content().pipe(P::from_string),
+                    )
+                    .build(),
+            ]
+        };
+
+        // Act
+        let failed_results =
+            email_client.send_batch(batch()).await;
+        let results =
+            email_client.send_batch(batch()).await;
+
+        // Assert
+        claims::assert_err!(&failed_results[0]);
+        claims::assert_ok!(&results[0]);
+        assert_eq!(email_client.unavailable_for(), None);
+    }
+
+    #[tokio::test]
+    async fn send_batch_returns_the_result_of_each_email() {
+        send_batch_returns_the_result_of_each_email_generic::<
+            ArcHKT,
+        >()
+        .await;
+    }
+
+    async fn send_batch_returns_the_result_of_each_email_generic<
+        P: SharedPointerHKT + SendHKT + SyncHKT,
+    >() {
+        // Arrange
+        let mock_server = MockServer::start().await;
+
+        let email_client = email_client(mock_server.uri());
+
+        Mock::given(path("/email/batch"))
+            .and(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(
+                    serde_json::json!([
+                        {
+                            "ErrorCode": 0,
+                            "Message": "OK",
+                            "MessageID": "b7bc2f4a"
+                        },
+                        {
+                            "ErrorCode": 406,
+                            "Message": "Inactive recipient"
+                        },
+                    ]),
+                ),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let batch_email = || {
+            email_client
+                .message(
+                    email(),
+                    subject().pipe(P::from_string),
+                    content().pipe(P::from_string),
+                    content().pipe(P::from_string),
+                )
+                .build()
+        };
+
+        // Act
+        let results = email_client
+            .send_batch(vec![
+                batch_email(),
+                batch_email(),
+                batch_email(),
+            ])
+            .await;
+
+        // Assert
+        assert_eq!(results.len(), 3);
+        let sent_email = claims::assert_ok!(&results[0]);
+        assert_eq!(
+            sent_email.message_id.as_deref(),
+            Some("b7bc2f4a")
+        );
+        claims::assert_matches!(
+            &results[1],
+            Err(SendEmailError::Rejected {
+                error_code: 406,
+                ..
+            })
+        );
+        claims::assert_matches!(
+            &results[2],
+            Err(SendEmailError::Missing { .. })
+        );
+    }
+
     #[tokio::test]
     async fn send_email_fails_if_server_returns_500() {
         send_email_fails_if_server_returns_code_n_generic::<
-            RcHKT,
+            ArcHKT,
         >(500)
         .await;
     }
 
     async fn send_email_fails_if_server_returns_code_n_generic<
-        P: SharedPointerHKT,
+        P: SharedPointerHKT + SendHKT + SyncHKT,
     >(
         n: u16,
     ) {
         // Arrange
         let mock_server = MockServer::start().await;
 
-        let email_client = email_client(
-            mock_server.uri().pipe(P::from_string),
-        );
+        let email_client = email_client(mock_server.uri());
 
         Mock::given(any())
             .respond_with(ResponseTemplate::new(n))
@@ -254,25 +1269,164 @@ mod tests {
         claims::assert_err!(send_result);
     }
 
+    #[tokio::test]
+    async fn send_email_parses_postmark_error_of_rejected_recipient()
+     {
+        send_email_parses_postmark_error_of_rejected_recipient_generic::<
+            ArcHKT,
+        >()
+        .await;
+    }
+
+    async fn send_email_parses_postmark_error_of_rejected_recipient_generic<
+        P: SharedPointerHKT + SendHKT + SyncHKT,
+    >() {
+        // Arrange
+        let mock_server = MockServer::start().await;
+
+        let email_client = email_client(mock_server.uri());
+
+        Mock::given(any())
+            .respond_with(
+                ResponseTemplate::new(422).set_body_json(
+                    serde_json::json!({
+                        "ErrorCode": 406,
+                        "Message": "Inactive recipient"
+                    }),
+                ),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        // Act
+        let send_result = email_client
+            .send_email(
+                email(),
+                subject().pipe(P::from_string),
+                content().pipe(P::from_string),
+                content().pipe(P::from_string),
+            )
+            .await;
+
+        // Assert
+        let e = claims::assert_err!(send_result);
+        claims::assert_matches!(
+            &e,
+            SendEmailError::Rejected {
+                http_status: 422,
+                error_code: 406,
+                ..
+            }
+        );
+        assert_eq!(e.kind(), SendEmailErrorKind::Permanent);
+    }
+
+    #[tokio::test]
+    async fn throttling_and_server_errors_are_transient() {
+        throttling_and_server_errors_are_transient_generic::<
+            ArcHKT,
+        >()
+        .await;
+    }
+
+    async fn throttling_and_server_errors_are_transient_generic<
+        P: SharedPointerHKT + SendHKT + SyncHKT,
+    >() {
+        for n in [429, 500, 503] {
+            // Arrange
+            let mock_server = MockServer::start().await;
+
+            let email_client =
+                email_client(mock_server.uri());
+
+            Mock::given(any())
+                .respond_with(ResponseTemplate::new(n))
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+
+            // Act
+            let send_result = email_client
+                .send_email(
+                    email(),
+                    subject().pipe(P::from_string),
+                    content().pipe(P::from_string),
+                    content().pipe(P::from_string),
+                )
+                .await;
+
+            // Assert
+            let e = claims::assert_err!(send_result);
+            assert_eq!(e.http_status(), Some(n));
+            assert_eq!(
+                e.kind(),
+                SendEmailErrorKind::Transient
+            );
+        }
+    }
+
+    #[test]
+    fn account_wide_rejections_are_transient() {
+        let e = SendEmailError::Rejected {
+            http_status: 422,
+            error_code: 405,
+            message: "Not allowed to send".to_owned(),
+        };
+
+        assert_eq!(e.kind(), SendEmailErrorKind::Transient);
+    }
+
+    #[test]
+    fn only_rejections_naming_the_recipient_are_permanent()
+    {
+        let inactive_recipient = SendEmailError::Rejected {
+            http_status: 422,
+            error_code: 406,
+            message: "Inactive recipient".to_owned(),
+        };
+
+        for e in [
+            SendEmailError::Status { http_status: 403 },
+            SendEmailError::Status { http_status: 404 },
+            SendEmailError::Status { http_status: 413 },
+            SendEmailError::Rejected {
+                http_status: 422,
+                error_code: 300,
+                message: "Invalid email request".to_owned(),
+            },
+            SendEmailError::Batch(Box::new(
+                inactive_recipient.clone(),
+            )),
+        ] {
+            assert_eq!(
+                e.kind(),
+                SendEmailErrorKind::Transient
+            );
+        }
+        assert_eq!(
+            inactive_recipient.kind(),
+            SendEmailErrorKind::Permanent
+        );
+    }
+
     #[tokio::test]
     async fn send_email_fails_if_server_takes_3_minutes() {
         send_email_fails_if_server_takes_too_long_generic::<
-            RcHKT,
+            ArcHKT,
         >(180)
         .await;
     }
 
     async fn send_email_fails_if_server_takes_too_long_generic<
-        P: SharedPointerHKT,
+        P: SharedPointerHKT + SendHKT + SyncHKT,
     >(
         secs: u64,
     ) {
         // Arrange
         let mock_server = MockServer::start().await;
 
-        let email_client = email_client(
-            mock_server.uri().pipe(P::from_string),
-        );
+        let email_client = email_client(mock_server.uri());
 
         Mock::given(any())
             .respond_with(
@@ -295,6 +1449,11 @@ mod tests {
             .await;
 
         // Assert
-        claims::assert_err!(send_result);
+        let e = claims::assert_err!(send_result);
+        claims::assert_matches!(
+            e,
+            SendEmailError::Timeout(_)
+        );
+        assert_eq!(e.kind(), SendEmailErrorKind::Transient);
     }
 }
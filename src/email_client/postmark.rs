@@ -0,0 +1,276 @@
+use reqwest::Client;
+use secrecy::{ExposeSecret, SecretString};
+
+use super::{
+    EmailHeader, EmailMetadata, EmailSender,
+    MAX_BATCH_SIZE, OutgoingEmail, SendEmailError,
+    SentEmail,
+};
+use crate::hkt::{
+    K1, RefHKT, SendHKT, SharedPointerHKT, SyncHKT,
+};
+use crate::utils::Pipe;
+
+pub(super) const X_POSTMARK_SERVER_TOKEN_HEADER: &str =
+    "X-Postmark-Server-Token";
+
+/// Sends emails through the JSON API of Postmark.
+#[derive(Debug, Clone)]
+pub struct PostmarkEmailSender {
+    http_client: Client,
+    base_url: String,
+    authorization_token: SecretString,
+}
+
+impl PostmarkEmailSender {
+    #[must_use]
+    pub fn new(
+        base_url: String,
+        authorization_token: SecretString,
+        timeout: std::time::Duration,
+    ) -> Self {
+        Self {
+            http_client: Client::builder()
+                .timeout(timeout)
+                .build()
+                .unwrap(),
+            base_url,
+            authorization_token,
+        }
+    }
+
+    async fn send_batch_request<P: SharedPointerHKT>(
+        &self,
+        emails: Vec<OutgoingEmail<P>>,
+    ) -> Vec<Result<SentEmail, SendEmailError>> {
+        let url = format!("{}/email/batch", self.base_url);
+        let n_emails = emails.len();
+
+        let request_body = emails
+            .into_iter()
+            .map(SendEmailRequest::new)
+            .collect::<Vec<_>>();
+
+        let response = match self
+            .http_client
+            .post(&url)
+            .header(
+                X_POSTMARK_SERVER_TOKEN_HEADER,
+                self.authorization_token.expose_secret(),
+            )
+            .json(&request_body)
+            .send()
+            .await
+            .pipe(error_for_status)
+            .await
+        {
+            Ok(response) => {
+                let http_status =
+                    response.status().as_u16();
+
+                response
+                    .json::<Vec<SendBatchEmailResponse>>()
+                    .await
+                    .map(|i| (http_status, i))
+                    .map_err(SendEmailError::from)
+            }
+            Err(e) => Err(e),
+        };
+
+        match response {
+            Ok((http_status, responses)) => {
+                let mut responses = responses.into_iter();
+
+                (0..n_emails)
+                    .map(|_| match responses.next() {
+                        Some(SendBatchEmailResponse {
+                            error_code: 0,
+                            message_id,
+                            ..
+                        }) => Ok(SentEmail {
+                            http_status: Some(http_status),
+                            message_id,
+                        }),
+                        Some(SendBatchEmailResponse {
+                            error_code,
+                            message,
+                            ..
+                        }) => {
+                            Err(SendEmailError::Rejected {
+                                http_status,
+                                error_code,
+                                message,
+                            })
+                        }
+                        None => {
+                            Err(SendEmailError::Missing {
+                                http_status,
+                            })
+                        }
+                    })
+                    .collect()
+            }
+            // The whole batch failed, along with each of its emails.
+            Err(e) => (0..n_emails)
+                .map(|_| {
+                    Err(SendEmailError::Batch(Box::new(
+                        e.clone(),
+                    )))
+                })
+                .collect(),
+        }
+    }
+}
+
+impl EmailSender for PostmarkEmailSender {
+    async fn send_email<
+        P: SharedPointerHKT + SendHKT + SyncHKT,
+    >(
+        &self,
+        email: OutgoingEmail<P>,
+    ) -> Result<SentEmail, SendEmailError> {
+        let url = format!("{}/email", self.base_url);
+
+        let response = self
+            .http_client
+            .post(&url)
+            .header(
+                X_POSTMARK_SERVER_TOKEN_HEADER,
+                self.authorization_token.expose_secret(),
+            )
+            .json(&SendEmailRequest::new(email))
+            .send()
+            .await
+            .pipe(error_for_status)
+            .await?;
+
+        let http_status = response.status().as_u16();
+
+        // The body only identifies the email, so failing to parse it does
+        // not fail the sending.
+        let message_id = response
+            .json::<SendEmailResponse>()
+            .await
+            .ok()
+            .and_then(|i| i.message_id);
+
+        Ok(SentEmail {
+            http_status: Some(http_status),
+            message_id,
+        })
+    }
+
+    /// Emails are sent in as few requests as the batch endpoint allows.
+    async fn send_batch<
+        P: SharedPointerHKT + SendHKT + SyncHKT,
+    >(
+        &self,
+        emails: Vec<OutgoingEmail<P>>,
+    ) -> Vec<Result<SentEmail, SendEmailError>> {
+        let mut results = Vec::with_capacity(emails.len());
+        let mut emails = emails.into_iter().peekable();
+
+        while emails.peek().is_some() {
+            let chunk = emails
+                .by_ref()
+                .take(MAX_BATCH_SIZE)
+                .collect::<Vec<_>>();
+
+            results.extend(
+                self.send_batch_request(chunk).await,
+            );
+        }
+
+        results
+    }
+}
+
+/// Error responses are parsed into the error Postmark describes in their
+/// body, if any.
+async fn error_for_status(
+    response: Result<reqwest::Response, reqwest::Error>,
+) -> Result<reqwest::Response, SendEmailError> {
+    let response = response?;
+    let http_status = response.status();
+
+    if http_status.is_success() {
+        return Ok(response);
+    }
+
+    let http_status = http_status.as_u16();
+
+    match response.json::<PostmarkErrorResponse>().await {
+        Ok(PostmarkErrorResponse {
+            error_code,
+            message,
+        }) => SendEmailError::Rejected {
+            http_status,
+            error_code,
+            message,
+        },
+        Err(_) => SendEmailError::Status { http_status },
+    }
+    .pipe(Err)
+}
+
+/// Body of Postmark error responses.
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PostmarkErrorResponse {
+    error_code: i64,
+    message: String,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SendEmailResponse {
+    #[serde(rename = "MessageID")]
+    message_id: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SendBatchEmailResponse {
+    error_code: i64,
+    message: String,
+    #[serde(rename = "MessageID")]
+    message_id: Option<String>,
+}
+
+// Prefer references over RC pointers.
+//#[derive(serde::Serialize)]
+//#[serde(rename_all = "PascalCase")]
+pub(super) struct SendEmailRequest<P: RefHKT> {
+    pub(super) from: K1<P, str>,
+    pub(super) to: K1<P, str>,
+    pub(super) subject: K1<P, str>,
+    pub(super) html_body: K1<P, str>,
+    pub(super) text_body: K1<P, str>,
+    pub(super) reply_to: Option<K1<P, str>>,
+    pub(super) headers: Vec<EmailHeader<P>>,
+    pub(super) tag: Option<K1<P, str>>,
+    pub(super) metadata: Vec<EmailMetadata<P>>,
+    pub(super) message_stream: Option<K1<P, str>>,
+}
+
+impl<P: SharedPointerHKT> SendEmailRequest<P> {
+    fn new(email: OutgoingEmail<P>) -> Self {
+        Self {
+            from: match email.from.name {
+                Some(_) => {
+                    P::from_string(email.from.to_string())
+                }
+                None => email.from.email.into(),
+            },
+            to: email.recipient.into(),
+            subject: email.subject,
+            html_body: email.html_content,
+            text_body: email.text_content,
+            reply_to: email.reply_to.map(Into::into),
+            headers: email.headers,
+            tag: email.tag,
+            metadata: email.metadata,
+            message_stream: email.message_stream,
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use std::{
+    collections::HashMap,
+    num::NonZeroU32,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::utils::Pipe;
+
+/// Maximum number of emails sent per second and per minute, either limit
+/// being optional.
+#[derive(
+    Debug, Clone, Copy, Default, serde::Deserialize,
+)]
+pub struct SendRate {
+    pub per_second: Option<NonZeroU32>,
+    pub per_minute: Option<NonZeroU32>,
+}
+
+/// Send rate applied on top of the global one for recipients of a domain.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct DomainSendRate {
+    pub domain: String,
+    pub send_rate: SendRate,
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    tokens_per_second: f64,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(
+        limit: NonZeroU32,
+        period: Duration,
+        now: Instant,
+    ) -> Self {
+        let capacity = f64::from(limit.get());
+
+        Self {
+            capacity,
+            tokens_per_second: capacity
+                / period.as_secs_f64(),
+            tokens: capacity,
+            refilled_at: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed =
+            now.saturating_duration_since(self.refilled_at);
+
+        self.tokens = self
+            .tokens_per_second
+            .mul_add(elapsed.as_secs_f64(), self.tokens)
+            .min(self.capacity);
+        self.refilled_at = now;
+    }
+
+    /// Time until a token is available, zero if one already is.
+    fn wait_time(&self) -> Duration {
+        Duration::from_secs_f64(
+            ((1.0 - self.tokens) / self.tokens_per_second)
+                .max(0.0),
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+struct Buckets(Vec<TokenBucket>);
+
+impl Buckets {
+    fn new(send_rate: SendRate, now: Instant) -> Self {
+        [
+            (send_rate.per_second, Duration::from_secs(1)),
+            (send_rate.per_minute, Duration::from_mins(1)),
+        ]
+        .into_iter()
+        .filter_map(|(limit, period)| {
+            limit.map(|i| TokenBucket::new(i, period, now))
+        })
+        .collect::<Vec<_>>()
+        .pipe(Self)
+    }
+}
+
+#[derive(Debug, Default)]
+struct RateLimiterState {
+    global: Buckets,
+    per_domain: HashMap<String, Buckets>,
+}
+
+/// Token buckets shared by every sender of a client, globally and per domain
+/// of the recipient.
+#[derive(Debug, Default)]
+pub struct RateLimiter(Mutex<RateLimiterState>);
+
+impl RateLimiter {
+    #[must_use]
+    pub fn new(
+        send_rate: SendRate,
+        send_rate_per_domain: &[DomainSendRate],
+    ) -> Self {
+        let now = Instant::now();
+
+        RateLimiterState {
+            global: Buckets::new(send_rate, now),
+            per_domain: send_rate_per_domain
+                .iter()
+                .map(|i| {
+                    (
+                        i.domain.to_lowercase(),
+                        Buckets::new(i.send_rate, now),
+                    )
+                })
+                .collect(),
+        }
+        .pipe(Mutex::new)
+        .pipe(Self)
+    }
+
+    /// Waits until an email can be sent to the recipient.
+    pub async fn acquire(&self, recipient: &str) {
+        while let Err(wait_time) =
+            self.try_acquire(recipient, Instant::now())
+        {
+            tokio::time::sleep(wait_time).await;
+        }
+    }
+
+    /// Takes a token of every applicable bucket, or none along with the time
+    /// to wait for all of them to have one.
+    fn try_acquire(
+        &self,
+        recipient: &str,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let mut state = self.0.lock().unwrap_or_else(
+            std::sync::PoisonError::into_inner,
+        );
+        let RateLimiterState { global, per_domain } =
+            &mut *state;
+
+        let domain = recipient
+            .rsplit_once('@')
+            .map(|(_, domain)| domain.to_lowercase());
+        let domain_buckets = domain
+            .and_then(|i| per_domain.get_mut(&i))
+            .map(|i| i.0.as_mut_slice())
+            .unwrap_or_default();
+
+        let buckets =
+            global.0.iter_mut().chain(domain_buckets);
+
+        let mut wait_time = Duration::ZERO;
+        let mut acquired = Vec::new();
+
+        for bucket in buckets {
+            bucket.refill(now);
+            wait_time = wait_time.max(bucket.wait_time());
+            acquired.push(bucket);
+        }
+
+        if !wait_time.is_zero() {
+            return Err(wait_time);
+        }
+
+        for bucket in acquired {
+            bucket.tokens -= 1.0;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        num::NonZeroU32,
+        time::{Duration, Instant},
+    };
+
+    use super::{DomainSendRate, RateLimiter, SendRate};
+
+    fn send_rate(per_second: u32) -> SendRate {
+        SendRate {
+            per_second: NonZeroU32::new(per_second),
+            per_minute: None,
+        }
+    }
+
+    #[test]
+    fn burst_up_to_the_limit_then_waits_for_a_refill() {
+        let limiter = RateLimiter::new(send_rate(2), &[]);
+        let now = Instant::now();
+
+        claims::assert_ok!(
+            limiter.try_acquire("a@example.com", now)
+        );
+        claims::assert_ok!(
+            limiter.try_acquire("b@example.com", now)
+        );
+        let wait_time = claims::assert_err!(
+            limiter.try_acquire("c@example.com", now)
+        );
+        assert!(wait_time <= Duration::from_millis(500));
+
+        claims::assert_ok!(limiter.try_acquire(
+            "c@example.com",
+            now + wait_time + Duration::from_millis(1)
+        ));
+    }
+
+    #[test]
+    fn domain_limit_applies_to_its_recipients_only() {
+        let limiter = RateLimiter::new(
+            SendRate::default(),
+            &[DomainSendRate {
+                domain: "gmail.com".to_owned(),
+                send_rate: send_rate(1),
+            }],
+        );
+        let now = Instant::now();
+
+        claims::assert_ok!(
+            limiter.try_acquire("a@gmail.com", now)
+        );
+        claims::assert_err!(
+            limiter.try_acquire("b@GMAIL.com", now)
+        );
+        claims::assert_ok!(
+            limiter.try_acquire("c@example.com", now)
+        );
+    }
+
+    #[test]
+    fn no_limit_never_waits() {
+        let limiter = RateLimiter::default();
+        let now = Instant::now();
+
+        for _ in 0..100 {
+            claims::assert_ok!(
+                limiter.try_acquire("a@example.com", now)
+            );
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Tokio1Executor,
+};
+use secrecy::{ExposeSecret, SecretString};
+use std::sync::Arc;
+
+use super::message::{build_message, message_id};
+use super::{
+    EmailSender, OutgoingEmail, SendEmailError, SentEmail,
+};
+use crate::hkt::{SendHKT, SharedPointerHKT, SyncHKT};
+
+/// Sends emails to an SMTP relay, e.g. of a provider without an API.
+#[derive(Clone)]
+pub struct SmtpEmailSender {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpEmailSender {
+    /// Upgrades the connection with STARTTLS before authenticating, so the
+    /// credentials are never sent in the clear.
+    pub fn new(
+        host: &str,
+        port: u16,
+        username: String,
+        password: &SecretString,
+        timeout: std::time::Duration,
+    ) -> Result<Self, lettre::transport::smtp::Error> {
+        let transport =
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(
+                host,
+            )?
+            .port(port)
+            .credentials(Credentials::new(
+                username,
+                password.expose_secret().to_owned(),
+            ))
+            .timeout(Some(timeout))
+            .build();
+
+        Ok(Self { transport })
+    }
+}
+
+impl std::fmt::Debug for SmtpEmailSender {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        f.debug_struct("SmtpEmailSender")
+            .finish_non_exhaustive()
+    }
+}
+
+impl EmailSender for SmtpEmailSender {
+    async fn send_email<
+        P: SharedPointerHKT + SendHKT + SyncHKT,
+    >(
+        &self,
+        email: OutgoingEmail<P>,
+    ) -> Result<SentEmail, SendEmailError> {
+        let message = build_message(&email)?;
+        let message_id = message_id(&message);
+
+        self.transport.send(message).await.map_err(
+            |e| SendEmailError::Smtp(Arc::new(e)),
+        )?;
+
+        Ok(SentEmail {
+            http_status: None,
+            message_id,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::Fake;
+    use fake::faker::internet::en::SafeEmail;
+    use secrecy::SecretString;
+
+    use crate::configuration::EmailTransportSettings;
+    use crate::domain::SubscriberEmail;
+    use crate::email_client::{
+        EmailSender, OutgoingEmail, SendEmailError,
+        SendEmailErrorKind, SenderAddress, SmtpEmailSender,
+    };
+    use crate::hkt::{ArcHKT, RefHKT};
+    use crate::utils::Pipe;
+
+    fn email() -> SubscriberEmail<ArcHKT> {
+        SafeEmail()
+            .fake::<String>()
+            .pipe(ArcHKT::from_string)
+            .pipe(SubscriberEmail::try_from)
+            .unwrap()
+    }
+
+    #[test]
+    fn smtp_transport_defaults_to_the_submission_port() {
+        // Act
+        let settings = serde_json::from_value::<
+            EmailTransportSettings,
+        >(serde_json::json!({
+            "kind": "smtp",
+            "host": "smtp.example.com",
+            "username": "username",
+            "password": "password",
+        }))
+        .unwrap();
+
+        // Assert
+        let EmailTransportSettings::Smtp(smtp) = settings
+        else {
+            panic!("Expected SMTP transport settings.");
+        };
+        assert_eq!(smtp.host, "smtp.example.com");
+        assert_eq!(smtp.port, 587);
+        claims::assert_ok!(SmtpEmailSender::new(
+            &smtp.host,
+            smtp.port,
+            smtp.username,
+            &smtp.password,
+            std::time::Duration::from_secs(1),
+        ));
+    }
+
+    #[tokio::test]
+    async fn send_email_fails_transiently_if_the_relay_is_unreachable()
+     {
+        // Arrange
+        let port =
+            std::net::TcpListener::bind("127.0.0.1:0")
+                .unwrap()
+                .local_addr()
+                .unwrap()
+                .port();
+        let email_sender = SmtpEmailSender::new(
+            "127.0.0.1",
+            port,
+            "username".to_owned(),
+            &SecretString::from("password"),
+            std::time::Duration::from_secs(1),
+        )
+        .unwrap();
+
+        // Act
+        let sent_email = email_sender
+            .send_email(OutgoingEmail {
+                from: SenderAddress {
+                    email: email(),
+                    name: None,
+                },
+                recipient: email(),
+                subject: ArcHKT::from_str("Subject"),
+                html_content: ArcHKT::from_str(
+                    "<p>Html body</p>",
+                ),
+                text_content: ArcHKT::from_str("Text body"),
+                reply_to: None,
+                headers: Vec::new(),
+                tag: None,
+                metadata: Vec::new(),
+                message_stream: None,
+            })
+            .await;
+
+        // Assert
+        let e = claims::assert_err!(sent_email);
+        assert!(matches!(e, SendEmailError::Smtp(_)));
+        assert_eq!(e.kind(), SendEmailErrorKind::Transient);
+    }
+}
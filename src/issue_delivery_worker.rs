@@ -1,34 +1,93 @@
 use std::{ops::ControlFlow, time::Duration};
 
+use chrono::{DateTime, Utc};
+
 use crate::hkt::{RefHKT as _, SendHKT, SyncHKT};
 use eyre::Context;
 use lazy_errors::{IntoEyreResult, OrStash};
 use uuid::Uuid;
 
-use crate::startup::GlobalSharedPointer;
+use crate::startup::{
+    ApplicationBaseUrl, GlobalSharedPointer,
+};
 use crate::{
-    configuration::Settings,
+    configuration::{
+        IssueDeliverySettings, Settings, UnsubscribeSecret,
+    },
     database::transactional::{
-        issue_delivery_queue::IssueDeliveryQueueRepository,
+        issue_delivery_queue::{
+            EmailDeliveryStatus,
+            IssueDeliveryQueueRepository, NewEmailDelivery,
+        },
         newsletters::{
             NewsletterContent, NewslettersRepository,
         },
+        notifications::{
+            IssueDeliveryQueueChannel, NotificationListener,
+        },
+        subscriptions::{
+            SubscriptionsRepository, SuppressionReason,
+        },
         unit_of_work::{BeginUnitOfWork, UnitOfWork as _},
     },
-    domain::SubscriberEmail,
-    email_client::EmailClient,
+    domain::{
+        NewsletterTemplate, SubscriberEmail,
+        TemplateContext, UnsubscribeToken,
+    },
+    email_client::{
+        EmailClient, EmailHeader, EmailSender,
+        MAX_BATCH_SIZE, OutgoingEmail, SendEmailError,
+        SendEmailErrorKind, SentEmail,
+    },
     hkt::{
         K1, SharedPointerHKT,
         traversable::traverse_result_future,
     },
+    routes::archive_issue_link,
     utils::Pipe as _,
+    worker::{RetryPolicy, wait_for_notification},
 };
 
 pub struct IssueDeliveryRecord {
     pub newsletter_issue_id: Uuid,
     pub subscriber_email: String,
+    pub n_retries: i32,
+    pub subscriber_id: Uuid,
+    pub subscriber_name: String,
+    pub confirmed_at: Option<DateTime<Utc>>,
+}
+
+/// Workers drain the queue concurrently, each waiting on its own when the
+/// queue is empty or failing.
+#[derive(Debug, Clone, Copy)]
+pub struct WorkerPool {
+    pub concurrency: usize,
+    /// Idle workers are woken up by notifications, polling the queue this
+    /// often only as a fallback.
+    pub idle_delay: Duration,
+    pub error_delay: Duration,
+}
+
+impl From<&IssueDeliverySettings> for WorkerPool {
+    fn from(value: &IssueDeliverySettings) -> Self {
+        Self {
+            concurrency: value.concurrency,
+            idle_delay: value.idle_delay(),
+            error_delay: value.error_delay(),
+        }
+    }
 }
 
+const CONFIRM_DATE_FORMAT: &str = "%Y-%m-%d";
+const NEWSLETTER_ISSUE_ID_METADATA: &str =
+    "newsletter_issue_id";
+const SUBSCRIBER_ID_METADATA: &str = "subscriber_id";
+const LIST_UNSUBSCRIBE_HEADER: &str = "List-Unsubscribe";
+const LIST_UNSUBSCRIBE_POST_HEADER: &str =
+    "List-Unsubscribe-Post";
+const LIST_UNSUBSCRIBE_POST_VALUE: &str =
+    "List-Unsubscribe=One-Click";
+
 pub trait IssueDeliveryWorkerDependencyAlias {
     type P: SharedPointerHKT + SendHKT + SyncHKT;
     type B: BeginUnitOfWork;
@@ -36,16 +95,27 @@ pub trait IssueDeliveryWorkerDependencyAlias {
         <Self::B as BeginUnitOfWork>::UnitOfWork>;
     type I: IssueDeliveryQueueRepository<UnitOfWork =
         <Self::B as BeginUnitOfWork>::UnitOfWork>;
+    type S: SubscriptionsRepository<UnitOfWork =
+        <Self::B as BeginUnitOfWork>::UnitOfWork>;
+    type L: NotificationListener<IssueDeliveryQueueChannel>;
+    type E: EmailSender;
 }
 
 pub struct IssueDeliveryWorkerDependencies<'a, D>
 where
     D: IssueDeliveryWorkerDependencyAlias,
 {
-    pub email_client: &'a EmailClient<D::P>,
+    pub email_client: &'a EmailClient<D::P, D::E>,
+    pub application_base_url: &'a ApplicationBaseUrl<D::P>,
+    pub unsubscribe_secret: &'a UnsubscribeSecret<D::P>,
+    pub retry_policy: &'a RetryPolicy,
+    /// Recipients acquired and sent to at once.
+    pub batch_size: usize,
     pub begin_unit_of_work: &'a D::B,
     pub issue_delivery_queue_repository: &'a D::I,
     pub newsletters_repository: &'a D::N,
+    /// Suppresses recipients the email provider permanently rejects.
+    pub subscriptions_repository: &'a D::S,
 }
 
 impl<D: IssueDeliveryWorkerDependencyAlias> Clone
@@ -54,11 +124,17 @@ impl<D: IssueDeliveryWorkerDependencyAlias> Clone
     fn clone(&self) -> Self {
         Self {
             email_client: self.email_client,
+            application_base_url: self.application_base_url,
+            unsubscribe_secret: self.unsubscribe_secret,
+            retry_policy: self.retry_policy,
+            batch_size: self.batch_size,
             begin_unit_of_work: self.begin_unit_of_work,
             issue_delivery_queue_repository: self
                 .issue_delivery_queue_repository,
             newsletters_repository: self
                 .newsletters_repository,
+            subscriptions_repository: self
+                .subscriptions_repository,
         }
     }
 }
@@ -71,43 +147,64 @@ pub async fn run_worker_until_stopped<
     >,
     begin_unit_of_work: GlobalSharedPointer<D::B>,
     newsletters_repository: GlobalSharedPointer<D::N>,
+    subscriptions_repository: GlobalSharedPointer<D::S>,
+    issue_delivery_queue_listener: GlobalSharedPointer<
+        D::L,
+    >,
+    // Shared with the application, along with its send rate limits.
+    email_client: EmailClient<D::P, D::E>,
     configuration: Settings<D::P>,
 ) -> Result<(), eyre::Report> {
-    let sender_email = configuration
-        .email_client
-        .sender()
-        .context("Invalid sender email address.")?;
-
-    let timeout = configuration.email_client.timeout();
-    let email_client = EmailClient::new(
-        configuration
-            .email_client
-            .base_url
-            .pipe_ref(K1::clone),
-        sender_email,
-        configuration
-            .email_client
-            .authorization_token
-            .pipe_ref(K1::clone),
-        timeout,
+    let application_base_url = ApplicationBaseUrl(
+        configuration.application.base_url.clone(),
+    );
+    let unsubscribe_secret = configuration
+        .application
+        .unsubscribe_secret
+        .as_ref()
+        .clone();
+    let retry_policy = RetryPolicy::from(
+        configuration.issue_delivery.as_ref(),
     );
 
     let dependencies = IssueDeliveryWorkerDependencies::<D> {
         email_client: &email_client,
+        application_base_url: &application_base_url,
+        unsubscribe_secret: &unsubscribe_secret,
+        retry_policy: &retry_policy,
+        batch_size: configuration
+            .issue_delivery
+            .batch_size
+            .clamp(1, MAX_BATCH_SIZE),
         begin_unit_of_work: &begin_unit_of_work,
         issue_delivery_queue_repository:
             &issue_delivery_queue_repository,
         newsletters_repository: &newsletters_repository,
+        subscriptions_repository: &subscriptions_repository,
     };
 
-    let iterator = get_newsletter_sending_worker_iterator(
-        &dependencies,
-    )
-    .await;
+    let worker_pool = WorkerPool::from(
+        configuration.issue_delivery.as_ref(),
+    );
 
-    for task in iterator {
-        task.await;
-    }
+    // Tasks are acquired with `SKIP LOCKED`, so workers never send to the same
+    // recipient, whether in this process or another.
+    (0..worker_pool.concurrency.max(1))
+        .map(|_| async {
+            let iterator =
+                get_newsletter_sending_worker_iterator(
+                    &dependencies,
+                    &worker_pool,
+                    &issue_delivery_queue_listener,
+                )
+                .await;
+
+            for task in iterator {
+                task.await;
+            }
+        })
+        .pipe(futures::future::join_all)
+        .await;
 
     Ok(())
 }
@@ -124,25 +221,43 @@ pub async fn get_newsletter_sending_worker_iterator<
         'a,
         D,
     >,
+    worker_pool: &'a WorkerPool,
+    issue_delivery_queue_listener: &'a D::L,
 ) -> impl Iterator<Item = impl Future> {
     std::iter::repeat_with(async || {
         let iterator = get_single_newsletter_picking_and_sending_iterator(
             dependencies
         );
+        let mut subscription = None;
 
         for task_result in iterator {
+            // Tasks are left queued while the provider is failing, rather
+            // than retried without being sent.
+            if let Some(retry_in) =
+                dependencies.email_client.unavailable_for()
+            {
+                tracing::debug!(
+                    ?retry_in,
+                    "Waiting for the circuit breaker of the email provider."
+                );
+                tokio::time::sleep(retry_in).await;
+                continue;
+            }
+
             use SingleNewsletterPickingAndSendingTaskResult as R;
             match task_result.await {
                 R::Completed => (),
                 R::NothingFound => {
-                    tokio::time::sleep(
-                        Duration::from_secs(10),
+                    wait_for_notification(
+                        issue_delivery_queue_listener,
+                        &mut subscription,
+                        worker_pool.idle_delay,
                     )
                     .await;
                 }
                 R::Error(_) => {
                     tokio::time::sleep(
-                        Duration::from_secs(1),
+                        worker_pool.error_delay,
                     )
                     .await;
                 }
@@ -194,9 +309,14 @@ pub fn get_single_newsletter_picking_and_sending_iterator<D: IssueDeliveryWorker
 
     let IssueDeliveryWorkerDependencies {
         email_client: _,
+        application_base_url: _,
+        unsubscribe_secret: _,
+        retry_policy: _,
+        batch_size: _,
         begin_unit_of_work,
         issue_delivery_queue_repository,
         newsletters_repository,
+        subscriptions_repository: _,
     } = dependencies.clone();
 
     std::iter::repeat_with(async || {
@@ -209,26 +329,53 @@ pub fn get_single_newsletter_picking_and_sending_iterator<D: IssueDeliveryWorker
             Err(e) => return R::Error(e),
         };
 
-        match issue_delivery_queue_repository.acquire_newsletter_task(&mut unit_of_work).await {
+        match issue_delivery_queue_repository
+            .acquire_newsletter_task(&mut unit_of_work)
+            .await
+        {
             Ok(Some(id)) => {
-                newsletters_repository.get_newsletter_content(&mut unit_of_work, id)
-                .await
-                .map_err(eyre::Report::new)
+                let content = newsletters_repository
+                    .get_newsletter_content(
+                        &mut unit_of_work,
+                        id,
+                    )
+                    .await
+                    .map_err(eyre::Report::new);
+
+                // Releases the picked task, to be acquired on its own like the others.
+                if let Err(e) = unit_of_work.commit().await
+                {
+                    return R::Error(eyre::Report::new(e));
+                }
+
+                content
                 .map(async |i| {
                     let NewsletterContent {
                         title,
                         text_content,
                         html_content,
+                        is_private,
                     } = i;
 
+                    let (html_content, text_content) = if is_private {
+                        (html_content, text_content)
+                    } else {
+                        with_view_in_browser_link(
+                            &html_content,
+                            &text_content,
+                            &dependencies.application_base_url.0,
+                            id,
+                        )
+                    };
+
                     let subject = D::P::from_string(title);
-                    let html_content = D::P::from_string(html_content);
-                    let text_content = D::P::from_string(text_content);
+                    let html_template = parse_template_or_literal(&html_content, id);
+                    let text_template = parse_template_or_literal(&text_content, id);
 
                     let iterator = get_sending_to_subscribers_of_single_newsletter_issue_iterator(
                         &subject,
-                        &html_content,
-                        &text_content,
+                        &html_template,
+                        &text_template,
                         &id,
                         dependencies
                     );
@@ -239,6 +386,11 @@ pub fn get_single_newsletter_picking_and_sending_iterator<D: IssueDeliveryWorker
                             ControlFlow::Break(Ok(())) => return R::Completed,
                             ControlFlow::Break(Err(e)) => return R::Error(e)
                         }
+
+                        // Left to the worker, waiting for the circuit breaker to close.
+                        if dependencies.email_client.unavailable_for().is_some() {
+                            return R::Completed;
+                        }
                     };
 
                     R::Completed
@@ -246,7 +398,7 @@ pub fn get_single_newsletter_picking_and_sending_iterator<D: IssueDeliveryWorker
                 .pipe(traverse_result_future)
                 .await
                 .pipe(R::from)
-            },
+            }
             Ok(None) => R::NothingFound,
             Err(e) => R::Error(e.into()),
         }
@@ -254,15 +406,15 @@ pub fn get_single_newsletter_picking_and_sending_iterator<D: IssueDeliveryWorker
 }
 
 #[tracing::instrument(
-    name = "Gets infinite iterator sending a newsletter to a subscriber."
+    name = "Gets infinite iterator sending a newsletter to a batch of subscribers."
     skip_all
 )]
 fn get_sending_to_subscribers_of_single_newsletter_issue_iterator<
     D: IssueDeliveryWorkerDependencyAlias,
 >(
     subject: &K1<D::P, str>,
-    html_content: &K1<D::P, str>,
-    text_content: &K1<D::P, str>,
+    html_template: &NewsletterTemplate,
+    text_template: &NewsletterTemplate,
     newsletter_issue_id: &Uuid,
     dependencies: &IssueDeliveryWorkerDependencies<D>,
 ) -> impl Iterator<
@@ -272,9 +424,14 @@ fn get_sending_to_subscribers_of_single_newsletter_issue_iterator<
 > {
     let IssueDeliveryWorkerDependencies {
         email_client,
+        application_base_url: _,
+        unsubscribe_secret: _,
+        retry_policy,
+        batch_size: _,
         begin_unit_of_work,
         issue_delivery_queue_repository,
         newsletters_repository: _,
+        subscriptions_repository,
     } = dependencies.clone();
     std::iter::repeat_with(async || {
         let mut unit_of_work = match begin_unit_of_work
@@ -286,68 +443,81 @@ fn get_sending_to_subscribers_of_single_newsletter_issue_iterator<
             Err(e) => return ControlFlow::Break(Err(e)),
         };
 
-        let result = match issue_delivery_queue_repository.acquire_newsletter_task_from_issue(
-            &mut unit_of_work,
-            *newsletter_issue_id,
-        ).await
+        let result = match issue_delivery_queue_repository
+            .acquire_newsletter_tasks_from_issue(
+                &mut unit_of_work,
+                *newsletter_issue_id,
+                dependencies.batch_size,
+            )
+            .await
         {
-            Ok(Some(record)) => {
-                match SubscriberEmail::try_from(record.subscriber_email.to_string()) {
-                    Ok(subscriber_email) => {
-                        match email_client
-                                .send_email(
-                                    subscriber_email.pipe_ref(SubscriberEmail::clone),
-                                    subject.pipe_ref(K1::clone),
-                                    html_content.pipe_ref(K1::clone),
-                                    text_content.pipe_ref(K1::clone),
-                                ).await
-                                {
-                                    Ok(()) => {
-                                        match issue_delivery_queue_repository.finalize_newsletter_task(
-                                            &mut unit_of_work,
-                                            record
-                                        ).await {
-                                            Ok(()) => ControlFlow::Continue(()),
-                                            Err(e) => e
-                                                .pipe(eyre::Report::new)
-                                                .wrap_err(format!("Failed to finalize newsletter task to: '{}'", subscriber_email))
-                                                .pipe(Err)
-                                                .pipe(ControlFlow::Break),
-                                        }
-                                    },
-                                    Err(e) => {
-
-                                        let mut error_stash = lazy_errors::ErrorStash::<_, _,eyre::Report>::new(|| format!("Failed to send newsletter to: '{}'", subscriber_email));
-
-                                        issue_delivery_queue_repository.schedule_task_retry(
-                                            &mut unit_of_work,
-                                            &record,
-                                        ).await
-                                        .context("Failed to schedule task retry.").or_stash(&mut error_stash);
-
-                                        error_stash.push(eyre::Report::new(e));
-
-                                        error_stash
-                                        .into_eyre_result()
-                                        .pipe(ControlFlow::Break)
-                                    },
-                                }
-                    }
-                    Err(e) => {
-                        tracing::warn!("Found subscriber with invalid email while attempting to send a newsletter to them: '{0}'\n
+            Ok(records) if records.is_empty() => {
+                ControlFlow::Break(Ok(()))
+            }
+            Ok(records) => {
+                let mut batch =
+                    Vec::with_capacity(records.len());
+                let mut batch_records =
+                    Vec::with_capacity(records.len());
+
+                for record in records {
+                    match SubscriberEmail::try_from(
+                        record.subscriber_email.clone(),
+                    ) {
+                        Ok(subscriber_email) => {
+                            let email = newsletter_email(
+                                dependencies,
+                                &record,
+                                subscriber_email,
+                                subject,
+                                html_template,
+                                text_template,
+                                newsletter_issue_id,
+                            );
+
+                            batch.push(email);
+                            batch_records.push(record);
+                        }
+                        Err(e) => {
+                            tracing::warn!("Found subscriber with invalid email while attempting to send a newsletter to them: '{0}'\n
                 Error: '{e}'", &record.subscriber_email);
 
-                        let _ = issue_delivery_queue_repository.disable_task(
-                            &mut unit_of_work,
-                            &record,
-                        ).await;
+                            let _ = issue_delivery_queue_repository.disable_task(
+                                &mut unit_of_work,
+                                &record,
+                            ).await;
+                        }
+                    }
+                }
+
+                let results =
+                    email_client.send_batch(batch).await;
 
-                        ControlFlow::Continue(())
-                    },
+                // Kept even if settling the batch is rolled back.
+                let _ = record_email_deliveries(
+                    begin_unit_of_work,
+                    issue_delivery_queue_repository,
+                    &batch_records,
+                    &results,
+                )
+                .await
+                .inspect_err(|e| tracing::error!(error.cause_chain = ?e, "Failed to log deliveries of a batch."));
+
+                match settle_batch_results(
+                    issue_delivery_queue_repository,
+                    subscriptions_repository,
+                    &mut unit_of_work,
+                    retry_policy,
+                    batch_records,
+                    results,
+                )
+                .await
+                {
+                    Ok(()) => ControlFlow::Continue(()),
+                    Err(e) => ControlFlow::Break(Err(e)),
                 }
-            },
-            Ok(None) => ControlFlow::Break(Ok(())),
-            Err(e) => ControlFlow::Break(Err(e.into()))
+            }
+            Err(e) => ControlFlow::Break(Err(e.into())),
         };
 
         match unit_of_work.commit().await {
@@ -360,3 +530,368 @@ fn get_sending_to_subscribers_of_single_newsletter_issue_iterator<
         }
     })
 }
+
+/// Email of the issue to one of its recipients, tagged with the issue &
+/// carrying its subscriber, for the events of the provider.
+fn newsletter_email<
+    D: IssueDeliveryWorkerDependencyAlias,
+>(
+    dependencies: &IssueDeliveryWorkerDependencies<D>,
+    record: &IssueDeliveryRecord,
+    subscriber_email: SubscriberEmail<D::P>,
+    subject: &K1<D::P, str>,
+    html_template: &NewsletterTemplate,
+    text_template: &NewsletterTemplate,
+    newsletter_issue_id: &Uuid,
+) -> OutgoingEmail<D::P> {
+    let (html_content, text_content, unsubscribe_link) =
+        render_for_recipient::<D::P>(
+            record,
+            html_template,
+            text_template,
+            dependencies.application_base_url,
+            dependencies.unsubscribe_secret,
+        );
+
+    dependencies
+        .email_client
+        .message(
+            subscriber_email,
+            subject.pipe_ref(K1::clone),
+            html_content,
+            text_content,
+        )
+        .headers(list_unsubscribe_headers::<D::P>(
+            &unsubscribe_link,
+        ))
+        .tag(D::P::from_string(
+            newsletter_issue_id.to_string(),
+        ))
+        .metadata(
+            D::P::from_static_str(
+                NEWSLETTER_ISSUE_ID_METADATA,
+            ),
+            D::P::from_string(
+                newsletter_issue_id.to_string(),
+            ),
+        )
+        .metadata(
+            D::P::from_static_str(SUBSCRIBER_ID_METADATA),
+            D::P::from_string(
+                record.subscriber_id.to_string(),
+            ),
+        )
+        .broadcast()
+        .build()
+}
+
+/// Logs the attempt at each recipient of a batch in a unit of work of its own,
+/// so the log survives a failure to settle the batch.
+async fn record_email_deliveries<
+    B: BeginUnitOfWork,
+    I: IssueDeliveryQueueRepository<
+        UnitOfWork = B::UnitOfWork,
+    >,
+>(
+    begin_unit_of_work: &B,
+    issue_delivery_queue_repository: &I,
+    records: &[IssueDeliveryRecord],
+    results: &[Result<SentEmail, SendEmailError>],
+) -> Result<(), eyre::Report> {
+    let mut unit_of_work = begin_unit_of_work
+        .begin()
+        .await
+        .map_err(eyre::Report::new)?;
+
+    for (record, result) in records.iter().zip(results) {
+        // Never attempted, the task is left queued.
+        if let Err(SendEmailError::CircuitOpen) = result {
+            continue;
+        }
+
+        issue_delivery_queue_repository
+            .record_email_delivery(
+                &mut unit_of_work,
+                &email_delivery(record, result),
+            )
+            .await
+            .map_err(eyre::Report::new)
+            .wrap_err(format!(
+                "Failed to log delivery to: '{}'",
+                record.subscriber_email
+            ))?;
+    }
+
+    unit_of_work
+        .commit()
+        .await
+        .map_err(eyre::Report::new)
+        .wrap_err("Failed to commit unit of work.")
+}
+
+/// Each recipient of a batch is finalized, retried or suppressed on its own
+/// result. Only transient failures are errors, so the worker backs
+/// off from the provider.
+async fn settle_batch_results<
+    I: IssueDeliveryQueueRepository,
+    S: SubscriptionsRepository<UnitOfWork = I::UnitOfWork>,
+>(
+    issue_delivery_queue_repository: &I,
+    subscriptions_repository: &S,
+    unit_of_work: &mut I::UnitOfWork,
+    retry_policy: &RetryPolicy,
+    records: Vec<IssueDeliveryRecord>,
+    results: Vec<Result<SentEmail, SendEmailError>>,
+) -> Result<(), eyre::Report> {
+    let mut error_stash = lazy_errors::ErrorStash::<
+        _,
+        _,
+        eyre::Report,
+    >::new(
+        || "Failed to send newsletter to some recipients of a batch.",
+    );
+
+    for (record, result) in records.into_iter().zip(results)
+    {
+        let subscriber_email =
+            record.subscriber_email.clone();
+
+        match result {
+            Ok(_) => {
+                issue_delivery_queue_repository
+                    .finalize_newsletter_task(unit_of_work, record)
+                    .await
+                    .map_err(eyre::Report::new)
+                    .wrap_err(format!("Failed to finalize newsletter task to: '{subscriber_email}'"))
+                    .or_stash(&mut error_stash);
+            }
+            // Never reached the provider, so the task is left queued without
+            // counting an attempt, until the circuit breaker closes.
+            Err(SendEmailError::CircuitOpen) => {
+                tracing::debug!(
+                    "Circuit breaker of the email provider is open, leaving the task to '{subscriber_email}' queued."
+                );
+            }
+            // The provider may have sent it, so it is not sent again.
+            Err(e @ SendEmailError::Missing { .. }) => {
+                tracing::warn!(
+                    error.cause_chain = ?e,
+                    "Email provider returned no result for '{subscriber_email}', not sending it again."
+                );
+
+                issue_delivery_queue_repository
+                    .finalize_newsletter_task(unit_of_work, record)
+                    .await
+                    .map_err(eyre::Report::new)
+                    .wrap_err(format!("Failed to finalize newsletter task to: '{subscriber_email}'"))
+                    .or_stash(&mut error_stash);
+            }
+            Err(e)
+                if e.kind()
+                    == SendEmailErrorKind::Permanent =>
+            {
+                tracing::warn!(
+                    error.cause_chain = ?e,
+                    "Email provider rejected '{subscriber_email}', suppressing them."
+                );
+
+                // Stops retries of the task even if suppressing fails.
+                issue_delivery_queue_repository
+                    .disable_task(unit_of_work, &record)
+                    .await
+                    .map_err(eyre::Report::new)
+                    .wrap_err(format!("Failed to disable newsletter task to: '{subscriber_email}'"))
+                    .or_stash(&mut error_stash);
+
+                // The provider would have bounced them anyway.
+                subscriptions_repository
+                    .suppress_email(unit_of_work, &subscriber_email, SuppressionReason::Bounced)
+                    .await
+                    .map_err(eyre::Report::new)
+                    .wrap_err(format!("Failed to suppress: '{subscriber_email}'"))
+                    .or_stash(&mut error_stash);
+            }
+            Err(e) => {
+                let e = eyre::Report::new(e);
+
+                schedule_task_retry_or_dead_letter(
+                    issue_delivery_queue_repository,
+                    unit_of_work,
+                    retry_policy,
+                    &record,
+                    &e,
+                )
+                .await
+                .or_stash(&mut error_stash);
+
+                error_stash.push(e.wrap_err(format!("Failed to send newsletter to: '{subscriber_email}'")));
+            }
+        }
+    }
+
+    error_stash.into_eyre_result()
+}
+
+fn email_delivery(
+    record: &IssueDeliveryRecord,
+    result: &Result<SentEmail, SendEmailError>,
+) -> NewEmailDelivery {
+    let (status, http_status, provider_message_id, error) =
+        match result {
+            Ok(sent_email) => (
+                EmailDeliveryStatus::Sent,
+                sent_email.http_status,
+                sent_email.message_id.clone(),
+                None,
+            ),
+            Err(e) => (
+                if matches!(
+                    e,
+                    SendEmailError::Missing { .. }
+                ) {
+                    EmailDeliveryStatus::Unknown
+                } else {
+                    EmailDeliveryStatus::Failed
+                },
+                e.http_status(),
+                None,
+                Some(format!(
+                    "{:#}",
+                    eyre::Report::new(e.clone())
+                )),
+            ),
+        };
+
+    NewEmailDelivery {
+        newsletter_issue_id: record.newsletter_issue_id,
+        subscriber_email: record.subscriber_email.clone(),
+        attempt: record.n_retries + 1,
+        status,
+        http_status,
+        provider_message_id,
+        error,
+    }
+}
+
+/// Retries are delayed for the failed recipient only, until it runs out of
+/// attempts.
+async fn schedule_task_retry_or_dead_letter<
+    I: IssueDeliveryQueueRepository,
+>(
+    issue_delivery_queue_repository: &I,
+    unit_of_work: &mut I::UnitOfWork,
+    retry_policy: &RetryPolicy,
+    record: &IssueDeliveryRecord,
+    error: &eyre::Report,
+) -> Result<(), eyre::Report> {
+    match retry_policy.retry_delay(record.n_retries) {
+        Some(retry_delay) => {
+            issue_delivery_queue_repository
+                .schedule_task_retry(
+                    unit_of_work,
+                    record,
+                    retry_delay,
+                )
+                .await
+                .context("Failed to schedule task retry.")
+        }
+        None => issue_delivery_queue_repository
+            .dead_letter_task(
+                unit_of_work,
+                record,
+                &format!("{error:#}"),
+            )
+            .await
+            .context("Failed to dead letter task."),
+    }
+}
+
+/// Returns the html & text bodies along with the unsubscribe link, which is
+/// also needed for the `List-Unsubscribe` header.
+fn render_for_recipient<P: SharedPointerHKT>(
+    record: &IssueDeliveryRecord,
+    html_template: &NewsletterTemplate,
+    text_template: &NewsletterTemplate,
+    application_base_url: &ApplicationBaseUrl<P>,
+    unsubscribe_secret: &UnsubscribeSecret<P>,
+) -> (K1<P, str>, K1<P, str>, String) {
+    let unsubscribe_link = UnsubscribeToken::sign(
+        unsubscribe_secret,
+        record.subscriber_id,
+    )
+    .link(&application_base_url.0, record.subscriber_id);
+    let confirm_date = record
+        .confirmed_at
+        .map(|d| d.format(CONFIRM_DATE_FORMAT).to_string())
+        .unwrap_or_default();
+    let context = TemplateContext {
+        subscriber_name: &record.subscriber_name,
+        unsubscribe_url: &unsubscribe_link,
+        confirm_date: &confirm_date,
+    };
+
+    (
+        html_template
+            .render_html(&context)
+            .pipe(P::from_string),
+        text_template
+            .render_text(&context)
+            .pipe(P::from_string),
+        unsubscribe_link,
+    )
+}
+
+/// Private issues have no archive page to link to.
+fn with_view_in_browser_link(
+    html_content: &str,
+    text_content: &str,
+    application_base_url: &str,
+    newsletter_issue_id: Uuid,
+) -> (String, String) {
+    let archive_link = archive_issue_link(
+        application_base_url,
+        newsletter_issue_id,
+    );
+
+    (
+        format!(
+            r#"<p><a href="{archive_link}">View in browser</a></p>{html_content}"#
+        ),
+        format!(
+            "View in browser: {archive_link}\n\n{text_content}"
+        ),
+    )
+}
+
+/// Issues are validated on publish, so this only catches content stored
+/// before placeholders were introduced.
+fn parse_template_or_literal(
+    content: &str,
+    newsletter_issue_id: Uuid,
+) -> NewsletterTemplate {
+    NewsletterTemplate::parse(content).unwrap_or_else(|e| {
+        tracing::warn!(
+            "Newsletter issue '{newsletter_issue_id}' has an invalid template, sending it verbatim: '{e}'"
+        );
+        NewsletterTemplate::literal(content)
+    })
+}
+
+/// Headers required by mailbox providers for one-click unsubscription (RFC 8058).
+fn list_unsubscribe_headers<P: SharedPointerHKT>(
+    unsubscribe_link: &str,
+) -> Vec<EmailHeader<P>> {
+    vec![
+        EmailHeader::new(
+            P::from_static_str(LIST_UNSUBSCRIBE_HEADER),
+            format!("<{unsubscribe_link}>")
+                .pipe(P::from_string),
+        ),
+        EmailHeader::new(
+            P::from_static_str(
+                LIST_UNSUBSCRIBE_POST_HEADER,
+            ),
+            P::from_static_str(LIST_UNSUBSCRIBE_POST_VALUE),
+        ),
+    ]
+}
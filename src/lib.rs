@@ -22,6 +22,7 @@ pub mod startup;
 pub mod telemetry;
 #[macro_use]
 pub mod utils;
+pub mod confirmation_email_worker;
 pub mod database;
 pub mod dependency_injection;
 pub mod idempotency;
@@ -29,3 +30,4 @@ pub mod issue_delivery_worker;
 pub mod services;
 pub mod session_state;
 pub mod tuples;
+pub mod worker;
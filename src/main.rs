@@ -8,6 +8,7 @@ use zero2prod::{
         ApplicationSettings, DatabaseSettings,
         EmailClientSettings, get_configuration,
     },
+    confirmation_email_worker,
     dependency_injection::app_state::{
         AppStateFactory, DefaultAppStateFactory,
         IssueDeliveryWorkerTypes,
@@ -52,12 +53,22 @@ where
     let configuration = get_configuration::<P>()
         .expect("Failed to find configuration file.");
 
-    let app_state = A::build(&configuration);
+    let app_state = A::build(&configuration)?;
+
+    let email_client =
+        configuration.email_client.as_ref().clone().client(
+            app_state.email_sender.clone(),
+            app_state.fallback_email_sender.clone(),
+        );
 
     let application =
-        Application::build_with::<P, A::AppStateTypes>(
+        Application::build_with_email_client::<
+            P,
+            A::AppStateTypes,
+        >(
             &configuration,
             app_state.clone(),
+            email_client.clone(),
         )
         .await?
         .run_until_stopped()
@@ -74,6 +85,19 @@ where
                 .clone(),
             app_state.begin_unit_of_work.clone(),
             app_state.newsletters_repository.clone(),
+            app_state.subscriptions_repository.clone(),
+            app_state.issue_delivery_queue_listener.clone(),
+            email_client.clone(),
+            configuration.clone(),
+        )
+        .pipe(tokio::spawn);
+
+    let confirmation_email_worker =
+        confirmation_email_worker::run_worker_until_stopped(
+            app_state.begin_unit_of_work.clone(),
+            app_state.subscriptions_repository.clone(),
+            app_state.confirmation_email_outbox_listener.clone(),
+            email_client,
             configuration,
         )
         .pipe(tokio::spawn);
@@ -81,6 +105,7 @@ where
     tokio::select! {
         i = application => report_exit("Application", i),
         i = worker => report_exit("Background Worker", i),
+        i = confirmation_email_worker => report_exit("Confirmation Email Worker", i),
     };
 
     Ok(())
@@ -6,7 +6,8 @@ use uuid::Uuid;
 use crate::{
     authentication::UserId,
     database::transactional::authentication::AuthenticationRepository,
-    dependency_injection::app_state::Inject, utils::Pipe,
+    dependency_injection::app_state::Inject,
+    utils::{Pipe, escape_html},
 };
 
 pub async fn admin_dashboard<
@@ -23,7 +24,9 @@ pub async fn admin_dashboard<
         .map_err(
             actix_web::error::ErrorInternalServerError,
         )?
-        .map(|credentials| credentials.username)
+        .map(|credentials| {
+            escape_html(&credentials.username).into_owned()
+        })
         .ok_or_else(|| {
             actix_web::error::ErrorNotFound(
                 "User not found",
@@ -44,12 +47,16 @@ r#"<!DOCTYPE html>
 <p>Available actions:</p>
 <ol>
     <li><a href="/admin/reset_password">Change password</a></li>
+    <li><a href="/admin/email">Change email</a></li>
     <li>
         <form name="logoutForm" action="/admin/logout" method="post">
             <input type="submit" value="Logout">
         </form>
     </li>
     <li><a href="/admin/newsletters">Post Newsletter</a></li>
+    <li><a href="/admin/newsletters/drafts">Newsletter Drafts</a></li>
+    <li><a href="/admin/newsletters/scheduled">Scheduled Newsletters</a></li>
+    <li><a href="/admin/issues">Published Newsletters</a></li>
 </ol>
 </body>
 </html>"#)).pipe(Ok)
@@ -0,0 +1,74 @@
+use actix_web::{
+    HttpResponse, http::header::ContentType, web,
+};
+use std::fmt::Write;
+use uuid::Uuid;
+
+use crate::{
+    authentication::UserId,
+    database::transactional::authentication::AuthenticationRepository,
+    dependency_injection::app_state::Inject,
+    utils::escape_html,
+};
+
+pub async fn get_change_email_form<
+    A: AuthenticationRepository,
+>(
+    user_id: web::ReqData<UserId>,
+    flash_messages: actix_web_flash_messages::IncomingFlashMessages,
+    authentication_repository: Inject<A>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let user_id: Uuid = user_id.into_inner().into();
+
+    let mut notification_html = String::new();
+
+    flash_messages.iter().for_each(|m| {
+        writeln!(
+            notification_html,
+            "<p><i>{}</i></p>",
+            escape_html(m.content())
+        )
+        .expect(
+            "Write to string should have been successful.",
+        );
+    });
+
+    let email = authentication_repository
+        .get_email_from_user_id(user_id)
+        .await
+        .map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?
+        .unwrap_or_default();
+    let email = escape_html(&email);
+
+    Ok(HttpResponse::Ok()
+.content_type(ContentType::html())
+.body(
+    format!(
+r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta http-equiv="content-type" content="text/html; charset=utf-8">
+<title>Change Email</title>
+</head>
+<body>
+{notification_html}
+<p>Test copies of newsletters are sent to this address when no other is given.</p>
+<form action="/admin/email" method="post">
+<label>Email
+<input
+type="email"
+placeholder="Leave empty to remove it"
+name="email"
+value="{email}"
+>
+</label>
+<br>
+<button type="submit">Change email</button>
+</form>
+<p><a href="/admin/dashboard">&lt;- Back</a></p>
+</body>
+</html>"#,
+)))
+}
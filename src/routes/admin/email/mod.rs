@@ -0,0 +1,7 @@
+mod get;
+mod post;
+pub use get::get_change_email_form;
+pub use post::{
+    EMAIL_CHANGED_MESSAGE, EMAIL_CLEARED_MESSAGE,
+    INVALID_EMAIL_MESSAGE, post_change_email,
+};
@@ -0,0 +1,84 @@
+use std::borrow::Cow;
+
+use actix_web::{HttpResponse, web};
+use uuid::Uuid;
+
+use crate::{
+    authentication::UserId,
+    database::transactional::authentication::{
+        AuthenticationRepository, UpdateEmailError,
+    },
+    dependency_injection::app_state::Inject,
+    domain::SubscriberEmail,
+    startup,
+    utils::{Pipe, see_other_response},
+};
+
+pub const EMAIL_CHANGED_MESSAGE: &str =
+    "Your email address has been changed.";
+
+pub const EMAIL_CLEARED_MESSAGE: &str =
+    "Your email address has been removed.";
+
+pub const INVALID_EMAIL_MESSAGE: &str =
+    "Email address is not valid:";
+
+#[derive(serde::Deserialize)]
+pub struct FormData<'a> {
+    pub email: Cow<'a, str>,
+}
+
+/// Sets the address writers receive test copies of issues at.
+pub async fn post_change_email<
+    A: AuthenticationRepository,
+>(
+    user_id: web::ReqData<UserId>,
+    form_data: web::Form<FormData<'_>>,
+    authentication_repository: Inject<A>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let user_id: Uuid = user_id.into_inner().into();
+    let email = form_data.0.email.trim();
+
+    if !email.is_empty()
+        && SubscriberEmail::<
+            startup::GlobalSharedPointerType,
+        >::try_from(email)
+        .is_err()
+    {
+        actix_web_flash_messages::FlashMessage::error(
+            format!("{INVALID_EMAIL_MESSAGE} {email}"),
+        )
+        .send();
+
+        return see_other_response("/admin/email").pipe(Ok);
+    }
+    let email = Some(email).filter(|i| !i.is_empty());
+
+    match authentication_repository
+        .update_email(user_id, email)
+        .await
+    {
+        Ok(()) => {}
+        Err(e @ UpdateEmailError::UserNotFound(_)) => {
+            return e
+                .pipe(actix_web::error::ErrorNotFound)
+                .pipe(Err);
+        }
+        Err(e) => {
+            return e
+                .pipe(actix_web::error::ErrorInternalServerError)
+                .pipe(Err);
+        }
+    }
+
+    actix_web_flash_messages::FlashMessage::info(
+        if email.is_some() {
+            EMAIL_CHANGED_MESSAGE
+        } else {
+            EMAIL_CLEARED_MESSAGE
+        },
+    )
+    .send();
+
+    see_other_response("/admin/email").pipe(Ok)
+}
@@ -1,11 +1,22 @@
 mod dashboard;
+pub mod email;
 mod logout;
 pub mod newsletter;
 mod password;
 
 pub use dashboard::admin_dashboard;
+pub use email::{get_change_email_form, post_change_email};
 pub use logout::logout;
 pub use newsletter::{
-    get_newsletter_form, publish_newsletter,
+    cancel_scheduled_newsletter, change_issue_delivery,
+    create_newsletter_draft, delete_newsletter_draft,
+    get_dead_letters, get_delivery_progress,
+    get_email_deliveries, get_newsletter_draft,
+    get_newsletter_drafts, get_newsletter_form,
+    get_published_newsletters, get_scheduled_newsletters,
+    preview_newsletter_draft, publish_newsletter,
+    publish_newsletter_draft, requeue_dead_letter,
+    send_test_newsletter, send_test_newsletter_draft,
+    set_newsletter_visibility, update_newsletter_draft,
 };
 pub use password::*;
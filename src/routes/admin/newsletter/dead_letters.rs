@@ -0,0 +1,195 @@
+use std::fmt::Write;
+
+use actix_web::{
+    HttpResponse, http::header::ContentType, web,
+};
+use uuid::Uuid;
+
+use crate::{
+    authentication::UserId,
+    database::transactional::{
+        issue_delivery_queue::{
+            DeadLetter, IssueDeliveryQueueRepository,
+            RequeueDeadLetterError,
+        },
+        unit_of_work::{BeginUnitOfWork, UnitOfWork as _},
+    },
+    dependency_injection::app_state::Inject,
+    utils::{Pipe, escape_html, see_other_response},
+};
+
+pub const DEAD_LETTER_REQUEUED_MESSAGE: &str =
+    "Delivery has been requeued.";
+
+pub const DEAD_LETTER_NOT_FOUND_MESSAGE: &str =
+    "Delivery is not a dead letter anymore.";
+
+pub const DEAD_LETTER_ALREADY_QUEUED_MESSAGE: &str =
+    "Delivery is already queued again.";
+
+#[derive(serde::Deserialize)]
+pub struct RequeueFormData {
+    newsletter_issue_id: Uuid,
+    subscriber_email: String,
+}
+
+fn dead_letter_row(dead_letter: &DeadLetter) -> String {
+    format!(
+        r#"<tr>
+<td>{title}</td>
+<td><a href="/admin/deliveries?subscriber_email={encoded_subscriber_email}">{subscriber_email}</a></td>
+<td>{n_attempts}</td>
+<td>{last_error}</td>
+<td>{dead_lettered_at}</td>
+<td>
+<form action="/admin/dead-letters/requeue" method="post">
+<input type="hidden" name="newsletter_issue_id" value="{id}">
+<input type="hidden" name="subscriber_email" value="{subscriber_email}">
+<button type="submit">Requeue</button>
+</form>
+</td>
+</tr>
+"#,
+        title = escape_html(&dead_letter.title),
+        subscriber_email =
+            escape_html(&dead_letter.subscriber_email),
+        encoded_subscriber_email = urlencoding::encode(
+            &dead_letter.subscriber_email
+        ),
+        n_attempts = dead_letter.n_attempts,
+        last_error = escape_html(&dead_letter.last_error),
+        dead_lettered_at = dead_letter
+            .dead_lettered_at
+            .format("%Y-%m-%d %H:%M UTC"),
+        id = dead_letter.newsletter_issue_id,
+    )
+}
+
+pub async fn get_dead_letters<
+    B: BeginUnitOfWork,
+    I: IssueDeliveryQueueRepository<
+        UnitOfWork = B::UnitOfWork,
+    >,
+>(
+    _user_id: web::ReqData<UserId>,
+    flash_messages: actix_web_flash_messages::IncomingFlashMessages,
+    begin_unit_of_work: Inject<B>,
+    issue_delivery_queue_repository: Inject<I>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let mut notification_html = String::new();
+
+    flash_messages.iter().for_each(|m| {
+        writeln!(
+            notification_html,
+            "<p><i>{}</i></p>",
+            escape_html(m.content())
+        )
+        .expect(
+            "Write to string should have been successful.",
+        );
+    });
+
+    let mut unit_of_work =
+        begin_unit_of_work.begin().await.map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+    let dead_letters = issue_delivery_queue_repository
+        .get_dead_letters(&mut unit_of_work)
+        .await
+        .map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+    unit_of_work.commit().await.map_err(
+        actix_web::error::ErrorInternalServerError,
+    )?;
+
+    let rows_html = dead_letters
+        .iter()
+        .map(dead_letter_row)
+        .collect::<String>();
+
+    HttpResponse::Ok()
+    .content_type(ContentType::html())
+    .body(format!(
+r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta http-equiv="content-type" content="text/html; charset=utf-8">
+<title>Dead letters</title>
+</head>
+<body>
+{notification_html}
+<table>
+<tr>
+<th>Title</th><th>Recipient</th><th>Attempts</th><th>Last error</th><th>Dead lettered at</th><th></th>
+</tr>
+{rows_html}
+</table>
+<p><a href="/admin/issues">&lt;- Back</a></p>
+</body>
+</html>"#))
+    .pipe(Ok)
+}
+
+pub async fn requeue_dead_letter<
+    B: BeginUnitOfWork,
+    I: IssueDeliveryQueueRepository<
+        UnitOfWork = B::UnitOfWork,
+    >,
+>(
+    _user_id: web::ReqData<UserId>,
+    form: web::Form<RequeueFormData>,
+    begin_unit_of_work: Inject<B>,
+    issue_delivery_queue_repository: Inject<I>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let RequeueFormData {
+        newsletter_issue_id,
+        subscriber_email,
+    } = form.into_inner();
+
+    let mut unit_of_work =
+        begin_unit_of_work.begin().await.map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+    match issue_delivery_queue_repository
+        .requeue_dead_letter(
+            &mut unit_of_work,
+            newsletter_issue_id,
+            &subscriber_email,
+        )
+        .await
+    {
+        Ok(()) => {
+            unit_of_work.commit().await.map_err(
+                actix_web::error::ErrorInternalServerError,
+            )?;
+
+            actix_web_flash_messages::FlashMessage::info(
+                DEAD_LETTER_REQUEUED_MESSAGE,
+            )
+            .send();
+        }
+        Err(RequeueDeadLetterError::NotFound(..)) => {
+            actix_web_flash_messages::FlashMessage::error(
+                DEAD_LETTER_NOT_FOUND_MESSAGE,
+            )
+            .send();
+        }
+        Err(RequeueDeadLetterError::AlreadyQueued(..)) => {
+            actix_web_flash_messages::FlashMessage::error(
+                DEAD_LETTER_ALREADY_QUEUED_MESSAGE,
+            )
+            .send();
+        }
+        Err(e) => {
+            return e
+                .pipe(actix_web::error::ErrorInternalServerError)
+                .pipe(Err);
+        }
+    }
+
+    see_other_response("/admin/dead-letters").pipe(Ok)
+}
@@ -0,0 +1,234 @@
+use actix_web::{
+    HttpResponse, http::header::ContentType, web,
+};
+use uuid::Uuid;
+
+use crate::{
+    authentication::UserId,
+    database::transactional::{
+        issue_delivery_queue::{
+            EmailDelivery, EmailDeliveryFilter,
+            IssueDeliveryQueueRepository,
+        },
+        unit_of_work::{BeginUnitOfWork, UnitOfWork as _},
+    },
+    dependency_injection::app_state::Inject,
+    utils::{Pipe, escape_html},
+};
+
+const EMAIL_DELIVERIES_PER_PAGE: usize = 50;
+
+/// Fields left empty by the filter form are not filtered on.
+#[derive(serde::Deserialize)]
+pub struct DeliveriesQuery {
+    #[serde(default)]
+    newsletter_issue_id: String,
+    #[serde(default)]
+    subscriber_email: String,
+    /// Counted from 0, the newest attempts.
+    #[serde(default)]
+    page: usize,
+}
+
+impl TryFrom<DeliveriesQuery> for EmailDeliveryFilter {
+    type Error = uuid::Error;
+
+    fn try_from(
+        value: DeliveriesQuery,
+    ) -> Result<Self, Self::Error> {
+        let newsletter_issue_id =
+            value.newsletter_issue_id.trim();
+        let subscriber_email =
+            value.subscriber_email.trim();
+
+        Self {
+            newsletter_issue_id: if newsletter_issue_id
+                .is_empty()
+            {
+                None
+            } else {
+                Uuid::parse_str(newsletter_issue_id)?
+                    .pipe(Some)
+            },
+            subscriber_email: if subscriber_email.is_empty()
+            {
+                None
+            } else {
+                subscriber_email.to_owned().pipe(Some)
+            },
+        }
+        .pipe(Ok)
+    }
+}
+
+fn email_delivery_row(
+    email_delivery: &EmailDelivery,
+) -> String {
+    format!(
+        r"<tr>
+<td>{title}</td>
+<td>{subscriber_email}</td>
+<td>{attempt}</td>
+<td>{status}</td>
+<td>{http_status}</td>
+<td>{provider_message_id}</td>
+<td>{error}</td>
+<td>{attempted_at}</td>
+</tr>
+",
+        title = escape_html(&email_delivery.title),
+        subscriber_email =
+            escape_html(&email_delivery.subscriber_email),
+        attempt = email_delivery.attempt,
+        status = email_delivery.status,
+        http_status = email_delivery
+            .http_status
+            .map(|i| i.to_string())
+            .unwrap_or_default(),
+        provider_message_id = escape_html(
+            email_delivery
+                .provider_message_id
+                .as_deref()
+                .unwrap_or_default()
+        ),
+        error = escape_html(
+            email_delivery
+                .error
+                .as_deref()
+                .unwrap_or_default()
+        ),
+        attempted_at = email_delivery
+            .attempted_at
+            .format("%Y-%m-%d %H:%M:%S UTC"),
+    )
+}
+
+/// Link to another page of the same filtered deliveries.
+fn page_link(
+    filter: &EmailDeliveryFilter,
+    page: usize,
+    text: &str,
+) -> String {
+    format!(
+        r#"<a href="/admin/deliveries?newsletter_issue_id={newsletter_issue_id}&amp;subscriber_email={subscriber_email}&amp;page={page}">{text}</a>"#,
+        newsletter_issue_id = filter
+            .newsletter_issue_id
+            .map(|i| i.to_string())
+            .unwrap_or_default(),
+        subscriber_email = urlencoding::encode(
+            filter
+                .subscriber_email
+                .as_deref()
+                .unwrap_or_default()
+        ),
+    )
+}
+
+/// Only filtered queries are run, a page at a time, the log growing with
+/// every attempt.
+pub async fn get_email_deliveries<
+    B: BeginUnitOfWork,
+    I: IssueDeliveryQueueRepository<
+        UnitOfWork = B::UnitOfWork,
+    >,
+>(
+    _user_id: web::ReqData<UserId>,
+    query: web::Query<DeliveriesQuery>,
+    begin_unit_of_work: Inject<B>,
+    issue_delivery_queue_repository: Inject<I>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let query = query.into_inner();
+    let page = query.page;
+    let filter = EmailDeliveryFilter::try_from(query)
+        .map_err(actix_web::error::ErrorBadRequest)?;
+
+    let mut email_deliveries = if filter.is_empty() {
+        Vec::new()
+    } else {
+        let mut unit_of_work =
+            begin_unit_of_work.begin().await.map_err(
+                actix_web::error::ErrorInternalServerError,
+            )?;
+
+        let email_deliveries = issue_delivery_queue_repository
+            // One more, to tell whether there is a next page.
+            .get_email_deliveries(
+                &mut unit_of_work,
+                &filter,
+                EMAIL_DELIVERIES_PER_PAGE + 1,
+                page.saturating_mul(EMAIL_DELIVERIES_PER_PAGE),
+            )
+            .await
+            .map_err(
+                actix_web::error::ErrorInternalServerError,
+            )?;
+
+        unit_of_work.commit().await.map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+        email_deliveries
+    };
+
+    let has_next_page =
+        email_deliveries.len() > EMAIL_DELIVERIES_PER_PAGE;
+    email_deliveries.truncate(EMAIL_DELIVERIES_PER_PAGE);
+
+    let previous_page_html = if page == 0 {
+        String::new()
+    } else {
+        page_link(&filter, page - 1, "Newer")
+    };
+    let next_page_html = if has_next_page {
+        page_link(&filter, page + 1, "Older")
+    } else {
+        String::new()
+    };
+
+    let rows_html = email_deliveries
+        .iter()
+        .map(email_delivery_row)
+        .collect::<String>();
+
+    let newsletter_issue_id = filter
+        .newsletter_issue_id
+        .map(|i| i.to_string())
+        .unwrap_or_default();
+    let subscriber_email = escape_html(
+        filter
+            .subscriber_email
+            .as_deref()
+            .unwrap_or_default(),
+    );
+
+    HttpResponse::Ok()
+    .content_type(ContentType::html())
+    .body(format!(
+r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta http-equiv="content-type" content="text/html; charset=utf-8">
+<title>Deliveries</title>
+</head>
+<body>
+<form action="/admin/deliveries" method="get">
+<label>Issue
+<input type="text" name="newsletter_issue_id" value="{newsletter_issue_id}">
+</label>
+<label>Recipient
+<input type="text" name="subscriber_email" value="{subscriber_email}">
+</label>
+<button type="submit">Filter</button>
+</form>
+<table>
+<tr>
+<th>Title</th><th>Recipient</th><th>Attempt</th><th>Status</th><th>HTTP status</th><th>Message ID</th><th>Error</th><th>Attempted at</th>
+</tr>
+{rows_html}
+</table>
+<p>{previous_page_html} {next_page_html}</p>
+<p><a href="/admin/issues">&lt;- Back</a></p>
+</body>
+</html>"#))
+    .pipe(Ok)
+}
@@ -0,0 +1,501 @@
+use std::{borrow::Cow, fmt::Write};
+
+use actix_web::{
+    HttpResponse, http::header::ContentType, web,
+};
+use uuid::Uuid;
+
+use crate::{
+    authentication::UserId,
+    database::transactional::{
+        newsletters::{
+            NewsletterDraftError, NewslettersRepository,
+        },
+        unit_of_work::{BeginUnitOfWork, UnitOfWork as _},
+    },
+    dependency_injection::app_state::Inject,
+    domain::{
+        NewsletterHtml, NewsletterTemplate, TemplateContext,
+    },
+    routes::admin::newsletter::post::Content,
+    utils::{Pipe, escape_html, see_other_response},
+};
+
+pub const DRAFT_SAVED_MESSAGE: &str =
+    "Draft has been saved.";
+
+pub(super) const PREVIEW_SUBSCRIBER_NAME: &str = "Jane Doe";
+
+pub const DRAFT_DELETED_MESSAGE: &str =
+    "Draft has been deleted.";
+
+#[derive(Debug, serde::Deserialize)]
+pub struct DraftFormData<'a> {
+    title: Cow<'a, str>,
+    #[serde(default)]
+    content_html: Cow<'a, str>,
+    #[serde(default)]
+    content_text: Cow<'a, str>,
+    content_markdown: Option<Cow<'a, str>>,
+}
+
+impl<'a> DraftFormData<'a> {
+    pub(super) fn into_parts(
+        self,
+    ) -> (Cow<'a, str>, Content<'a>) {
+        (
+            self.title,
+            Content::from_form(
+                self.content_html,
+                self.content_text,
+                self.content_markdown,
+            ),
+        )
+    }
+}
+
+fn draft_error(
+    e: NewsletterDraftError,
+) -> actix_web::Error {
+    match e {
+        NewsletterDraftError::NotFound(_) => {
+            actix_web::error::ErrorNotFound(e)
+        }
+        NewsletterDraftError::Unexpected(_) => {
+            actix_web::error::ErrorInternalServerError(e)
+        }
+    }
+}
+
+fn notifications_html(
+    flash_messages: &actix_web_flash_messages::IncomingFlashMessages,
+) -> String {
+    let mut notification_html = String::new();
+
+    flash_messages.iter().for_each(|m| {
+        writeln!(
+            notification_html,
+            "<p><i>{}</i></p>",
+            escape_html(m.content())
+        )
+        .expect(
+            "Write to string should have been successful.",
+        );
+    });
+
+    notification_html
+}
+
+fn draft_form_html(
+    action: &str,
+    title: &str,
+    content_text: &str,
+    content_html: &str,
+    content_markdown: &str,
+    submit: &str,
+) -> String {
+    let title = escape_html(title);
+    let content_text = escape_html(content_text);
+    let content_html = escape_html(content_html);
+    let content_markdown = escape_html(content_markdown);
+
+    format!(
+        r#"<form action="{action}" method="post">
+<label>Title
+<input
+type="text"
+placeholder="Enter title"
+name="title"
+value="{title}"
+>
+</label>
+<br>
+<label>Newsletter Content (Text)
+<textarea
+placeholder="Enter newsletter content (text)"
+name="content_text"
+>{content_text}</textarea>
+</label>
+<br>
+<label>Newsletter Content (Html)
+<textarea
+placeholder="Enter newsletter content (Html)"
+name="content_html"
+>{content_html}</textarea>
+</label>
+<br>
+<label>Newsletter Content (Markdown, replaces Text & Html)
+<textarea
+placeholder="Enter newsletter content (Markdown)"
+name="content_markdown"
+>{content_markdown}</textarea>
+</label>
+<br>
+<button type="submit">{submit}</button>
+</form>"#
+    )
+}
+
+pub async fn get_newsletter_drafts<
+    B: BeginUnitOfWork,
+    N: NewslettersRepository<UnitOfWork = B::UnitOfWork>,
+>(
+    _user_id: web::ReqData<UserId>,
+    flash_messages: actix_web_flash_messages::IncomingFlashMessages,
+    begin_unit_of_work: Inject<B>,
+    newsletters_repository: Inject<N>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let notification_html =
+        notifications_html(&flash_messages);
+
+    let mut unit_of_work =
+        begin_unit_of_work.begin().await.map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+    let drafts = newsletters_repository
+        .get_newsletter_drafts(&mut unit_of_work)
+        .await
+        .map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+    unit_of_work.commit().await.map_err(
+        actix_web::error::ErrorInternalServerError,
+    )?;
+
+    let mut rows_html = String::new();
+
+    for draft in drafts {
+        writeln!(
+            rows_html,
+            r#"<tr>
+<td><a href="/admin/newsletters/drafts/{id}">{title}</a></td>
+<td>{updated_at}</td>
+</tr>"#,
+            id = draft.newsletter_issue_id,
+            title = escape_html(&draft.title),
+            updated_at =
+                draft.updated_at.format("%Y-%m-%d %H:%M UTC"),
+        )
+        .expect(
+            "Write to string should have been successful.",
+        );
+    }
+
+    let new_draft_form_html = draft_form_html(
+        "/admin/newsletters/drafts",
+        "",
+        "",
+        "",
+        "",
+        "Create draft",
+    );
+
+    HttpResponse::Ok()
+    .content_type(ContentType::html())
+    .body(format!(
+r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta http-equiv="content-type" content="text/html; charset=utf-8">
+<title>Newsletter drafts</title>
+</head>
+<body>
+{notification_html}
+<table>
+<tr><th>Title</th><th>Last edited</th></tr>
+{rows_html}
+</table>
+<h2>New draft</h2>
+{new_draft_form_html}
+<p><a href="/admin/dashboard">&lt;- Back</a></p>
+</body>
+</html>"#))
+    .pipe(Ok)
+}
+
+pub async fn create_newsletter_draft<
+    B: BeginUnitOfWork,
+    N: NewslettersRepository<UnitOfWork = B::UnitOfWork>,
+>(
+    _user_id: web::ReqData<UserId>,
+    form: web::Form<DraftFormData<'_>>,
+    begin_unit_of_work: Inject<B>,
+    newsletters_repository: Inject<N>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let mut unit_of_work =
+        begin_unit_of_work.begin().await.map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+    let (title, content) = form.into_inner().into_parts();
+
+    let newsletter_issue_id = newsletters_repository
+        .insert_newsletter_draft(
+            &mut unit_of_work,
+            &title,
+            &content.text,
+            &content.html,
+            content.markdown.as_deref(),
+        )
+        .await
+        .map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+    unit_of_work.commit().await.map_err(
+        actix_web::error::ErrorInternalServerError,
+    )?;
+
+    actix_web_flash_messages::FlashMessage::info(
+        DRAFT_SAVED_MESSAGE,
+    )
+    .send();
+
+    see_other_response(&format!(
+        "/admin/newsletters/drafts/{newsletter_issue_id}"
+    ))
+    .pipe(Ok)
+}
+
+pub async fn get_newsletter_draft<
+    B: BeginUnitOfWork,
+    N: NewslettersRepository<UnitOfWork = B::UnitOfWork>,
+>(
+    _user_id: web::ReqData<UserId>,
+    flash_messages: actix_web_flash_messages::IncomingFlashMessages,
+    newsletter_issue_id: web::Path<Uuid>,
+    begin_unit_of_work: Inject<B>,
+    newsletters_repository: Inject<N>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let notification_html =
+        notifications_html(&flash_messages);
+
+    let mut unit_of_work =
+        begin_unit_of_work.begin().await.map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+    let draft = newsletters_repository
+        .get_newsletter_draft(
+            &mut unit_of_work,
+            newsletter_issue_id.into_inner(),
+        )
+        .await
+        .map_err(draft_error)?;
+
+    unit_of_work.commit().await.map_err(
+        actix_web::error::ErrorInternalServerError,
+    )?;
+
+    let id = draft.newsletter_issue_id;
+
+    let edit_form_html = draft_form_html(
+        &format!("/admin/newsletters/drafts/{id}"),
+        &draft.title,
+        &draft.text_content,
+        &draft.html_content,
+        draft
+            .markdown_content
+            .as_deref()
+            .unwrap_or_default(),
+        "Save draft",
+    );
+
+    let idempotency_key = Uuid::new_v4();
+
+    HttpResponse::Ok()
+    .content_type(ContentType::html())
+    .body(format!(
+r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta http-equiv="content-type" content="text/html; charset=utf-8">
+<title>Edit draft</title>
+</head>
+<body>
+{notification_html}
+{edit_form_html}
+<p>
+Preview:
+<a href="/admin/newsletters/drafts/{id}/preview/html">HTML</a>
+<a href="/admin/newsletters/drafts/{id}/preview/text">Text</a>
+</p>
+<form action="/admin/newsletters/drafts/{id}/publish" method="post">
+<label>Schedule for (UTC, leave empty to send now)
+<input
+type="datetime-local"
+name="scheduled_for"
+>
+</label>
+<input hidden type="text" name="idempotency_key" value="{idempotency_key}">
+<button type="submit">Publish</button>
+</form>
+<form action="/admin/newsletters/drafts/{id}/test" method="post">
+<label>Test recipients (comma separated, leave empty to use your own address)
+<input
+type="text"
+placeholder="Enter test email addresses"
+name="test_recipients"
+>
+</label>
+<button type="submit">Send test</button>
+</form>
+<form action="/admin/newsletters/drafts/{id}/delete" method="post">
+<button type="submit">Delete draft</button>
+</form>
+<p><a href="/admin/newsletters/drafts">&lt;- Back</a></p>
+</body>
+</html>"#))
+    .pipe(Ok)
+}
+
+pub async fn update_newsletter_draft<
+    B: BeginUnitOfWork,
+    N: NewslettersRepository<UnitOfWork = B::UnitOfWork>,
+>(
+    _user_id: web::ReqData<UserId>,
+    newsletter_issue_id: web::Path<Uuid>,
+    form: web::Form<DraftFormData<'_>>,
+    begin_unit_of_work: Inject<B>,
+    newsletters_repository: Inject<N>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let newsletter_issue_id =
+        newsletter_issue_id.into_inner();
+
+    let mut unit_of_work =
+        begin_unit_of_work.begin().await.map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+    let (title, content) = form.into_inner().into_parts();
+
+    newsletters_repository
+        .update_newsletter_draft(
+            &mut unit_of_work,
+            newsletter_issue_id,
+            &title,
+            &content.text,
+            &content.html,
+            content.markdown.as_deref(),
+        )
+        .await
+        .map_err(draft_error)?;
+
+    unit_of_work.commit().await.map_err(
+        actix_web::error::ErrorInternalServerError,
+    )?;
+
+    actix_web_flash_messages::FlashMessage::info(
+        DRAFT_SAVED_MESSAGE,
+    )
+    .send();
+
+    see_other_response(&format!(
+        "/admin/newsletters/drafts/{newsletter_issue_id}"
+    ))
+    .pipe(Ok)
+}
+
+pub async fn delete_newsletter_draft<
+    B: BeginUnitOfWork,
+    N: NewslettersRepository<UnitOfWork = B::UnitOfWork>,
+>(
+    _user_id: web::ReqData<UserId>,
+    newsletter_issue_id: web::Path<Uuid>,
+    begin_unit_of_work: Inject<B>,
+    newsletters_repository: Inject<N>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let mut unit_of_work =
+        begin_unit_of_work.begin().await.map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+    newsletters_repository
+        .delete_newsletter_draft(
+            &mut unit_of_work,
+            newsletter_issue_id.into_inner(),
+        )
+        .await
+        .map_err(draft_error)?;
+
+    unit_of_work.commit().await.map_err(
+        actix_web::error::ErrorInternalServerError,
+    )?;
+
+    actix_web_flash_messages::FlashMessage::info(
+        DRAFT_DELETED_MESSAGE,
+    )
+    .send();
+
+    see_other_response("/admin/newsletters/drafts").pipe(Ok)
+}
+
+#[derive(Debug, Clone, Copy, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PreviewFormat {
+    Html,
+    Text,
+}
+
+/// Renders the draft body as-is, which is what the delivery worker
+/// hands to `EmailClient::send_email`.
+pub async fn preview_newsletter_draft<
+    B: BeginUnitOfWork,
+    N: NewslettersRepository<UnitOfWork = B::UnitOfWork>,
+>(
+    _user_id: web::ReqData<UserId>,
+    path: web::Path<(Uuid, PreviewFormat)>,
+    begin_unit_of_work: Inject<B>,
+    newsletters_repository: Inject<N>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let (newsletter_issue_id, format) = path.into_inner();
+
+    let mut unit_of_work =
+        begin_unit_of_work.begin().await.map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+    let draft = newsletters_repository
+        .get_newsletter_draft(
+            &mut unit_of_work,
+            newsletter_issue_id,
+        )
+        .await
+        .map_err(draft_error)?;
+
+    unit_of_work.commit().await.map_err(
+        actix_web::error::ErrorInternalServerError,
+    )?;
+
+    let confirm_date =
+        chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let context = TemplateContext {
+        subscriber_name: PREVIEW_SUBSCRIBER_NAME,
+        unsubscribe_url: "#",
+        confirm_date: &confirm_date,
+    };
+
+    // Drafts are only validated on publish, so show invalid ones verbatim.
+    let render = |content: &str, html: bool| {
+        match NewsletterTemplate::parse(content) {
+            Ok(t) if html => t.render_html(&context),
+            Ok(t) => t.render_text(&context),
+            Err(_) => content.to_owned(),
+        }
+    };
+
+    match format {
+        PreviewFormat::Html => HttpResponse::Ok()
+            .content_type(ContentType::html())
+            // Served from the admin origin, so never unsanitised.
+            .body(String::from(NewsletterHtml::sanitise(
+                &render(&draft.html_content, true),
+            ))),
+        PreviewFormat::Text => HttpResponse::Ok()
+            .content_type(ContentType::plaintext())
+            .body(render(&draft.text_content, false)),
+    }
+    .pipe(Ok)
+}
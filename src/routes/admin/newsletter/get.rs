@@ -4,7 +4,7 @@ use actix_web::{
 use std::fmt::Write;
 use uuid::Uuid;
 
-use crate::authentication::UserId;
+use crate::{authentication::UserId, utils::escape_html};
 
 pub async fn get_newsletter_form(
     _user_id: web::ReqData<UserId>,
@@ -22,7 +22,7 @@ pub async fn get_newsletter_form(
             writeln!(
             notification_html,
             "<p><i>{}</i></p>",
-            m.content()
+            escape_html(m.content())
         )
         .expect(
             "Write to string should have been successful.",
@@ -65,8 +65,28 @@ placeholder="Enter newsletter content (Html)"
 name="content_html"
 >
 <br>
+<label>Newsletter Content (Markdown, replaces Text & Html)
+<textarea
+placeholder="Enter newsletter content (Markdown)"
+name="content_markdown"
+></textarea>
+<br>
+<label>Schedule for (UTC, leave empty to send now)
+<input
+type="datetime-local"
+name="scheduled_for"
+>
+<br>
 <input hidden type="text" name="idempotency_key" value="{idempotency_key}">
 <button type="submit">Send newsletter</button>
+<br>
+<label>Test recipients (comma separated, leave empty to use your own address)
+<input
+type="text"
+placeholder="Enter test email addresses"
+name="test_recipients"
+>
+<button type="submit" formaction="/admin/newsletters/test">Send test</button>
 </form>
 <p><a href="/admin/dashboard">&lt;- Back</a></p>
 </body>
@@ -0,0 +1,492 @@
+use std::{collections::HashMap, fmt::Write};
+
+use actix_web::{
+    HttpResponse, http::header::ContentType, web,
+};
+use const_format::concatcp;
+use uuid::Uuid;
+
+use crate::{
+    authentication::UserId,
+    database::transactional::{
+        issue_delivery_queue::{
+            IssueDeliveryProgress,
+            IssueDeliveryQueueRepository,
+            IssueDeliveryStatus,
+            UpdateIssueDeliveryStatusError,
+        },
+        newsletters::{
+            NewslettersRepository,
+            PublicNewsletterIssueError,
+            PublishedNewsletterIssue,
+        },
+        unit_of_work::{BeginUnitOfWork, UnitOfWork as _},
+    },
+    dependency_injection::app_state::Inject,
+    utils::{Pipe, escape_html, see_other_response},
+};
+
+pub const MADE_PRIVATE_MESSAGE: &str =
+    "Newsletter has been removed from the public archive.";
+
+pub const MADE_PUBLIC_MESSAGE: &str =
+    "Newsletter has been added to the public archive.";
+
+pub const ISSUE_NOT_FOUND_MESSAGE: &str =
+    "Newsletter has not been published.";
+
+pub const DELIVERY_PAUSED_MESSAGE: &str =
+    "Delivery of the newsletter has been paused.";
+
+pub const DELIVERY_RESUMED_MESSAGE: &str =
+    "Delivery of the newsletter has been resumed.";
+
+pub const DELIVERY_CANCELLED_MESSAGE: &str = "Remaining deliveries of the newsletter have been cancelled.";
+
+pub const DELIVERY_NOT_CHANGEABLE_MESSAGE: &str = "Delivery of the newsletter cannot be changed this way anymore.";
+
+#[derive(serde::Deserialize)]
+pub struct VisibilityFormData {
+    is_private: bool,
+}
+
+#[derive(
+    Clone, Copy, serde::Deserialize, derive_more::Display,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryAction {
+    #[display("pause")]
+    Pause,
+    #[display("resume")]
+    Resume,
+    #[display("cancel")]
+    Cancel,
+}
+
+/// How often the issue list polls the progress of deliveries.
+const PROGRESS_REFRESH_INTERVAL_MS: u32 = 2000;
+
+/// Keeps the counts of the issue list up to date while the queue drains.
+const PROGRESS_SCRIPT: &str = concatcp!(
+    r#"<script>
+async function refreshProgress() {
+    const response = await fetch("/admin/issues/progress");
+    if (!response.ok) return;
+    for (const progress of await response.json()) {
+        for (const [key, value] of Object.entries(progress)) {
+            const cell = document.getElementById(`${progress.newsletter_issue_id}-${key}`);
+            if (cell) cell.textContent = value;
+        }
+    }
+}
+setInterval(refreshProgress, "#,
+    PROGRESS_REFRESH_INTERVAL_MS,
+    r");
+</script>"
+);
+
+fn progress_cells(
+    progress: &IssueDeliveryProgress,
+) -> String {
+    let IssueDeliveryProgress {
+        newsletter_issue_id,
+        delivery_status,
+        total_recipients,
+        pending,
+        retrying,
+        failed,
+        skipped,
+        cancelled,
+        dead_lettered,
+        delivered,
+    } = progress;
+
+    let mut cells_html = String::new();
+
+    for (column, value) in [
+        ("delivery_status", delivery_status.to_string()),
+        ("total_recipients", total_recipients.to_string()),
+        ("pending", pending.to_string()),
+        ("retrying", retrying.to_string()),
+        ("failed", failed.to_string()),
+        ("skipped", skipped.to_string()),
+        ("cancelled", cancelled.to_string()),
+        ("dead_lettered", dead_lettered.to_string()),
+        ("delivered", delivered.to_string()),
+    ] {
+        write!(
+            cells_html,
+            r#"<td id="{newsletter_issue_id}-{column}">{value}</td>"#
+        )
+        .expect(
+            "Write to string should have been successful.",
+        );
+    }
+
+    cells_html
+}
+
+/// Actions allowed from the current state, as long as tasks are left.
+fn delivery_actions(
+    progress: &IssueDeliveryProgress,
+) -> String {
+    let remaining = progress.pending + progress.retrying;
+
+    let actions: &[(DeliveryAction, &str)] =
+        match progress.delivery_status {
+            _ if remaining == 0 => &[],
+            IssueDeliveryStatus::Active => &[
+                (DeliveryAction::Pause, "Pause"),
+                (DeliveryAction::Cancel, "Cancel"),
+            ],
+            IssueDeliveryStatus::Paused => &[
+                (DeliveryAction::Resume, "Resume"),
+                (DeliveryAction::Cancel, "Cancel"),
+            ],
+            IssueDeliveryStatus::Cancelled => &[],
+        };
+
+    let mut actions_html = String::new();
+
+    for (action, label) in actions {
+        writeln!(
+            actions_html,
+            r#"<form action="/admin/issues/{id}/delivery/{action}" method="post">
+<button type="submit">{label}</button>
+</form>"#,
+            id = progress.newsletter_issue_id,
+        )
+        .expect(
+            "Write to string should have been successful.",
+        );
+    }
+
+    actions_html
+}
+
+fn issue_row(
+    issue: &PublishedNewsletterIssue,
+    progress: &IssueDeliveryProgress,
+) -> String {
+    let (visibility, toggle_label) = if issue.is_private {
+        ("Private", "Make public")
+    } else {
+        ("Public", "Make private")
+    };
+
+    format!(
+        r#"<tr>
+<td>{title}</td>
+<td>{published_at}</td>
+{progress_cells}
+<td>
+{delivery_actions}</td>
+<td><a href="/admin/deliveries?newsletter_issue_id={id}">Deliveries</a></td>
+<td>{visibility}</td>
+<td>
+<form action="/admin/issues/{id}/visibility" method="post">
+<input type="hidden" name="is_private" value="{is_private}">
+<button type="submit">{toggle_label}</button>
+</form>
+</td>
+</tr>
+"#,
+        title = escape_html(&issue.title),
+        published_at =
+            issue.published_at.format("%Y-%m-%d %H:%M UTC"),
+        id = issue.newsletter_issue_id,
+        is_private = !issue.is_private,
+        progress_cells = progress_cells(progress),
+        delivery_actions = delivery_actions(progress),
+    )
+}
+
+async fn get_delivery_progress_of_published_issues<
+    B: BeginUnitOfWork,
+    I: IssueDeliveryQueueRepository<
+        UnitOfWork = B::UnitOfWork,
+    >,
+>(
+    begin_unit_of_work: &B,
+    issue_delivery_queue_repository: &I,
+) -> Result<Vec<IssueDeliveryProgress>, actix_web::Error> {
+    let mut unit_of_work =
+        begin_unit_of_work.begin().await.map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+    let progress = issue_delivery_queue_repository
+        .get_delivery_progress(&mut unit_of_work)
+        .await
+        .map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+    unit_of_work.commit().await.map_err(
+        actix_web::error::ErrorInternalServerError,
+    )?;
+
+    Ok(progress)
+}
+
+pub async fn get_delivery_progress<
+    B: BeginUnitOfWork,
+    I: IssueDeliveryQueueRepository<
+        UnitOfWork = B::UnitOfWork,
+    >,
+>(
+    _user_id: web::ReqData<UserId>,
+    begin_unit_of_work: Inject<B>,
+    issue_delivery_queue_repository: Inject<I>,
+) -> Result<HttpResponse, actix_web::Error> {
+    get_delivery_progress_of_published_issues(
+        &*begin_unit_of_work,
+        &*issue_delivery_queue_repository,
+    )
+    .await?
+    .pipe_ref(|progress| HttpResponse::Ok().json(progress))
+    .pipe(Ok)
+}
+
+pub async fn get_published_newsletters<
+    B: BeginUnitOfWork,
+    I: IssueDeliveryQueueRepository<
+        UnitOfWork = B::UnitOfWork,
+    >,
+    N: NewslettersRepository<UnitOfWork = B::UnitOfWork>,
+>(
+    _user_id: web::ReqData<UserId>,
+    flash_messages: actix_web_flash_messages::IncomingFlashMessages,
+    begin_unit_of_work: Inject<B>,
+    issue_delivery_queue_repository: Inject<I>,
+    newsletters_repository: Inject<N>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let mut notification_html = String::new();
+
+    flash_messages.iter().for_each(|m| {
+        writeln!(
+            notification_html,
+            "<p><i>{}</i></p>",
+            escape_html(m.content())
+        )
+        .expect(
+            "Write to string should have been successful.",
+        );
+    });
+
+    let mut unit_of_work =
+        begin_unit_of_work.begin().await.map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+    let issues = newsletters_repository
+        .get_published_newsletter_issues(&mut unit_of_work)
+        .await
+        .map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+    unit_of_work.commit().await.map_err(
+        actix_web::error::ErrorInternalServerError,
+    )?;
+
+    let mut progress_by_issue =
+        get_delivery_progress_of_published_issues(
+            &*begin_unit_of_work,
+            &*issue_delivery_queue_repository,
+        )
+        .await?
+        .into_iter()
+        .map(|p| (p.newsletter_issue_id, p))
+        .collect::<HashMap<_, _>>();
+
+    let rows_html = issues
+        .iter()
+        .map(|issue| {
+            let progress = progress_by_issue
+                .remove(&issue.newsletter_issue_id)
+                .unwrap_or_else(|| {
+                    IssueDeliveryProgress::new(
+                        issue.newsletter_issue_id,
+                        IssueDeliveryStatus::Active,
+                        None,
+                        0,
+                        0,
+                        0,
+                        0,
+                        0,
+                        0,
+                        0,
+                    )
+                });
+
+            issue_row(issue, &progress)
+        })
+        .collect::<String>();
+
+    HttpResponse::Ok()
+    .content_type(ContentType::html())
+    .body(format!(
+r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta http-equiv="content-type" content="text/html; charset=utf-8">
+<title>Published newsletters</title>
+</head>
+<body>
+{notification_html}
+<table>
+<tr>
+<th>Title</th><th>Published at</th>
+<th>Delivery</th><th>Recipients</th><th>Pending</th><th>Retrying</th><th>Failed</th><th>Skipped</th><th>Cancelled</th><th>Dead letters</th><th>Delivered</th><th></th>
+<th>Log</th><th>Archive</th><th></th>
+</tr>
+{rows_html}
+</table>
+<p><a href="/admin/dead-letters">Dead letters</a></p>
+<p><a href="/admin/dashboard">&lt;- Back</a></p>
+{PROGRESS_SCRIPT}
+</body>
+</html>"#))
+    .pipe(Ok)
+}
+
+pub async fn set_newsletter_visibility<
+    B: BeginUnitOfWork,
+    N: NewslettersRepository<UnitOfWork = B::UnitOfWork>,
+>(
+    _user_id: web::ReqData<UserId>,
+    newsletter_issue_id: web::Path<Uuid>,
+    form: web::Form<VisibilityFormData>,
+    begin_unit_of_work: Inject<B>,
+    newsletters_repository: Inject<N>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let VisibilityFormData { is_private } =
+        form.into_inner();
+
+    let mut unit_of_work =
+        begin_unit_of_work.begin().await.map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+    match newsletters_repository
+        .set_newsletter_issue_private(
+            &mut unit_of_work,
+            newsletter_issue_id.into_inner(),
+            is_private,
+        )
+        .await
+    {
+        Ok(()) => {
+            unit_of_work.commit().await.map_err(
+                actix_web::error::ErrorInternalServerError,
+            )?;
+
+            actix_web_flash_messages::FlashMessage::info(
+                if is_private {
+                    MADE_PRIVATE_MESSAGE
+                } else {
+                    MADE_PUBLIC_MESSAGE
+                },
+            )
+            .send();
+        }
+        Err(PublicNewsletterIssueError::NotFound(_)) => {
+            actix_web_flash_messages::FlashMessage::error(
+                ISSUE_NOT_FOUND_MESSAGE,
+            )
+            .send();
+        }
+        Err(e) => {
+            return e
+                .pipe(actix_web::error::ErrorInternalServerError)
+                .pipe(Err);
+        }
+    }
+
+    see_other_response("/admin/issues").pipe(Ok)
+}
+
+pub async fn change_issue_delivery<
+    B: BeginUnitOfWork,
+    I: IssueDeliveryQueueRepository<
+        UnitOfWork = B::UnitOfWork,
+    >,
+>(
+    _user_id: web::ReqData<UserId>,
+    path: web::Path<(Uuid, DeliveryAction)>,
+    begin_unit_of_work: Inject<B>,
+    issue_delivery_queue_repository: Inject<I>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let (newsletter_issue_id, action) = path.into_inner();
+
+    let mut unit_of_work =
+        begin_unit_of_work.begin().await.map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+    let result = match action {
+        DeliveryAction::Pause => {
+            issue_delivery_queue_repository
+                .pause_issue_delivery(
+                    &mut unit_of_work,
+                    newsletter_issue_id,
+                )
+                .await
+        }
+        DeliveryAction::Resume => {
+            issue_delivery_queue_repository
+                .resume_issue_delivery(
+                    &mut unit_of_work,
+                    newsletter_issue_id,
+                )
+                .await
+        }
+        DeliveryAction::Cancel => {
+            issue_delivery_queue_repository
+                .cancel_issue_delivery(
+                    &mut unit_of_work,
+                    newsletter_issue_id,
+                )
+                .await
+        }
+    };
+
+    match result {
+        Ok(()) => {
+            unit_of_work.commit().await.map_err(
+                actix_web::error::ErrorInternalServerError,
+            )?;
+
+            actix_web_flash_messages::FlashMessage::info(
+                match action {
+                    DeliveryAction::Pause => {
+                        DELIVERY_PAUSED_MESSAGE
+                    }
+                    DeliveryAction::Resume => {
+                        DELIVERY_RESUMED_MESSAGE
+                    }
+                    DeliveryAction::Cancel => {
+                        DELIVERY_CANCELLED_MESSAGE
+                    }
+                },
+            )
+            .send();
+        }
+        Err(UpdateIssueDeliveryStatusError::NotFound(
+            _,
+        )) => {
+            actix_web_flash_messages::FlashMessage::error(
+                DELIVERY_NOT_CHANGEABLE_MESSAGE,
+            )
+            .send();
+        }
+        Err(e) => {
+            return e
+                .pipe(actix_web::error::ErrorInternalServerError)
+                .pipe(Err);
+        }
+    }
+
+    see_other_response("/admin/issues").pipe(Ok)
+}
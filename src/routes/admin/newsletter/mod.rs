@@ -1,7 +1,48 @@
+mod dead_letters;
+mod deliveries;
+mod drafts;
 mod get;
+mod issues;
 mod post;
+mod scheduled;
+mod test_send;
 
+pub use dead_letters::{
+    DEAD_LETTER_ALREADY_QUEUED_MESSAGE,
+    DEAD_LETTER_NOT_FOUND_MESSAGE,
+    DEAD_LETTER_REQUEUED_MESSAGE, get_dead_letters,
+    requeue_dead_letter,
+};
+pub use deliveries::get_email_deliveries;
+pub use drafts::{
+    DRAFT_DELETED_MESSAGE, DRAFT_SAVED_MESSAGE,
+    create_newsletter_draft, delete_newsletter_draft,
+    get_newsletter_draft, get_newsletter_drafts,
+    preview_newsletter_draft, update_newsletter_draft,
+};
 pub use get::get_newsletter_form;
+pub use issues::{
+    DELIVERY_CANCELLED_MESSAGE,
+    DELIVERY_NOT_CHANGEABLE_MESSAGE,
+    DELIVERY_PAUSED_MESSAGE, DELIVERY_RESUMED_MESSAGE,
+    ISSUE_NOT_FOUND_MESSAGE, MADE_PRIVATE_MESSAGE,
+    MADE_PUBLIC_MESSAGE, change_issue_delivery,
+    get_delivery_progress, get_published_newsletters,
+    set_newsletter_visibility,
+};
 pub use post::{
-    ERROR_MESSAGE, SUCCESS_MESSAGE, publish_newsletter,
+    ERROR_MESSAGE, INVALID_HTML_MESSAGE,
+    INVALID_TEMPLATE_MESSAGE, SCHEDULED_MESSAGE,
+    SUCCESS_MESSAGE, publish_newsletter,
+    publish_newsletter_draft,
+};
+pub use scheduled::{
+    CANCELLED_MESSAGE, NOT_CANCELLABLE_MESSAGE,
+    cancel_scheduled_newsletter, get_scheduled_newsletters,
+};
+pub use test_send::{
+    INVALID_TEST_RECIPIENT_MESSAGE,
+    NO_TEST_RECIPIENTS_MESSAGE, TEST_FAILED_MESSAGE,
+    TEST_SENT_MESSAGE, TEST_SUBJECT_PREFIX,
+    send_test_newsletter, send_test_newsletter_draft,
 };
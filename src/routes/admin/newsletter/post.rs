@@ -11,16 +11,21 @@ use crate::{
         unit_of_work::{BeginUnitOfWork, UnitOfWork},
     },
     dependency_injection::app_state::Inject,
+    domain::{
+        NewsletterHtml, NewsletterHtmlValidationError,
+        NewsletterMarkdown, NewsletterTemplate,
+        NewsletterTemplateParseError,
+    },
     hkt::SharedPointerHKT,
     idempotency::IdempotencyKey,
     startup,
     utils::{Pipe, see_other_response},
 };
 use actix_web::{
-    HttpResponse, error::InternalError, http::StatusCode,
-    web,
+    Either, HttpResponse, error::InternalError,
+    http::StatusCode, web,
 };
-use const_format::formatcp;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use nameof::name_of;
 use std::{
     borrow::Cow,
@@ -30,38 +35,111 @@ use uuid::Uuid;
 
 #[derive(Debug, serde::Deserialize)]
 pub struct BodyData<'a> {
-    title: Cow<'a, str>,
-    content: Content<'a>,
+    issue: IssueSource<'a>,
     idempotency_key: Cow<'a, str>,
+    scheduled_for: Option<Cow<'a, str>>,
+}
+
+/// Issues are either written & published at once, or published from a draft.
+#[derive(Debug, serde::Deserialize)]
+pub enum IssueSource<'a> {
+    New {
+        title: Cow<'a, str>,
+        content: Content<'a>,
+    },
+    Draft(Uuid),
 }
 
 #[derive(Debug, serde::Deserialize)]
 pub struct Content<'a> {
-    html: Cow<'a, str>,
-    text: Cow<'a, str>,
+    pub(super) html: Cow<'a, str>,
+    pub(super) text: Cow<'a, str>,
+    pub(super) markdown: Option<Cow<'a, str>>,
+}
+
+impl<'a> Content<'a> {
+    /// Markdown, when given, replaces both the html & text versions.
+    pub(super) fn from_form(
+        html: Cow<'a, str>,
+        text: Cow<'a, str>,
+        markdown: Option<Cow<'a, str>>,
+    ) -> Self {
+        match non_empty(markdown) {
+            Some(markdown) => {
+                let NewsletterMarkdown { html, text } =
+                    NewsletterMarkdown::render(&markdown);
+                Self {
+                    html: html.into(),
+                    text: text.into(),
+                    markdown: Some(markdown),
+                }
+            }
+            None => Self {
+                html,
+                text,
+                markdown: None,
+            },
+        }
+    }
 }
 
 #[derive(Debug, serde::Deserialize)]
 pub struct FormData<'a> {
     title: Cow<'a, str>,
+    #[serde(default)]
     content_html: Cow<'a, str>,
+    #[serde(default)]
     content_text: Cow<'a, str>,
+    content_markdown: Option<Cow<'a, str>>,
     idempotency_key: Cow<'a, str>,
+    scheduled_for: Option<Cow<'a, str>>,
 }
 
 impl<'a> From<FormData<'a>> for BodyData<'a> {
     fn from(value: FormData<'a>) -> Self {
         BodyData {
-            title: value.title,
-            content: Content {
-                html: value.content_html,
-                text: value.content_text,
+            issue: IssueSource::New {
+                title: value.title,
+                content: Content::from_form(
+                    value.content_html,
+                    value.content_text,
+                    value.content_markdown,
+                ),
             },
             idempotency_key: value.idempotency_key,
+            scheduled_for: non_empty(value.scheduled_for),
         }
     }
 }
 
+/// Browsers are redirected with flash messages, while json clients get
+/// validation errors in the response body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BodyFormat {
+    Form,
+    Json,
+}
+
+/// Validation errors returned to json clients.
+#[derive(Debug, serde::Serialize)]
+struct ValidationErrorBody {
+    message: &'static str,
+    errors: Vec<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct PublishDraftFormData<'a> {
+    idempotency_key: Cow<'a, str>,
+    scheduled_for: Option<Cow<'a, str>>,
+}
+
+// Empty inputs, e.g. `datetime-local`, are still submitted.
+pub(super) fn non_empty(
+    value: Option<Cow<'_, str>>,
+) -> Option<Cow<'_, str>> {
+    value.filter(|i| !i.trim().is_empty())
+}
+
 #[tracing::instrument(
     name = "Publishing Newsletter To Confirmed Subscribers",
     skip(
@@ -87,9 +165,19 @@ pub async fn publish_newsletter<
     issue_delivery_queue_repository: Inject<I>,
     newsletters_repository: Inject<N>,
     persistence_repository: Inject<Pr>,
-    body: web::Form<FormData<'_>>,
+    body: Either<
+        web::Json<BodyData<'_>>,
+        web::Form<FormData<'_>>,
+    >,
     user_id: web::ReqData<UserId>,
 ) -> Result<HttpResponse, actix_web::Error> {
+    let (body, format) = match body {
+        Either::Left(body) => (body.0, BodyFormat::Json),
+        Either::Right(body) => {
+            (body.0.into(), BodyFormat::Form)
+        }
+    };
+
     publish_newsletter_with_pointer::<
         startup::GlobalSharedPointerType,
         _,
@@ -103,7 +191,69 @@ pub async fn publish_newsletter<
         issue_delivery_queue_repository,
         newsletters_repository,
         persistence_repository,
-        body.0.into(),
+        body,
+        format,
+        user_id.into_inner(),
+    )
+    .await
+}
+
+#[tracing::instrument(
+    name = "Publishing Newsletter Draft To Confirmed Subscribers",
+    skip(
+        authentication_repository,
+        begin_unit_of_work,
+        issue_delivery_queue_repository,
+        newsletters_repository,
+        persistence_repository,
+        body
+    )
+)]
+#[allow(clippy::too_many_arguments)]
+pub async fn publish_newsletter_draft<
+    A: AuthenticationRepository,
+    B: BeginUnitOfWork,
+    I: IssueDeliveryQueueRepository<
+        UnitOfWork = B::UnitOfWork,
+    >,
+    N: NewslettersRepository<UnitOfWork = B::UnitOfWork>,
+    Pr: PersistenceRepository<UnitOfWork = B::UnitOfWork>,
+>(
+    authentication_repository: Inject<A>,
+    begin_unit_of_work: Inject<B>,
+    issue_delivery_queue_repository: Inject<I>,
+    newsletters_repository: Inject<N>,
+    persistence_repository: Inject<Pr>,
+    newsletter_issue_id: web::Path<Uuid>,
+    body: web::Form<PublishDraftFormData<'_>>,
+    user_id: web::ReqData<UserId>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let PublishDraftFormData {
+        idempotency_key,
+        scheduled_for,
+    } = body.0;
+
+    publish_newsletter_with_pointer::<
+        startup::GlobalSharedPointerType,
+        _,
+        _,
+        _,
+        _,
+        _,
+    >(
+        authentication_repository,
+        begin_unit_of_work,
+        issue_delivery_queue_repository,
+        newsletters_repository,
+        persistence_repository,
+        BodyData {
+            issue: IssueSource::Draft(
+                newsletter_issue_id.into_inner(),
+            ),
+            idempotency_key,
+            scheduled_for: non_empty(scheduled_for),
+        },
+        BodyFormat::Form,
         user_id.into_inner(),
     )
     .await
@@ -207,6 +357,7 @@ async fn persist_response<
         body
     )
 )]
+#[allow(clippy::too_many_arguments)]
 async fn publish_newsletter_with_pointer<
     P: SharedPointerHKT,
     A: AuthenticationRepository,
@@ -223,6 +374,7 @@ async fn publish_newsletter_with_pointer<
     newsletters_repository: Inject<N>,
     persistence_repository: Inject<Pr>,
     body: BodyData<'_>,
+    format: BodyFormat,
     user_id: UserId,
 ) -> Result<HttpResponse, actix_web::Error>
 where
@@ -256,15 +408,21 @@ where
     );
 
     let BodyData {
-        title,
-        content,
+        issue,
         idempotency_key,
+        scheduled_for,
     } = body;
 
     let idempotency_key =
         IdempotencyKey::try_from(idempotency_key)
             .map_err(actix_web::error::ErrorBadRequest)?;
 
+    let scheduled_for = scheduled_for
+        .as_deref()
+        .map(parse_scheduled_for)
+        .transpose()
+        .map_err(actix_web::error::ErrorBadRequest)?;
+
     let mut unit_of_work = begin_unit_of_work
         .begin()
         .await
@@ -284,15 +442,17 @@ where
         return restore_saved_response(saved_response);
     }
 
-    let issue_id = newsletters_repository
-        .insert_newsletter_issue(
-            &mut unit_of_work,
-            &title,
-            &content.text,
-            &content.html,
-        )
-        .await
-        .map_err(redirect_to_self_with_err)?;
+    let issue_id = insert_or_publish_issue(
+        &*newsletters_repository,
+        &mut unit_of_work,
+        issue,
+        scheduled_for,
+    )
+    .await
+    .map_err(|e| match format {
+        BodyFormat::Form => reject_invalid_issue(e),
+        BodyFormat::Json => reject_invalid_issue_as_json(e),
+    })?;
 
     issue_delivery_queue_repository
         .enqueue_delivery_tasks(&mut unit_of_work, issue_id)
@@ -313,29 +473,236 @@ where
         .await
         .map_err(redirect_to_self_with_err)?;
 
-    success().send();
+    if scheduled_for.is_some() {
+        scheduled().send();
+    } else {
+        success().send();
+    }
 
     headers_response.pipe(Ok)
 }
 
+async fn insert_or_publish_issue<
+    N: NewslettersRepository,
+>(
+    newsletters_repository: &N,
+    unit_of_work: &mut N::UnitOfWork,
+    issue: IssueSource<'_>,
+    scheduled_for: Option<DateTime<Utc>>,
+) -> Result<Uuid, PublishIssueError> {
+    match issue {
+        IssueSource::New { title, content } => {
+            let html = validate_content(
+                &content.html,
+                &content.text,
+            )?;
+
+            newsletters_repository
+                .insert_newsletter_issue(
+                    unit_of_work,
+                    &title,
+                    &content.text,
+                    html.as_ref(),
+                    content.markdown.as_deref(),
+                    scheduled_for,
+                )
+                .await
+                .map_err(eyre::Report::new)?
+                .pipe(Ok)
+        }
+        IssueSource::Draft(issue_id) => {
+            let draft = newsletters_repository
+                .get_newsletter_draft(
+                    unit_of_work,
+                    issue_id,
+                )
+                .await
+                .map_err(eyre::Report::new)?;
+
+            let html = validate_content(
+                &draft.html_content,
+                &draft.text_content,
+            )?;
+
+            // Published content is the sanitised one.
+            newsletters_repository
+                .update_newsletter_draft(
+                    unit_of_work,
+                    issue_id,
+                    &draft.title,
+                    &draft.text_content,
+                    html.as_ref(),
+                    draft.markdown_content.as_deref(),
+                )
+                .await
+                .map_err(eyre::Report::new)?;
+
+            newsletters_repository
+                .publish_newsletter_draft(
+                    unit_of_work,
+                    issue_id,
+                    scheduled_for,
+                )
+                .await
+                .map_err(eyre::Report::new)?;
+
+            Ok(issue_id)
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(super) enum PublishIssueError {
+    #[error(transparent)]
+    InvalidTemplate(#[from] NewsletterTemplateParseError),
+    #[error(transparent)]
+    InvalidHtml(#[from] NewsletterHtmlValidationError),
+    #[error(transparent)]
+    Unexpected(#[from] eyre::Report),
+}
+
+/// Placeholders are rendered per recipient by the delivery worker, so they
+/// must be checked before anything is enqueued, same as the html.
+pub(super) fn validate_content(
+    html: &str,
+    text: &str,
+) -> Result<NewsletterHtml, PublishIssueError> {
+    NewsletterTemplate::parse(html)?;
+    NewsletterTemplate::parse(text)?;
+    NewsletterHtml::parse(html)?.pipe(Ok)
+}
+
 pub const SUCCESS_MESSAGE: &str = "Newsletter has been successfully enqueued & \
             will be sent to subscribers shortly.";
 
+/// Accepts RFC 3339 or the `datetime-local` input format, read as UTC.
+fn parse_scheduled_for(
+    scheduled_for: &str,
+) -> Result<DateTime<Utc>, chrono::ParseError> {
+    DateTime::parse_from_rfc3339(scheduled_for)
+        .map(|i| i.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(
+                scheduled_for,
+                "%Y-%m-%dT%H:%M",
+            )
+            .or_else(|_| {
+                NaiveDateTime::parse_from_str(
+                    scheduled_for,
+                    "%Y-%m-%dT%H:%M:%S",
+                )
+            })
+            .map(|i| i.and_utc())
+        })
+}
+
+pub const SCHEDULED_MESSAGE: &str = "Newsletter has been successfully scheduled & \
+            will be sent to subscribers at the chosen time.";
+
 pub const ERROR_MESSAGE: &str = "One or more errors occurred trying to post the newsletter.";
 
+pub const INVALID_TEMPLATE_MESSAGE: &str =
+    "Newsletter has an invalid template:";
+
+pub const INVALID_HTML_MESSAGE: &str = "Newsletter html contains content that is not allowed in emails:";
+
 fn success() -> actix_web_flash_messages::FlashMessage {
     actix_web_flash_messages::FlashMessage::info(
         SUCCESS_MESSAGE,
     )
 }
 
+fn scheduled() -> actix_web_flash_messages::FlashMessage {
+    actix_web_flash_messages::FlashMessage::info(
+        SCHEDULED_MESSAGE,
+    )
+}
+
+fn reject_invalid_issue(
+    cause: PublishIssueError,
+) -> actix_web::Error {
+    reject_invalid_issue_to(cause, "/admin/newsletters")
+}
+
+/// Lists what is wrong with the issue, one flash message per html violation.
+pub(super) fn reject_invalid_issue_to(
+    cause: PublishIssueError,
+    location: &str,
+) -> actix_web::Error {
+    match &cause {
+        PublishIssueError::InvalidTemplate(e) => {
+            actix_web_flash_messages::FlashMessage::error(
+                format!("{INVALID_TEMPLATE_MESSAGE} {e}"),
+            )
+            .send();
+        }
+        PublishIssueError::InvalidHtml(e) => {
+            actix_web_flash_messages::FlashMessage::error(
+                INVALID_HTML_MESSAGE,
+            )
+            .send();
+            for violation in &e.violations {
+                actix_web_flash_messages::FlashMessage::error(
+                    violation.to_string(),
+                )
+                .send();
+            }
+        }
+        PublishIssueError::Unexpected(_) => {
+            actix_web_flash_messages::FlashMessage::error(
+                ERROR_MESSAGE,
+            )
+            .send();
+        }
+    }
+
+    see_other_response(location).pipe(|r| {
+        InternalError::from_response(cause, r).into()
+    })
+}
+
+/// Lists what is wrong with the issue in a 400 response.
+fn reject_invalid_issue_as_json(
+    cause: PublishIssueError,
+) -> actix_web::Error {
+    let body = match &cause {
+        PublishIssueError::InvalidTemplate(e) => {
+            ValidationErrorBody {
+                message: INVALID_TEMPLATE_MESSAGE,
+                errors: vec![e.to_string()],
+            }
+        }
+        PublishIssueError::InvalidHtml(e) => {
+            ValidationErrorBody {
+                message: INVALID_HTML_MESSAGE,
+                errors: e
+                    .violations
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect(),
+            }
+        }
+        PublishIssueError::Unexpected(_) => {
+            return actix_web::error::ErrorInternalServerError(
+                cause,
+            );
+        }
+    };
+
+    InternalError::from_response(
+        cause,
+        HttpResponse::BadRequest().json(body),
+    )
+    .into()
+}
+
 fn redirect_to_self_with_err<
     T: Debug + Display + 'static,
 >(
     cause: T,
 ) -> actix_web::Error {
     actix_web_flash_messages::FlashMessage::error(
-        formatcp!("<p><i>{}</i></p>", ERROR_MESSAGE),
+        ERROR_MESSAGE,
     )
     .send();
     see_other_response("/admin/newsletters").pipe(|r| {
@@ -0,0 +1,161 @@
+use std::fmt::Write;
+
+use actix_web::{
+    HttpResponse, http::header::ContentType, web,
+};
+use uuid::Uuid;
+
+use crate::{
+    authentication::UserId,
+    database::transactional::{
+        newsletters::{
+            CancelScheduledNewsletterIssueError,
+            NewslettersRepository,
+        },
+        unit_of_work::{BeginUnitOfWork, UnitOfWork as _},
+    },
+    dependency_injection::app_state::Inject,
+    utils::{Pipe, escape_html, see_other_response},
+};
+
+pub const CANCELLED_MESSAGE: &str =
+    "Scheduled newsletter has been cancelled.";
+
+pub const NOT_CANCELLABLE_MESSAGE: &str = "Newsletter is not scheduled anymore & cannot be cancelled.";
+
+pub async fn get_scheduled_newsletters<
+    B: BeginUnitOfWork,
+    N: NewslettersRepository<UnitOfWork = B::UnitOfWork>,
+>(
+    _user_id: web::ReqData<UserId>,
+    flash_messages: actix_web_flash_messages::IncomingFlashMessages,
+    begin_unit_of_work: Inject<B>,
+    newsletters_repository: Inject<N>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let mut notification_html = String::new();
+
+    flash_messages.iter().for_each(|m| {
+        writeln!(
+            notification_html,
+            "<p><i>{}</i></p>",
+            escape_html(m.content())
+        )
+        .expect(
+            "Write to string should have been successful.",
+        );
+    });
+
+    let mut unit_of_work =
+        begin_unit_of_work.begin().await.map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+    let issues = newsletters_repository
+        .get_scheduled_newsletter_issues(&mut unit_of_work)
+        .await
+        .map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+    unit_of_work.commit().await.map_err(
+        actix_web::error::ErrorInternalServerError,
+    )?;
+
+    let mut rows_html = String::new();
+
+    for issue in issues {
+        writeln!(
+            rows_html,
+            r#"<tr>
+<td>{title}</td>
+<td>{scheduled_for}</td>
+<td>
+<form action="/admin/newsletters/scheduled/{id}/cancel" method="post">
+<button type="submit">Cancel</button>
+</form>
+</td>
+</tr>"#,
+            title = escape_html(&issue.title),
+            scheduled_for = issue
+                .scheduled_for
+                .format("%Y-%m-%d %H:%M UTC"),
+            id = issue.newsletter_issue_id,
+        )
+        .expect(
+            "Write to string should have been successful.",
+        );
+    }
+
+    HttpResponse::Ok()
+    .content_type(ContentType::html())
+    .body(format!(
+r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta http-equiv="content-type" content="text/html; charset=utf-8">
+<title>Scheduled newsletters</title>
+</head>
+<body>
+{notification_html}
+<p>Scheduled newsletters are sent to the confirmed subscribers as of their scheduling, not to those subscribing since.</p>
+<table>
+<tr><th>Title</th><th>Scheduled for</th><th></th></tr>
+{rows_html}
+</table>
+<p><a href="/admin/dashboard">&lt;- Back</a></p>
+</body>
+</html>"#))
+    .pipe(Ok)
+}
+
+pub async fn cancel_scheduled_newsletter<
+    B: BeginUnitOfWork,
+    N: NewslettersRepository<UnitOfWork = B::UnitOfWork>,
+>(
+    _user_id: web::ReqData<UserId>,
+    newsletter_issue_id: web::Path<Uuid>,
+    begin_unit_of_work: Inject<B>,
+    newsletters_repository: Inject<N>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let mut unit_of_work =
+        begin_unit_of_work.begin().await.map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+    match newsletters_repository
+        .cancel_scheduled_newsletter_issue(
+            &mut unit_of_work,
+            newsletter_issue_id.into_inner(),
+        )
+        .await
+    {
+        Ok(()) => {
+            unit_of_work.commit().await.map_err(
+                actix_web::error::ErrorInternalServerError,
+            )?;
+
+            actix_web_flash_messages::FlashMessage::info(
+                CANCELLED_MESSAGE,
+            )
+            .send();
+        }
+        Err(
+            CancelScheduledNewsletterIssueError::NotFound(
+                _,
+            ),
+        ) => {
+            actix_web_flash_messages::FlashMessage::error(
+                NOT_CANCELLABLE_MESSAGE,
+            )
+            .send();
+        }
+        Err(e) => {
+            return e
+                .pipe(actix_web::error::ErrorInternalServerError)
+                .pipe(Err);
+        }
+    }
+
+    see_other_response("/admin/newsletters/scheduled")
+        .pipe(Ok)
+}
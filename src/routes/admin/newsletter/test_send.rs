@@ -0,0 +1,325 @@
+use std::borrow::Cow;
+
+use actix_web::{HttpResponse, web};
+use uuid::Uuid;
+
+use crate::{
+    authentication::UserId,
+    database::transactional::{
+        authentication::AuthenticationRepository,
+        newsletters::NewslettersRepository,
+        unit_of_work::{BeginUnitOfWork, UnitOfWork as _},
+    },
+    dependency_injection::app_state::Inject,
+    domain::{
+        NewsletterTemplate, SubscriberEmail,
+        TemplateContext,
+    },
+    email_client::{EmailClient, EmailSender},
+    hkt::{K1, RefHKT},
+    routes::admin::newsletter::{
+        drafts::{DraftFormData, PREVIEW_SUBSCRIBER_NAME},
+        post::{
+            Content, reject_invalid_issue_to,
+            validate_content,
+        },
+    },
+    startup,
+    utils::{Pipe, see_other_response},
+};
+
+pub const TEST_SENT_MESSAGE: &str =
+    "Test newsletter has been sent to:";
+
+pub const TEST_FAILED_MESSAGE: &str =
+    "Test newsletter could not be sent to:";
+
+pub const NO_TEST_RECIPIENTS_MESSAGE: &str = "No test recipients given and your account has no email address.";
+
+pub const INVALID_TEST_RECIPIENT_MESSAGE: &str =
+    "Test recipient is not a valid email address:";
+
+pub const TEST_SUBJECT_PREFIX: &str = "[Test]";
+
+#[derive(Debug, serde::Deserialize)]
+pub struct TestSendFormData<'a> {
+    #[serde(flatten)]
+    issue: DraftFormData<'a>,
+    test_recipients: Option<Cow<'a, str>>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct TestSendDraftFormData<'a> {
+    test_recipients: Option<Cow<'a, str>>,
+}
+
+type GlobalPointer = startup::GlobalSharedPointerType;
+
+type GlobalEmailClient<E> = EmailClient<GlobalPointer, E>;
+
+/// Sends the issue being written to chosen addresses only, neither enqueueing
+/// it nor recording an idempotency key.
+#[tracing::instrument(
+    name = "Sending test newsletter",
+    skip(authentication_repository, email_client, form)
+)]
+pub async fn send_test_newsletter<
+    A: AuthenticationRepository,
+    E: EmailSender,
+>(
+    user_id: web::ReqData<UserId>,
+    authentication_repository: Inject<A>,
+    email_client: web::Data<GlobalEmailClient<E>>,
+    form: web::Form<TestSendFormData<'_>>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let TestSendFormData {
+        issue,
+        test_recipients,
+    } = form.into_inner();
+    let (title, content) = issue.into_parts();
+
+    send_test_issue(
+        &*authentication_repository,
+        &email_client,
+        user_id.into_inner().into(),
+        &title,
+        &content,
+        test_recipients.as_deref(),
+        "/admin/newsletters",
+    )
+    .await
+}
+
+#[tracing::instrument(
+    name = "Sending test newsletter from draft",
+    skip(
+        authentication_repository,
+        email_client,
+        begin_unit_of_work,
+        newsletters_repository,
+        form
+    )
+)]
+pub async fn send_test_newsletter_draft<
+    A: AuthenticationRepository,
+    B: BeginUnitOfWork,
+    N: NewslettersRepository<UnitOfWork = B::UnitOfWork>,
+    E: EmailSender,
+>(
+    user_id: web::ReqData<UserId>,
+    newsletter_issue_id: web::Path<Uuid>,
+    authentication_repository: Inject<A>,
+    email_client: web::Data<GlobalEmailClient<E>>,
+    begin_unit_of_work: Inject<B>,
+    newsletters_repository: Inject<N>,
+    form: web::Form<TestSendDraftFormData<'_>>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let newsletter_issue_id =
+        newsletter_issue_id.into_inner();
+
+    let mut unit_of_work =
+        begin_unit_of_work.begin().await.map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+    let draft = newsletters_repository
+        .get_newsletter_draft(
+            &mut unit_of_work,
+            newsletter_issue_id,
+        )
+        .await
+        .map_err(actix_web::error::ErrorNotFound)?;
+
+    unit_of_work.commit().await.map_err(
+        actix_web::error::ErrorInternalServerError,
+    )?;
+
+    send_test_issue(
+        &*authentication_repository,
+        &email_client,
+        user_id.into_inner().into(),
+        &draft.title,
+        &Content {
+            html: draft.html_content.into(),
+            text: draft.text_content.into(),
+            markdown: None,
+        },
+        form.test_recipients.as_deref(),
+        &format!(
+            "/admin/newsletters/drafts/{newsletter_issue_id}"
+        ),
+    )
+    .await
+}
+
+/// Addresses are separated by commas or whitespace, falling back to the
+/// address of the writer. Errors are messages for the writer.
+async fn resolve_test_recipients<
+    A: AuthenticationRepository,
+>(
+    authentication_repository: &A,
+    user_id: Uuid,
+    test_recipients: &str,
+) -> Result<
+    Result<Vec<SubscriberEmail<GlobalPointer>>, String>,
+    actix_web::Error,
+> {
+    let recipients = test_recipients
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|i| !i.is_empty())
+        .map(|i| {
+            SubscriberEmail::try_from(i).map_err(|_| {
+                format!(
+                    "{INVALID_TEST_RECIPIENT_MESSAGE} {i}"
+                )
+            })
+        })
+        .collect::<Result<Vec<_>, _>>();
+
+    if !matches!(&recipients, Ok(r) if r.is_empty()) {
+        return Ok(recipients);
+    }
+
+    authentication_repository
+        .get_email_from_user_id(user_id)
+        .await
+        .map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?
+        .and_then(|i| SubscriberEmail::try_from(i).ok())
+        .map(|i| vec![i])
+        .ok_or_else(|| {
+            NO_TEST_RECIPIENTS_MESSAGE.to_owned()
+        })
+        .pipe(Ok)
+}
+
+/// Renders placeholders the same way as draft previews.
+fn render_test_issue(
+    title: &str,
+    html: &str,
+    text: &str,
+) -> (
+    K1<GlobalPointer, str>,
+    K1<GlobalPointer, str>,
+    K1<GlobalPointer, str>,
+) {
+    let confirm_date =
+        chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let template_context = TemplateContext {
+        subscriber_name: PREVIEW_SUBSCRIBER_NAME,
+        unsubscribe_url: "#",
+        confirm_date: &confirm_date,
+    };
+
+    // Validated beforehand, so parsing does not fail.
+    let parse = |template: &str| {
+        NewsletterTemplate::parse(template).unwrap_or_else(
+            |_| NewsletterTemplate::literal(template),
+        )
+    };
+
+    (
+        GlobalPointer::from_string(format!(
+            "{TEST_SUBJECT_PREFIX} {title}"
+        )),
+        parse(html)
+            .render_html(&template_context)
+            .pipe(GlobalPointer::from_string),
+        parse(text)
+            .render_text(&template_context)
+            .pipe(GlobalPointer::from_string),
+    )
+}
+
+async fn send_test_issue<
+    A: AuthenticationRepository,
+    E: EmailSender,
+>(
+    authentication_repository: &A,
+    email_client: &GlobalEmailClient<E>,
+    user_id: Uuid,
+    title: &str,
+    content: &Content<'_>,
+    test_recipients: Option<&str>,
+    location: &str,
+) -> Result<HttpResponse, actix_web::Error> {
+    let html =
+        validate_content(&content.html, &content.text)
+            .map_err(|e| {
+                reject_invalid_issue_to(e, location)
+            })?;
+
+    let recipients = match resolve_test_recipients(
+        authentication_repository,
+        user_id,
+        test_recipients.unwrap_or_default(),
+    )
+    .await?
+    {
+        Ok(recipients) => recipients,
+        Err(message) => {
+            actix_web_flash_messages::FlashMessage::error(
+                message,
+            )
+            .send();
+            return see_other_response(location).pipe(Ok);
+        }
+    };
+
+    let (subject, html_content, text_content) =
+        render_test_issue(
+            title,
+            html.as_ref(),
+            &content.text,
+        );
+
+    let mut sent = Vec::new();
+    let mut failed = Vec::new();
+
+    for recipient in recipients {
+        let address = recipient.as_ref().to_string();
+
+        match email_client
+            .send_email(
+                recipient,
+                subject.clone(),
+                html_content.clone(),
+                text_content.clone(),
+            )
+            .await
+        {
+            Ok(()) => sent.push(address),
+            Err(e) => {
+                tracing::warn!(
+                    error.cause_chain = ?e,
+                    error.message = %e,
+                    "Failed to send test newsletter to {address}."
+                );
+                failed.push(address);
+            }
+        }
+    }
+
+    if !sent.is_empty() {
+        actix_web_flash_messages::FlashMessage::info(
+            format!(
+                "{TEST_SENT_MESSAGE} {}",
+                sent.join(", ")
+            ),
+        )
+        .send();
+    }
+
+    if !failed.is_empty() {
+        actix_web_flash_messages::FlashMessage::error(
+            format!(
+                "{TEST_FAILED_MESSAGE} {}",
+                failed.join(", ")
+            ),
+        )
+        .send();
+    }
+
+    see_other_response(location).pipe(Ok)
+}
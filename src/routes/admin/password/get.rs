@@ -3,7 +3,7 @@ use actix_web::{
 };
 use std::fmt::Write;
 
-use crate::authentication::UserId;
+use crate::{authentication::UserId, utils::escape_html};
 
 pub async fn get_reset_password_form(
     _user_id: web::ReqData<UserId>,
@@ -15,7 +15,7 @@ pub async fn get_reset_password_form(
         writeln!(
             notification_html,
             "<p><i>{}</i></p>",
-            m.content()
+            escape_html(m.content())
         )
         .expect(
             "Write to string should have been successful.",
@@ -0,0 +1,401 @@
+use std::fmt::Write;
+
+use actix_web::{
+    HttpResponse, http::header::ContentType, web,
+};
+use chrono::SecondsFormat;
+use uuid::Uuid;
+
+use crate::{
+    database::transactional::{
+        newsletters::{
+            NewslettersRepository, PublicNewsletterIssue,
+            PublicNewsletterIssueError,
+        },
+        unit_of_work::{BeginUnitOfWork, UnitOfWork as _},
+    },
+    dependency_injection::app_state::Inject,
+    domain::{
+        NewsletterHtml, NewsletterTemplate, TemplateContext,
+    },
+    startup::{self, ApplicationBaseUrl},
+    utils::{Pipe, escape_html},
+};
+
+pub const FEED_TITLE: &str = "Newsletter";
+
+const FEED_LENGTH: usize = 20;
+
+const ARCHIVE_ISSUES_PER_PAGE: usize = 50;
+
+#[derive(Debug, serde::Deserialize)]
+pub struct ArchiveQuery {
+    /// Counted from 0, the newest issues.
+    #[serde(default)]
+    page: usize,
+}
+
+/// Placeholders cannot be personalised for the public, see
+/// `issue_delivery_worker` for the per-subscriber rendering.
+const ARCHIVE_CONTEXT: TemplateContext<'static> =
+    TemplateContext {
+        subscriber_name: "reader",
+        unsubscribe_url: "#",
+        confirm_date: "",
+    };
+
+#[must_use]
+pub fn archive_issue_link(
+    base_url: &str,
+    newsletter_issue_id: Uuid,
+) -> String {
+    format!("{base_url}/archive/{newsletter_issue_id}")
+}
+
+/// Issues published before templates & sanitisation existed are rendered
+/// verbatim, then sanitised since they are served from our own origin.
+fn render_issue_html(html_content: &str) -> String {
+    let rendered =
+        match NewsletterTemplate::parse(html_content) {
+            Ok(template) => {
+                template.render_html(&ARCHIVE_CONTEXT)
+            }
+            Err(_) => html_content.to_owned(),
+        };
+
+    NewsletterHtml::sanitise(&rendered).into()
+}
+
+fn archived_issue_error(
+    e: PublicNewsletterIssueError,
+) -> actix_web::Error {
+    match e {
+        PublicNewsletterIssueError::NotFound(_) => {
+            actix_web::error::ErrorNotFound(e)
+        }
+        PublicNewsletterIssueError::Unexpected(_) => {
+            actix_web::error::ErrorInternalServerError(e)
+        }
+    }
+}
+
+async fn get_public_newsletter_issues<
+    B: BeginUnitOfWork,
+    N: NewslettersRepository<UnitOfWork = B::UnitOfWork>,
+>(
+    begin_unit_of_work: &B,
+    newsletters_repository: &N,
+    limit: usize,
+    offset: usize,
+) -> Result<Vec<PublicNewsletterIssue>, actix_web::Error> {
+    let mut unit_of_work =
+        begin_unit_of_work.begin().await.map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+    let issues = newsletters_repository
+        .get_public_newsletter_issues(
+            &mut unit_of_work,
+            limit,
+            offset,
+        )
+        .await
+        .map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+    unit_of_work.commit().await.map_err(
+        actix_web::error::ErrorInternalServerError,
+    )?;
+
+    Ok(issues)
+}
+
+/// Link to another page of the archive.
+fn page_link(page: usize, text: &str) -> String {
+    format!(r#"<a href="/archive?page={page}">{text}</a>"#)
+}
+
+/// Shown a page at a time, since anyone may request it.
+#[tracing::instrument(
+    name = "Get newsletter archive",
+    skip(begin_unit_of_work, newsletters_repository)
+)]
+pub async fn get_archive<
+    B: BeginUnitOfWork,
+    N: NewslettersRepository<UnitOfWork = B::UnitOfWork>,
+>(
+    query: web::Query<ArchiveQuery>,
+    begin_unit_of_work: Inject<B>,
+    newsletters_repository: Inject<N>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let page = query.page;
+
+    let mut issues = get_public_newsletter_issues(
+        &*begin_unit_of_work,
+        &*newsletters_repository,
+        // One more, to tell whether there is a next page.
+        ARCHIVE_ISSUES_PER_PAGE + 1,
+        page.saturating_mul(ARCHIVE_ISSUES_PER_PAGE),
+    )
+    .await?;
+
+    let has_next_page =
+        issues.len() > ARCHIVE_ISSUES_PER_PAGE;
+    issues.truncate(ARCHIVE_ISSUES_PER_PAGE);
+
+    let previous_page_html = if page == 0 {
+        String::new()
+    } else {
+        page_link(page - 1, "Newer")
+    };
+    let next_page_html = if has_next_page {
+        page_link(page + 1, "Older")
+    } else {
+        String::new()
+    };
+
+    let mut rows_html = String::new();
+
+    for issue in issues {
+        writeln!(
+            rows_html,
+            r#"<li><a href="/archive/{id}">{title}</a> ({published_at})</li>"#,
+            id = issue.newsletter_issue_id,
+            title = escape_html(&issue.title),
+            published_at =
+                issue.published_at.format("%Y-%m-%d"),
+        )
+        .expect(
+            "Write to string should have been successful.",
+        );
+    }
+
+    HttpResponse::Ok()
+    .content_type(ContentType::html())
+    .body(format!(
+r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta http-equiv="content-type" content="text/html; charset=utf-8">
+<title>{FEED_TITLE} archive</title>
+<link rel="alternate" type="application/atom+xml" href="/feed.atom">
+<link rel="alternate" type="application/rss+xml" href="/feed.rss">
+</head>
+<body>
+<h1>{FEED_TITLE} archive</h1>
+<ul>
+{rows_html}
+</ul>
+<p>{previous_page_html} {next_page_html}</p>
+</body>
+</html>"#))
+    .pipe(Ok)
+}
+
+#[tracing::instrument(
+    name = "Get archived newsletter issue",
+    skip(begin_unit_of_work, newsletters_repository)
+)]
+pub async fn get_archived_issue<
+    B: BeginUnitOfWork,
+    N: NewslettersRepository<UnitOfWork = B::UnitOfWork>,
+>(
+    newsletter_issue_id: web::Path<Uuid>,
+    begin_unit_of_work: Inject<B>,
+    newsletters_repository: Inject<N>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let mut unit_of_work =
+        begin_unit_of_work.begin().await.map_err(
+            actix_web::error::ErrorInternalServerError,
+        )?;
+
+    let issue = newsletters_repository
+        .get_public_newsletter_issue(
+            &mut unit_of_work,
+            newsletter_issue_id.into_inner(),
+        )
+        .await
+        .map_err(archived_issue_error)?;
+
+    unit_of_work.commit().await.map_err(
+        actix_web::error::ErrorInternalServerError,
+    )?;
+
+    let title = escape_html(&issue.title);
+    let published_at =
+        issue.published_at.format("%Y-%m-%d");
+    let content_html =
+        render_issue_html(&issue.html_content);
+
+    HttpResponse::Ok()
+    .content_type(ContentType::html())
+    .body(format!(
+r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta http-equiv="content-type" content="text/html; charset=utf-8">
+<title>{title}</title>
+</head>
+<body>
+<h1>{title}</h1>
+<p><i>{published_at}</i></p>
+{content_html}
+<p><a href="/archive">&lt;- Archive</a></p>
+</body>
+</html>"#))
+    .pipe(Ok)
+}
+
+#[tracing::instrument(
+    name = "Get newsletter Atom feed",
+    skip(
+        base_url,
+        begin_unit_of_work,
+        newsletters_repository
+    )
+)]
+pub async fn get_atom_feed<
+    B: BeginUnitOfWork,
+    N: NewslettersRepository<UnitOfWork = B::UnitOfWork>,
+>(
+    base_url: web::ThinData<
+        ApplicationBaseUrl<
+            startup::GlobalSharedPointerType,
+        >,
+    >,
+    begin_unit_of_work: Inject<B>,
+    newsletters_repository: Inject<N>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let base_url = &*base_url.0.0;
+
+    let issues = get_public_newsletter_issues(
+        &*begin_unit_of_work,
+        &*newsletters_repository,
+        FEED_LENGTH,
+        0,
+    )
+    .await?;
+
+    let updated = issues
+        .first()
+        .map(|i| i.published_at)
+        .unwrap_or_default()
+        .to_rfc3339_opts(SecondsFormat::Secs, true);
+
+    let mut entries_xml = String::new();
+
+    for issue in issues {
+        writeln!(
+            entries_xml,
+            r#"<entry>
+<title>{title}</title>
+<id>{link}</id>
+<link href="{link}"/>
+<updated>{published_at}</updated>
+<content type="html">{content}</content>
+</entry>"#,
+            title = escape_html(&issue.title),
+            link = archive_issue_link(
+                base_url,
+                issue.newsletter_issue_id
+            ),
+            published_at = issue
+                .published_at
+                .to_rfc3339_opts(SecondsFormat::Secs, true),
+            content = escape_html(&render_issue_html(
+                &issue.html_content
+            )),
+        )
+        .expect(
+            "Write to string should have been successful.",
+        );
+    }
+
+    HttpResponse::Ok()
+        .content_type("application/atom+xml; charset=utf-8")
+        .body(format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+<title>{FEED_TITLE}</title>
+<id>{base_url}/archive</id>
+<link href="{base_url}/archive"/>
+<link rel="self" href="{base_url}/feed.atom"/>
+<author><name>{FEED_TITLE}</name></author>
+<updated>{updated}</updated>
+{entries_xml}</feed>"#
+        ))
+        .pipe(Ok)
+}
+
+#[tracing::instrument(
+    name = "Get newsletter RSS feed",
+    skip(
+        base_url,
+        begin_unit_of_work,
+        newsletters_repository
+    )
+)]
+pub async fn get_rss_feed<
+    B: BeginUnitOfWork,
+    N: NewslettersRepository<UnitOfWork = B::UnitOfWork>,
+>(
+    base_url: web::ThinData<
+        ApplicationBaseUrl<
+            startup::GlobalSharedPointerType,
+        >,
+    >,
+    begin_unit_of_work: Inject<B>,
+    newsletters_repository: Inject<N>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let base_url = &*base_url.0.0;
+
+    let issues = get_public_newsletter_issues(
+        &*begin_unit_of_work,
+        &*newsletters_repository,
+        FEED_LENGTH,
+        0,
+    )
+    .await?;
+
+    let mut items_xml = String::new();
+
+    for issue in issues {
+        writeln!(
+            items_xml,
+            r#"<item>
+<title>{title}</title>
+<link>{link}</link>
+<guid isPermaLink="true">{link}</guid>
+<pubDate>{published_at}</pubDate>
+<description>{content}</description>
+</item>"#,
+            title = escape_html(&issue.title),
+            link = archive_issue_link(
+                base_url,
+                issue.newsletter_issue_id
+            ),
+            published_at = issue.published_at.to_rfc2822(),
+            content = escape_html(&render_issue_html(
+                &issue.html_content
+            )),
+        )
+        .expect(
+            "Write to string should have been successful.",
+        );
+    }
+
+    HttpResponse::Ok()
+        .content_type("application/rss+xml; charset=utf-8")
+        .body(format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<rss version="2.0">
+<channel>
+<title>{FEED_TITLE}</title>
+<link>{base_url}/archive</link>
+<description>{FEED_TITLE} archive</description>
+{items_xml}</channel>
+</rss>"#
+        ))
+        .pipe(Ok)
+}
@@ -0,0 +1,247 @@
+use actix_web::{
+    HttpRequest, HttpResponse,
+    http::{
+        StatusCode,
+        header::{self, HeaderValue},
+    },
+    web,
+};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use secrecy::ExposeSecret;
+use sha2::Sha256;
+
+use crate::{
+    authentication::{
+        BasicAuthCredentials, basic_authentication,
+    },
+    configuration::WebhookSettings,
+    database::transactional::{
+        subscriptions::{
+            EmailEvent, RecordEmailEventError,
+            SubscriptionsRepository, SuppressEmailError,
+            SuppressionReason,
+        },
+        unit_of_work::{BeginUnitOfWork, UnitOfWork as _},
+    },
+    dependency_injection::app_state::Inject,
+    utils::Pipe,
+};
+
+/// Postmark bounce & spam complaint webhook payload. Other record
+/// types (deliveries, opens, ...) are acknowledged & ignored.
+#[derive(Debug, serde::Deserialize)]
+#[serde(tag = "RecordType")]
+pub enum EmailEventPayload {
+    Bounce(BouncePayload),
+    SpamComplaint(BouncePayload),
+    #[serde(other)]
+    Other,
+}
+
+/// Postmark sends complaints with the same fields as bounces.
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct BouncePayload {
+    #[serde(rename = "Type")]
+    pub bounce_type: String,
+    #[serde(rename = "MessageID")]
+    pub message_id: Option<String>,
+    pub email: String,
+    pub description: Option<String>,
+    pub bounced_at: DateTime<Utc>,
+}
+
+impl EmailEventPayload {
+    fn into_email_event(self) -> Option<EmailEvent> {
+        let (record_type, payload) = match self {
+            EmailEventPayload::Bounce(payload) => {
+                ("Bounce", payload)
+            }
+            EmailEventPayload::SpamComplaint(payload) => {
+                ("SpamComplaint", payload)
+            }
+            EmailEventPayload::Other => return None,
+        };
+
+        EmailEvent {
+            record_type: record_type.to_owned(),
+            email: payload.email,
+            provider_message_id: payload.message_id,
+            bounce_type: payload.bounce_type,
+            description: payload.description,
+            occurred_at: payload.bounced_at,
+        }
+        .pipe(Some)
+    }
+}
+
+/// Soft bounces are transient, so only hard bounces & complaints
+/// suppress the address.
+fn suppression_reason(
+    email_event: &EmailEvent,
+) -> Option<SuppressionReason> {
+    match (
+        email_event.record_type.as_str(),
+        email_event.bounce_type.as_str(),
+    ) {
+        ("SpamComplaint", _) => {
+            Some(SuppressionReason::Complained)
+        }
+        ("Bounce", "HardBounce") => {
+            Some(SuppressionReason::Bounced)
+        }
+        _ => None,
+    }
+}
+
+#[tracing::instrument(
+    name = "Receive email event",
+    skip(
+        request,
+        webhook,
+        payload,
+        begin_unit_of_work,
+        subscriptions_repository
+    )
+)]
+pub async fn receive_email_event<
+    B: BeginUnitOfWork,
+    S: SubscriptionsRepository<UnitOfWork = B::UnitOfWork>,
+>(
+    request: HttpRequest,
+    webhook: web::ThinData<WebhookSettings>,
+    payload: web::Json<EmailEventPayload>,
+    begin_unit_of_work: Inject<B>,
+    subscriptions_repository: Inject<S>,
+) -> Result<HttpResponse, ReceiveEmailEventError> {
+    let credentials = basic_authentication(
+        request.headers(),
+    )
+    .map_err(ReceiveEmailEventError::Unauthorized)?;
+    if !credentials_match(&credentials, &webhook) {
+        return eyre::eyre!("Invalid webhook credentials.")
+            .pipe(ReceiveEmailEventError::Unauthorized)
+            .pipe(Err);
+    }
+
+    let Some(email_event) =
+        payload.into_inner().into_email_event()
+    else {
+        return HttpResponse::Ok().finish().pipe(Ok);
+    };
+
+    let mut unit_of_work = begin_unit_of_work
+        .begin()
+        .await
+        .map_err(eyre::Report::new)?;
+
+    subscriptions_repository
+        .record_email_event(&mut unit_of_work, &email_event)
+        .await?;
+
+    if let Some(reason) = suppression_reason(&email_event) {
+        subscriptions_repository
+            .suppress_email(
+                &mut unit_of_work,
+                &email_event.email,
+                reason,
+            )
+            .await?;
+    }
+
+    unit_of_work
+        .commit()
+        .await
+        .map_err(eyre::Report::new)?;
+
+    HttpResponse::Ok().finish().pipe(Ok)
+}
+
+/// Compares through HMAC tags so the check takes constant time.
+fn credentials_match(
+    credentials: &BasicAuthCredentials<'_>,
+    webhook: &WebhookSettings,
+) -> bool {
+    let tag = |username: &str, password: &str| {
+        let mut mac = Hmac::<Sha256>::new_from_slice(
+            webhook.password.expose_secret().as_bytes(),
+        )
+        .expect("HMAC can take key of any size.");
+        mac.update(username.as_bytes());
+        mac.update(b":");
+        mac.update(password.as_bytes());
+        mac
+    };
+
+    let expected = tag(
+        &webhook.username,
+        webhook.password.expose_secret(),
+    )
+    .finalize()
+    .into_bytes();
+
+    tag(
+        &credentials.username,
+        credentials.raw_password.expose_secret(),
+    )
+    .verify_slice(&expected)
+    .is_ok()
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReceiveEmailEventError {
+    #[error("Authentication failed: {0}")]
+    Unauthorized(#[source] eyre::Report),
+    #[error("Unexpected: {0}")]
+    Unexpected(#[from] eyre::Report),
+}
+
+impl From<RecordEmailEventError>
+    for ReceiveEmailEventError
+{
+    fn from(value: RecordEmailEventError) -> Self {
+        match value {
+            RecordEmailEventError::Unexpected(e) => {
+                e.into()
+            }
+        }
+    }
+}
+
+impl From<SuppressEmailError> for ReceiveEmailEventError {
+    fn from(value: SuppressEmailError) -> Self {
+        match value {
+            SuppressEmailError::Unexpected(e) => e.into(),
+        }
+    }
+}
+
+impl actix_web::ResponseError for ReceiveEmailEventError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ReceiveEmailEventError::Unauthorized(_) => {
+                StatusCode::UNAUTHORIZED
+            }
+            ReceiveEmailEventError::Unexpected(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut response =
+            HttpResponse::new(self.status_code());
+        if let ReceiveEmailEventError::Unauthorized(_) =
+            self
+        {
+            response.headers_mut().insert(
+                header::WWW_AUTHENTICATE,
+                HeaderValue::from_static(
+                    r#"Basic realm="email-events""#,
+                ),
+            );
+        }
+        response
+    }
+}
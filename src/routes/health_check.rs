@@ -1,5 +1,21 @@
-use actix_web::{HttpResponse, Responder};
+use actix_web::{HttpResponse, Responder, web};
 
-pub async fn health_check() -> impl Responder {
-    HttpResponse::Ok()
+use crate::email_client::{CircuitBreaker, CircuitState};
+
+#[derive(serde::Serialize)]
+struct HealthCheckResponse {
+    email_circuit_state: CircuitState,
+}
+
+/// Stays successful while the email provider fails, as the application
+/// itself does not.
+pub async fn health_check(
+    circuit_breaker: Option<web::Data<CircuitBreaker>>,
+) -> impl Responder {
+    let email_circuit_state = circuit_breaker
+        .map_or(CircuitState::Closed, |i| i.state());
+
+    HttpResponse::Ok().json(HealthCheckResponse {
+        email_circuit_state,
+    })
 }
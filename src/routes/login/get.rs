@@ -1,6 +1,6 @@
 use actix_web::{HttpResponse, http::header::ContentType};
 
-use crate::utils::Pipe;
+use crate::utils::{Pipe, escape_html};
 use std::fmt::Write;
 
 pub async fn login_form(
@@ -11,7 +11,7 @@ pub async fn login_form(
     flash_messages.iter()
     .filter(|m|m.level() > actix_web_flash_messages::Level::Debug)
     .for_each(|m|
-        writeln!(error_html, "<p><i>{}</i></p>", m.content())
+        writeln!(error_html, "<p><i>{}</i></p>", escape_html(m.content()))
         .expect("Write to string should have been successful.")
     );
 
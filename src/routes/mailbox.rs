@@ -0,0 +1,131 @@
+use actix_web::{
+    HttpResponse, http::header::ContentType, web,
+};
+use uuid::Uuid;
+
+use crate::{
+    email_client::{CapturedEmail, Mailbox},
+    utils::{Pipe, escape_html},
+};
+
+fn captured_email_row(email: &CapturedEmail) -> String {
+    format!(
+        r#"<tr>
+<td>{captured_at}</td>
+<td>{to}</td>
+<td><a href="/dev/mailbox/{id}">{subject}</a></td>
+</tr>
+"#,
+        captured_at = email
+            .captured_at
+            .format("%Y-%m-%d %H:%M:%S UTC"),
+        to = escape_html(&email.to),
+        id = email.id,
+        subject = escape_html(&email.subject),
+    )
+}
+
+fn header_row((name, value): (&str, &str)) -> String {
+    format!(
+        "<tr><th>{}</th><td>{}</td></tr>\n",
+        escape_html(name),
+        escape_html(value)
+    )
+}
+
+/// Only registered in local environments, sending through the in-memory
+/// transport.
+pub async fn get_mailbox(
+    mailbox: web::Data<Mailbox>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let rows_html = mailbox
+        .emails()
+        .iter()
+        .map(captured_email_row)
+        .collect::<String>();
+
+    HttpResponse::Ok()
+    .content_type(ContentType::html())
+    .body(format!(
+r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta http-equiv="content-type" content="text/html; charset=utf-8">
+<title>Mailbox</title>
+</head>
+<body>
+<p>Emails sent by the application, newest first.</p>
+<table>
+<tr>
+<th>Sent at</th><th>To</th><th>Subject</th>
+</tr>
+{rows_html}
+</table>
+</body>
+</html>"#))
+    .pipe(Ok)
+}
+
+/// Links of the HTML body open in place of the mailbox, e.g. to confirm a
+/// subscription.
+pub async fn get_mailbox_email(
+    email_id: web::Path<Uuid>,
+    mailbox: web::Data<Mailbox>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let email = mailbox
+        .email(email_id.into_inner())
+        .ok_or_else(|| {
+            actix_web::error::ErrorNotFound(
+                "No such email in the mailbox.",
+            )
+        })?;
+
+    let headers_html = [
+        ("From", Some(email.from.as_str())),
+        ("To", Some(email.to.as_str())),
+        ("Reply-To", email.reply_to.as_deref()),
+        ("Subject", Some(email.subject.as_str())),
+        ("Tag", email.tag.as_deref()),
+        ("Message stream", email.message_stream.as_deref()),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| Some((name, value?)))
+    .chain(email.headers.iter().map(|(name, value)| {
+        (name.as_str(), value.as_str())
+    }))
+    .map(header_row)
+    .chain(email.metadata.iter().map(|(key, value)| {
+        header_row((&format!("Metadata {key}"), value))
+    }))
+    .collect::<String>();
+
+    let html_content = escape_html(&format!(
+        r#"<base target="_top">{}"#,
+        email.html_content
+    ))
+    .into_owned();
+    let text_content = escape_html(&email.text_content);
+    let subject = escape_html(&email.subject);
+
+    HttpResponse::Ok()
+    .content_type(ContentType::html())
+    .body(format!(
+r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta http-equiv="content-type" content="text/html; charset=utf-8">
+<title>{subject}</title>
+</head>
+<body>
+<table>
+{headers_html}
+</table>
+<h2>HTML</h2>
+<iframe sandbox="allow-top-navigation-by-user-activation" srcdoc="{html_content}" width="100%" height="400"></iframe>
+<h2>Text</h2>
+<pre>{text_content}</pre>
+<p><a href="/dev/mailbox">&lt;- Back</a></p>
+</body>
+</html>"#))
+    .pipe(Ok)
+}
@@ -1,12 +1,20 @@
 mod admin;
+mod archive;
+mod email_events;
 mod health_check;
 mod home;
 mod login;
+mod mailbox;
 mod subscriptions;
 mod subscriptions_confirm;
+mod subscriptions_unsubscribe;
 pub use admin::*;
+pub use archive::*;
+pub use email_events::*;
 pub use health_check::*;
 pub use home::*;
 pub use login::*;
+pub use mailbox::*;
 pub use subscriptions::*;
 pub use subscriptions_confirm::*;
+pub use subscriptions_unsubscribe::*;
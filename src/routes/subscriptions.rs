@@ -8,12 +8,14 @@ use crate::{
         NewSubscriber, NewSubscriberParseError,
         SubscriberEmail,
     },
-    email_client::EmailClient,
+    email_client::{
+        EmailClient, EmailSender, SendEmailError,
+    },
     hkt::{
-        RefHKT, SendHKT, SharedPointerHKT, SyncHKT,
+        SendHKT, SharedPointerHKT, SyncHKT,
         traversable::traverse_result_future_result,
     },
-    startup::{self, ApplicationBaseUrl},
+    startup,
     utils::Pipe,
 };
 use actix_web::{
@@ -35,7 +37,7 @@ const SUBSCRIBE_INSTRUMENT_NAME: &str =
 
 #[tracing::instrument(
     name = SUBSCRIBE_INSTRUMENT_NAME,
-    skip(form, email_client, base_url, begin_unit_of_work,
+    skip(form, begin_unit_of_work,
         subscriptions_repository),
     fields(
         subscriber_email = %form.email,
@@ -47,21 +49,15 @@ pub async fn subscribe<
     S: SubscriptionsRepository<UnitOfWork = B::UnitOfWork>,
 >(
     form: web::Form<SubscribeFormData>,
-    email_client: web::Data<
-        EmailClient<startup::GlobalSharedPointerType>,
-    >,
-    base_url: web::ThinData<
-        ApplicationBaseUrl<
-            startup::GlobalSharedPointerType,
-        >,
-    >,
     begin_unit_of_work: Inject<B>,
     subscriptions_repository: Inject<S>,
 ) -> impl Responder {
-    subscribe_with_shared_pointer(
+    subscribe_with_shared_pointer::<
+        startup::GlobalSharedPointerType,
+        _,
+        _,
+    >(
         form,
-        email_client,
-        &base_url,
         &*begin_unit_of_work,
         &*subscriptions_repository,
     )
@@ -69,9 +65,12 @@ pub async fn subscribe<
     .await
 }
 
+/// The confirmation email is written to the outbox along with the
+/// subscription, so signing up neither waits for nor fails with the email
+/// provider. Its link and token are made when it is sent.
 #[tracing::instrument(
     name = SUBSCRIBE_INSTRUMENT_NAME,
-    skip(form, email_client, base_url, begin_unit_of_work,
+    skip(form, begin_unit_of_work,
         subscriptions_repository),
     fields(
         subscriber_email = %form.email,
@@ -80,13 +79,10 @@ pub async fn subscribe<
 )]
 pub async fn subscribe_with_shared_pointer<
     P: SharedPointerHKT + SendHKT + SyncHKT,
-    DataP: RefHKT,
     B: BeginUnitOfWork,
     S: SubscriptionsRepository<UnitOfWork = B::UnitOfWork>,
 >(
     form: web::Form<SubscribeFormData>,
-    email_client: web::Data<EmailClient<P>>,
-    base_url: &ApplicationBaseUrl<DataP>,
     begin_unit_of_work: &B,
     subscriptions_repository: &S,
 ) -> Result<HttpResponse, SubscribeError> {
@@ -98,33 +94,27 @@ pub async fn subscribe_with_shared_pointer<
             NewSubscriber::try_from(form.0)
                 .map_err(SubscribeError::from)
                 .map(async |subscriber| {
-                    subscriptions_repository.insert_subscriber::<P>(
+                    subscriptions_repository.upsert_subscriber::<P>(
                         &mut unit_of_work,
                         &subscriber,
                     )
                     .await
-                    .context("Failed to insert new subscriber to database.")
+                    .context("Failed to upsert subscriber to database.")
                     .map_err(SubscribeError::from)
-                    .map(async |subscriber_id| {
-                        subscriptions_repository.store_token::<P>(
+                    .map(async |upserted| {
+                        // Confirmed subscribers get the same response, so
+                        // the endpoint does not reveal who is subscribed.
+                        let Some(subscriber_id) = upserted.awaiting_confirmation() else {
+                            return Ok(());
+                        };
+
+                        subscriptions_repository.enqueue_confirmation_email(
                             &mut unit_of_work,
-                            &subscriber_id,
+                            subscriber_id,
+                            subscriber.email.as_ref(),
                         )
                         .await
-                        .context("Failed to store confirmation token of new subscriber to database.")
-                        .map_err(SubscribeError::from)
-                    })
-                    .pipe(traverse_result_future_result)
-                    .await
-                    .map(async |token| {
-                        send_confirmation_email(
-                            &email_client,
-                            &subscriber,
-                            &base_url.0,
-                            token.to_string().as_str(),
-                        )
-                        .await
-                        .context("Failed to send confirmation email to new subscriber's email.")
+                        .context("Failed to write confirmation email of new subscriber to the outbox.")
                         .map_err(SubscribeError::from)
                     })
                     .pipe(traverse_result_future_result)
@@ -147,31 +137,32 @@ pub async fn subscribe_with_shared_pointer<
         .map(|()| HttpResponse::Ok().finish())
 }
 
+#[must_use]
+pub fn confirmation_link(
+    base_url: &str,
+    subscription_token: &str,
+) -> String {
+    format!(
+        "{}/subscriptions/confirm?subscription_token={}",
+        base_url, subscription_token
+    )
+}
+
 #[tracing::instrument(
     name = "Send confirmation email to new subscriber",
-    skip(
-        email_client,
-        new_subscriber,
-        confirmation_link,
-        subscription_token
-    )
+    skip(email_client, recipient, confirmation_link)
 )]
 pub async fn send_confirmation_email<
-    P: SharedPointerHKT,
+    P: SharedPointerHKT + SendHKT + SyncHKT,
+    E: EmailSender,
 >(
-    email_client: &EmailClient<P>,
-    new_subscriber: &NewSubscriber<P>,
+    email_client: &EmailClient<P, E>,
+    recipient: SubscriberEmail<P>,
     confirmation_link: &str,
-    subscription_token: &str,
-) -> Result<(), reqwest::Error> {
-    let confirmation_link = format!(
-        "{}/subscriptions/confirm?subscription_token={}",
-        confirmation_link, subscription_token
-    );
-
+) -> Result<(), SendEmailError> {
     email_client
         .send_email(
-            new_subscriber.email.pipe_ref(SubscriberEmail::clone),
+            recipient,
             "Welcome!"
                 .pipe(P::from_static_str),
             format!(
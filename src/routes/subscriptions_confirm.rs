@@ -1,16 +1,22 @@
 use std::{borrow::Cow, str::FromStr};
 
-use actix_web::{HttpResponse, http::StatusCode, web};
-use uuid::Uuid;
+use actix_web::{
+    HttpResponse, http::StatusCode,
+    http::header::ContentType, web,
+};
 
 use crate::{
-    database::transactional::subscriptions_confirm::{
-        GetSubscriberIdOfConfirmationTokenError,
-        SubscriptionsConfirmRepository,
-        UpdateConfirmationStatusOfSubscriberIdError,
+    database::transactional::{
+        subscriptions_confirm::{
+            ConsumeConfirmationTokenError,
+            SubscriptionsConfirmRepository,
+            UpdateConfirmationStatusOfSubscriberIdError,
+        },
+        unit_of_work::{BeginUnitOfWork, UnitOfWork as _},
     },
     dependency_injection::app_state::Inject,
-    hkt::traversable,
+    domain::SubscriptionToken,
+    startup::ConfirmationTokenTtl,
     utils::Pipe,
 };
 
@@ -19,66 +25,86 @@ pub struct Parameters<'a> {
     subscription_token: Cow<'a, str>,
 }
 
-// Note: Status is usually only updated once for each subscriber, transactionalization is probably not needed.
+/// Consuming the token & confirming the subscriber share one transaction,
+/// so a token is never spent without the status being updated.
 #[tracing::instrument(
     name = "Confirm pending subscriber",
-    skip(parameters, subscriptions_confirm_repository)
+    skip(
+        parameters,
+        time_to_live,
+        begin_unit_of_work,
+        subscriptions_confirm_repository
+    )
 )]
 pub async fn confirm_subscription_token<
-    S: SubscriptionsConfirmRepository,
+    B: BeginUnitOfWork,
+    S: SubscriptionsConfirmRepository<
+        UnitOfWork = B::UnitOfWork,
+    >,
 >(
     parameters: web::Query<Parameters<'_>>,
+    time_to_live: web::ThinData<ConfirmationTokenTtl>,
+    begin_unit_of_work: Inject<B>,
     subscriptions_confirm_repository: Inject<S>,
 ) -> Result<HttpResponse, ConfirmSubscriptionTokenError> {
-    parameters
-        .subscription_token
-        .ref_cast()
-        .as_ref()
-        .pipe(Uuid::from_str)
-        .map_err(|_| {
-            ConfirmSubscriptionTokenError::Unauthorized
-        })
-        .map(async |subscription_token| {
-            subscriptions_confirm_repository.get_subscriber_id_of_confirmation_token(
-                subscription_token,
-            )
-            .await
-            .map_err(ConfirmSubscriptionTokenError::from)
-        })
-        .pipe(traversable::traverse_result_future_result)
+    let subscription_token = SubscriptionToken::from_str(
+        &parameters.subscription_token,
+    )
+    .map_err(|_| {
+        ConfirmSubscriptionTokenError::Unauthorized
+    })?;
+
+    let mut unit_of_work = begin_unit_of_work
+        .begin()
         .await
-        .map(async |subscriber_id| {
-            subscriptions_confirm_repository.update_status_of_subscriber_id_to_confirmed(
-                subscriber_id,
-            )
-            .await
-            .map_err(ConfirmSubscriptionTokenError::from)
-        })
-        .pipe(traversable::traverse_result_future_result)
+        .map_err(eyre::Report::new)?;
+
+    let subscriber_id = subscriptions_confirm_repository
+        .consume_confirmation_token(
+            &mut unit_of_work,
+            &subscription_token,
+            time_to_live.0.0,
+        )
+        .await?;
+
+    subscriptions_confirm_repository
+        .update_status_of_subscriber_id_to_confirmed(
+            &mut unit_of_work,
+            subscriber_id,
+        )
+        .await?;
+
+    unit_of_work
+        .commit()
         .await
-        .map(|()| HttpResponse::Ok().finish())
+        .map_err(eyre::Report::new)?;
+
+    HttpResponse::Ok().finish().pipe(Ok)
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum ConfirmSubscriptionTokenError {
     #[error("Invalid subscription token.")]
     Unauthorized,
+    #[error("Subscription token expired.")]
+    Expired,
     #[error("Unexpected: {0}")]
     Unexpected(#[from] eyre::Report),
 }
 
-impl From<GetSubscriberIdOfConfirmationTokenError>
+impl From<ConsumeConfirmationTokenError>
     for ConfirmSubscriptionTokenError
 {
-    fn from(
-        value: GetSubscriberIdOfConfirmationTokenError,
-    ) -> Self {
+    fn from(value: ConsumeConfirmationTokenError) -> Self {
         match value {
-            GetSubscriberIdOfConfirmationTokenError::TokenNotFound {
-                subscription_token: _
-            } => ConfirmSubscriptionTokenError::Unauthorized,
-            GetSubscriberIdOfConfirmationTokenError::Unexpected(e) => e
-            .pipe(ConfirmSubscriptionTokenError::from)
+            ConsumeConfirmationTokenError::TokenNotFound => {
+                ConfirmSubscriptionTokenError::Unauthorized
+            }
+            ConsumeConfirmationTokenError::TokenExpired => {
+                ConfirmSubscriptionTokenError::Expired
+            }
+            ConsumeConfirmationTokenError::Unexpected(e) => e
+                .pipe(ConfirmSubscriptionTokenError::from),
         }
     }
 }
@@ -103,9 +129,44 @@ impl actix_web::ResponseError
             ConfirmSubscriptionTokenError::Unauthorized => {
                 StatusCode::UNAUTHORIZED
             }
+            ConfirmSubscriptionTokenError::Expired => {
+                StatusCode::GONE
+            }
             ConfirmSubscriptionTokenError::Unexpected(
                 _,
             ) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            ConfirmSubscriptionTokenError::Expired => {
+                HttpResponse::build(self.status_code())
+                .content_type(ContentType::html())
+                .body(
+r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta http-equiv="content-type" content="text/html; charset=utf-8">
+<title>Link expired</title>
+</head>
+<body>
+<p>This confirmation link has expired. Request a new link below.</p>
+<form action="/subscriptions" method="post">
+<label>Name
+<input type="text" placeholder="Enter your name" name="name">
+</label>
+<label>Email
+<input type="email" placeholder="Enter your email" name="email">
+</label>
+<button type="submit">Request a new link</button>
+</form>
+</body>
+</html>"#)
+            }
+            _ => HttpResponse::build(self.status_code())
+                .content_type(ContentType::plaintext())
+                .body(self.to_string()),
+        }
+    }
 }
@@ -0,0 +1,188 @@
+use std::{borrow::Cow, str::FromStr};
+
+use actix_web::{
+    HttpResponse, http::StatusCode,
+    http::header::ContentType, web,
+};
+use uuid::Uuid;
+
+use crate::{
+    configuration::UnsubscribeSecret,
+    database::transactional::{
+        subscriptions::{
+            SubscriptionsRepository, UnsubscribeError,
+        },
+        unit_of_work::{BeginUnitOfWork, UnitOfWork as _},
+    },
+    dependency_injection::app_state::Inject,
+    domain::{UnsubscribeToken, UnsubscribeTokenError},
+    startup,
+    utils::Pipe,
+};
+
+#[derive(serde::Deserialize)]
+pub struct UnsubscribeParameters<'a> {
+    subscriber_id: Cow<'a, str>,
+    token: Cow<'a, str>,
+}
+
+impl UnsubscribeParameters<'_> {
+    fn verify(
+        &self,
+        unsubscribe_secret: &UnsubscribeSecret<
+            startup::GlobalSharedPointerType,
+        >,
+    ) -> Result<Uuid, UnsubscribeSubscriberError> {
+        let subscriber_id = Uuid::from_str(
+            &self.subscriber_id,
+        )
+        .map_err(|_| {
+            UnsubscribeSubscriberError::Unauthorized
+        })?;
+
+        UnsubscribeToken::verify(
+            &self.token,
+            unsubscribe_secret,
+            subscriber_id,
+        )?;
+
+        Ok(subscriber_id)
+    }
+}
+
+/// Landing page of the link in `List-Unsubscribe`.
+/// Nothing is changed here since link scanners prefetch GET requests.
+#[tracing::instrument(
+    name = "Show unsubscribe confirmation page",
+    skip(parameters, unsubscribe_secret)
+)]
+pub async fn get_unsubscribe_form(
+    parameters: web::Query<UnsubscribeParameters<'_>>,
+    unsubscribe_secret: web::Data<
+        UnsubscribeSecret<startup::GlobalSharedPointerType>,
+    >,
+) -> Result<HttpResponse, UnsubscribeSubscriberError> {
+    parameters.verify(&unsubscribe_secret)?;
+
+    let subscriber_id = &parameters.subscriber_id;
+    let token = &parameters.token;
+
+    HttpResponse::Ok()
+    .content_type(ContentType::html())
+    .body(format!(
+r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta http-equiv="content-type" content="text/html; charset=utf-8">
+<title>Unsubscribe</title>
+</head>
+<body>
+<p>Do you want to stop receiving our newsletter?</p>
+<form action="/subscriptions/unsubscribe?subscriber_id={subscriber_id}&token={token}" method="post">
+<button type="submit">Unsubscribe</button>
+</form>
+</body>
+</html>"#))
+    .pipe(Ok)
+}
+
+/// Handles both the confirmation form & RFC 8058 one-click requests.
+#[tracing::instrument(
+    name = "Unsubscribe subscriber",
+    skip(
+        parameters,
+        unsubscribe_secret,
+        begin_unit_of_work,
+        subscriptions_repository
+    )
+)]
+pub async fn unsubscribe<
+    B: BeginUnitOfWork,
+    S: SubscriptionsRepository<UnitOfWork = B::UnitOfWork>,
+>(
+    parameters: web::Query<UnsubscribeParameters<'_>>,
+    unsubscribe_secret: web::Data<
+        UnsubscribeSecret<startup::GlobalSharedPointerType>,
+    >,
+    begin_unit_of_work: Inject<B>,
+    subscriptions_repository: Inject<S>,
+) -> Result<HttpResponse, UnsubscribeSubscriberError> {
+    let subscriber_id =
+        parameters.verify(&unsubscribe_secret)?;
+
+    let mut unit_of_work = begin_unit_of_work
+        .begin()
+        .await
+        .map_err(eyre::Report::new)?;
+
+    subscriptions_repository
+        .unsubscribe(&mut unit_of_work, subscriber_id)
+        .await?;
+
+    unit_of_work
+        .commit()
+        .await
+        .map_err(eyre::Report::new)?;
+
+    HttpResponse::Ok()
+    .content_type(ContentType::html())
+    .body(
+r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta http-equiv="content-type" content="text/html; charset=utf-8">
+<title>Unsubscribed</title>
+</head>
+<body>
+<p>You have been unsubscribed and will no longer receive our newsletter.</p>
+</body>
+</html>"#)
+    .pipe(Ok)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UnsubscribeSubscriberError {
+    #[error("Invalid unsubscribe link.")]
+    Unauthorized,
+    #[error("Subscriber not found.")]
+    NotFound,
+    #[error("Unexpected: {0}")]
+    Unexpected(#[from] eyre::Report),
+}
+
+impl From<UnsubscribeTokenError>
+    for UnsubscribeSubscriberError
+{
+    fn from(_: UnsubscribeTokenError) -> Self {
+        UnsubscribeSubscriberError::Unauthorized
+    }
+}
+
+impl From<UnsubscribeError> for UnsubscribeSubscriberError {
+    fn from(value: UnsubscribeError) -> Self {
+        match value {
+            UnsubscribeError::NotFound(_) => {
+                UnsubscribeSubscriberError::NotFound
+            }
+            UnsubscribeError::Unexpected(e) => e.into(),
+        }
+    }
+}
+
+impl actix_web::ResponseError
+    for UnsubscribeSubscriberError
+{
+    fn status_code(&self) -> StatusCode {
+        match self {
+            UnsubscribeSubscriberError::Unauthorized => {
+                StatusCode::UNAUTHORIZED
+            }
+            UnsubscribeSubscriberError::NotFound => {
+                StatusCode::NOT_FOUND
+            }
+            UnsubscribeSubscriberError::Unexpected(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
@@ -13,19 +13,35 @@ use tracing_actix_web::TracingLogger;
 
 use crate::{
     authentication::reject_anonymous_users,
-    configuration::{HmacSecret, Settings},
+    configuration::{Environment, HmacSecret, Settings},
     dependency_injection::app_state::{
         AppState, AppStateFactory, AppStateTypes, Inject,
     },
+    email_client::{EmailClient, EmailSender as _},
     hkt::{
         ArcHKT, HKT1Unsized, K1, RefHKT, SendHKT,
         SharedPointerHKT, SyncHKT,
     },
     routes::{
-        admin_dashboard, confirm_subscription_token,
-        get_newsletter_form, get_reset_password_form,
+        admin_dashboard, cancel_scheduled_newsletter,
+        change_issue_delivery, confirm_subscription_token,
+        create_newsletter_draft, delete_newsletter_draft,
+        get_archive, get_archived_issue, get_atom_feed,
+        get_change_email_form, get_dead_letters,
+        get_delivery_progress, get_email_deliveries,
+        get_mailbox, get_mailbox_email,
+        get_newsletter_draft, get_newsletter_drafts,
+        get_newsletter_form, get_published_newsletters,
+        get_reset_password_form, get_rss_feed,
+        get_scheduled_newsletters, get_unsubscribe_form,
         health_check, home, login, login_form, logout,
-        post_reset_password, publish_newsletter, subscribe,
+        post_change_email, post_reset_password,
+        preview_newsletter_draft, publish_newsletter,
+        publish_newsletter_draft, receive_email_event,
+        requeue_dead_letter, send_test_newsletter,
+        send_test_newsletter_draft,
+        set_newsletter_visibility, subscribe, unsubscribe,
+        update_newsletter_draft,
     },
     tuples::{LifterMut, ThinDataHKT, TupleMap9},
     utils::Pipe,
@@ -50,7 +66,11 @@ impl<P: SharedPointerHKT> Clone for ApplicationBaseUrl<P> {
     }
 }
 
-#[allow(clippy::unused_async)]
+/// How long a subscription confirmation link stays valid.
+#[derive(Clone, Copy)]
+pub struct ConfirmationTokenTtl(pub std::time::Duration);
+
+#[allow(clippy::unused_async, clippy::too_many_lines)]
 pub async fn run<
     P: RefHKT + SendHKT + SyncHKT,
     A: AppStateTypes,
@@ -108,10 +128,57 @@ pub async fn run<
                 "/subscriptions/confirm",
                 web::get().to(
                     confirm_subscription_token::<
+                        A::BeginUnitOfWork,
                         A::SubscriptionsConfirmRepository,
                     >,
                 ),
             )
+            .route(
+                "/subscriptions/unsubscribe",
+                web::get().to(get_unsubscribe_form),
+            )
+            .route(
+                "/subscriptions/unsubscribe",
+                web::post().to(unsubscribe::<
+                    A::BeginUnitOfWork,
+                    A::SubscriptionsRepository,
+                >),
+            )
+            .route(
+                "/webhooks/email-events",
+                web::post().to(receive_email_event::<
+                    A::BeginUnitOfWork,
+                    A::SubscriptionsRepository,
+                >),
+            )
+            .route(
+                "/archive",
+                web::get().to(get_archive::<
+                    A::BeginUnitOfWork,
+                    A::NewslettersRepository,
+                >),
+            )
+            .route(
+                "/archive/{newsletter_issue_id}",
+                web::get().to(get_archived_issue::<
+                    A::BeginUnitOfWork,
+                    A::NewslettersRepository,
+                >),
+            )
+            .route(
+                "/feed.atom",
+                web::get().to(get_atom_feed::<
+                    A::BeginUnitOfWork,
+                    A::NewslettersRepository,
+                >),
+            )
+            .route(
+                "/feed.rss",
+                web::get().to(get_rss_feed::<
+                    A::BeginUnitOfWork,
+                    A::NewslettersRepository,
+                >),
+            )
             .service(
                 web::scope("/admin")
                     .wrap(actix_web::middleware::from_fn(
@@ -136,6 +203,18 @@ pub async fn run<
                             >,
                         ),
                     )
+                    .route(
+                        "/email",
+                        web::get().to(get_change_email_form::<
+                            A::AuthenticationRepository,
+                        >),
+                    )
+                    .route(
+                        "/email",
+                        web::post().to(post_change_email::<
+                            A::AuthenticationRepository,
+                        >),
+                    )
                     .route(
                         "/logout",
                         web::post().to(logout),
@@ -154,6 +233,168 @@ pub async fn run<
                     .route(
                         "/newsletters",
                         web::get().to(get_newsletter_form),
+                    )
+                    .route(
+                        "/newsletters/test",
+                        web::post().to(send_test_newsletter::<
+                            A::AuthenticationRepository,
+                            A::EmailSender,
+                        >),
+                    )
+                    .route(
+                        "/newsletters/drafts",
+                        web::get().to(get_newsletter_drafts::<
+                            A::BeginUnitOfWork,
+                            A::NewslettersRepository,
+                        >),
+                    )
+                    .route(
+                        "/newsletters/drafts",
+                        web::post().to(
+                            create_newsletter_draft::<
+                                A::BeginUnitOfWork,
+                                A::NewslettersRepository,
+                            >,
+                        ),
+                    )
+                    .route(
+                        "/newsletters/drafts/{newsletter_issue_id}",
+                        web::get().to(get_newsletter_draft::<
+                            A::BeginUnitOfWork,
+                            A::NewslettersRepository,
+                        >),
+                    )
+                    .route(
+                        "/newsletters/drafts/{newsletter_issue_id}",
+                        web::post().to(
+                            update_newsletter_draft::<
+                                A::BeginUnitOfWork,
+                                A::NewslettersRepository,
+                            >,
+                        ),
+                    )
+                    .route(
+                        "/newsletters/drafts/{newsletter_issue_id}/delete",
+                        web::post().to(
+                            delete_newsletter_draft::<
+                                A::BeginUnitOfWork,
+                                A::NewslettersRepository,
+                            >,
+                        ),
+                    )
+                    .route(
+                        "/newsletters/drafts/{newsletter_issue_id}/preview/{format}",
+                        web::get().to(
+                            preview_newsletter_draft::<
+                                A::BeginUnitOfWork,
+                                A::NewslettersRepository,
+                            >,
+                        ),
+                    )
+                    .route(
+                        "/newsletters/drafts/{newsletter_issue_id}/test",
+                        web::post().to(
+                            send_test_newsletter_draft::<
+                                A::AuthenticationRepository,
+                                A::BeginUnitOfWork,
+                                A::NewslettersRepository,
+                                A::EmailSender,
+                            >,
+                        ),
+                    )
+                    .route(
+                        "/newsletters/drafts/{newsletter_issue_id}/publish",
+                        web::post().to(
+                            publish_newsletter_draft::<
+                                A::AuthenticationRepository,
+                                A::BeginUnitOfWork,
+                                A::IssueDeliveryQueueRepository,
+                                A::NewslettersRepository,
+                                A::PersistenceRepository,
+                            >,
+                        ),
+                    )
+                    .route(
+                        "/newsletters/scheduled",
+                        web::get().to(
+                            get_scheduled_newsletters::<
+                                A::BeginUnitOfWork,
+                                A::NewslettersRepository,
+                            >,
+                        ),
+                    )
+                    .route(
+                        "/newsletters/scheduled/{newsletter_issue_id}/cancel",
+                        web::post().to(
+                            cancel_scheduled_newsletter::<
+                                A::BeginUnitOfWork,
+                                A::NewslettersRepository,
+                            >,
+                        ),
+                    )
+                    .route(
+                        "/issues",
+                        web::get().to(
+                            get_published_newsletters::<
+                                A::BeginUnitOfWork,
+                                A::IssueDeliveryQueueRepository,
+                                A::NewslettersRepository,
+                            >,
+                        ),
+                    )
+                    .route(
+                        "/issues/{newsletter_issue_id}/delivery/{action}",
+                        web::post().to(
+                            change_issue_delivery::<
+                                A::BeginUnitOfWork,
+                                A::IssueDeliveryQueueRepository,
+                            >,
+                        ),
+                    )
+                    .route(
+                        "/issues/progress",
+                        web::get().to(
+                            get_delivery_progress::<
+                                A::BeginUnitOfWork,
+                                A::IssueDeliveryQueueRepository,
+                            >,
+                        ),
+                    )
+                    .route(
+                        "/dead-letters",
+                        web::get().to(
+                            get_dead_letters::<
+                                A::BeginUnitOfWork,
+                                A::IssueDeliveryQueueRepository,
+                            >,
+                        ),
+                    )
+                    .route(
+                        "/dead-letters/requeue",
+                        web::post().to(
+                            requeue_dead_letter::<
+                                A::BeginUnitOfWork,
+                                A::IssueDeliveryQueueRepository,
+                            >,
+                        ),
+                    )
+                    .route(
+                        "/deliveries",
+                        web::get().to(
+                            get_email_deliveries::<
+                                A::BeginUnitOfWork,
+                                A::IssueDeliveryQueueRepository,
+                            >,
+                        ),
+                    )
+                    .route(
+                        "/issues/{newsletter_issue_id}/visibility",
+                        web::post().to(
+                            set_newsletter_visibility::<
+                                A::BeginUnitOfWork,
+                                A::NewslettersRepository,
+                            >,
+                        ),
                     ),
             )
             .configure(configurer.clone())
@@ -197,7 +438,7 @@ impl Application {
     ) -> Result<Application, eyre::Report> {
         Self::build_with::<P, A::AppStateTypes>(
             configuration,
-            A::build(configuration),
+            A::build(configuration)?,
         )
         .await
     }
@@ -213,10 +454,45 @@ impl Application {
             .email_client
             .as_ref()
             .clone()
-            .client();
+            .client(
+                app_state.email_sender.clone(),
+                app_state.fallback_email_sender.clone(),
+            );
+
+        Self::build_with_email_client(
+            configuration,
+            app_state,
+            email_client,
+        )
+        .await
+    }
 
+    /// The email client is shared with the issue delivery worker, so that
+    /// both are bound by the same send rate limits.
+    pub async fn build_with_email_client<
+        P: SharedPointerHKT + SendHKT + SyncHKT,
+        A: AppStateTypes,
+    >(
+        configuration: &Settings<P>,
+        app_state: AppState<A>,
+        email_client: EmailClient<P, A::EmailSender>,
+    ) -> Result<Application, eyre::Report> {
+        // Reported by health checks.
+        let circuit_breaker = web::Data::new(
+            email_client.circuit_breaker().clone(),
+        );
         let email_client = web::Data::new(email_client);
 
+        // Its routes are only registered along with it.
+        let mailbox = match Environment::current() {
+            Environment::Local => app_state
+                .email_sender
+                .mailbox()
+                .cloned()
+                .map(web::Data::new),
+            Environment::Production => None,
+        };
+
         let configuration = configuration.clone();
 
         let address = format!(
@@ -254,7 +530,20 @@ impl Application {
                 .clone(),
             move |cfg| {
                 app_state.clone().map_mut(&mut Cfg(cfg));
+                if let Some(mailbox) = &mailbox {
+                    cfg.app_data(mailbox.clone())
+                        .route(
+                            "/dev/mailbox",
+                            web::get().to(get_mailbox),
+                        )
+                        .route(
+                            "/dev/mailbox/{email_id}",
+                            web::get()
+                                .to(get_mailbox_email),
+                        );
+                }
                 cfg.app_data(email_client.clone())
+                    .app_data(circuit_breaker.clone())
                     .app_data(
                         ApplicationBaseUrl(
                             configuration
@@ -263,7 +552,28 @@ impl Application {
                                 .clone(),
                         )
                         .pipe(web::ThinData),
-                    );
+                    )
+                    .app_data(
+                        ConfirmationTokenTtl(
+                            configuration
+                                .application
+                                .confirmation_token_ttl(),
+                        )
+                        .pipe(web::ThinData),
+                    )
+                    .app_data(web::ThinData(
+                        configuration
+                            .email_client
+                            .webhook
+                            .clone(),
+                    ))
+                    .app_data(web::Data::new(
+                        configuration
+                            .application
+                            .unsubscribe_secret
+                            .as_ref()
+                            .clone(),
+                    ));
             },
         )
         .await
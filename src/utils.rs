@@ -61,6 +61,30 @@ pub fn see_other_response(
         .finish()
 }
 
+/// Escapes user-controlled strings interpolated into html pages.
+#[must_use]
+pub fn escape_html(
+    value: &str,
+) -> std::borrow::Cow<'_, str> {
+    if !value.contains(['&', '<', '>', '"', '\'']) {
+        return std::borrow::Cow::Borrowed(value);
+    }
+
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            c => escaped.push(c),
+        }
+    }
+
+    std::borrow::Cow::Owned(escaped)
+}
+
 pub async fn await_sequential<I>(
     iter: I,
 ) -> Vec<<I::Item as Future>::Output>
@@ -0,0 +1,146 @@
+use std::time::Duration;
+
+use crate::{
+    configuration::{
+        ConfirmationEmailSettings, IssueDeliverySettings,
+    },
+    database::transactional::notifications::{
+        Channel, NotificationListener,
+        NotificationSubscription,
+    },
+};
+
+/// Exponential backoff with jitter, per task, for a bounded number of
+/// attempts.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Delay before the next attempt of a task which failed after
+    /// `n_retries` retries, `None` once it ran out of attempts.
+    #[must_use]
+    pub fn retry_delay(
+        &self,
+        n_retries: i32,
+    ) -> Option<Duration> {
+        let n_attempts =
+            u32::try_from(n_retries).unwrap_or(0) + 1;
+
+        if n_attempts >= self.max_attempts {
+            return None;
+        }
+
+        let delay = self
+            .base_delay
+            .saturating_mul(
+                2_u32.saturating_pow(n_attempts - 1),
+            )
+            .min(self.max_delay);
+
+        // Half of the delay is random so failures of the same outage spread out.
+        let half = delay / 2;
+        Some(half + half.mul_f64(rand::random::<f64>()))
+    }
+}
+
+impl From<&IssueDeliverySettings> for RetryPolicy {
+    fn from(value: &IssueDeliverySettings) -> Self {
+        Self {
+            max_attempts: value.max_attempts,
+            base_delay: value.retry_base_delay(),
+            max_delay: value.retry_max_delay(),
+        }
+    }
+}
+
+impl From<&ConfirmationEmailSettings> for RetryPolicy {
+    fn from(value: &ConfirmationEmailSettings) -> Self {
+        Self {
+            max_attempts: value.max_attempts,
+            base_delay: value.retry_base_delay(),
+            max_delay: value.retry_max_delay(),
+        }
+    }
+}
+
+/// Listens lazily, so a worker keeps polling while the database refuses
+/// the connection & listens again once it accepts it.
+pub async fn wait_for_notification<
+    C: Channel,
+    L: NotificationListener<C>,
+>(
+    listener: &L,
+    subscription: &mut Option<L::Subscription>,
+    idle_delay: Duration,
+) {
+    if subscription.is_none() {
+        *subscription = listener
+            .listen()
+            .await
+            .inspect_err(|e| tracing::warn!(
+                error.cause_chain = ?e,
+                "Failed to listen to '{}', polling instead.",
+                C::NAME
+            ))
+            .ok();
+    }
+
+    let Some(listening) = subscription else {
+        tokio::time::sleep(idle_delay).await;
+        return;
+    };
+
+    if let Err(e) =
+        listening.wait_for_notification(idle_delay).await
+    {
+        tracing::warn!(
+            error.cause_chain = ?e,
+            "Stopped listening to '{}'.",
+            C::NAME
+        );
+        *subscription = None;
+        tokio::time::sleep(idle_delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::RetryPolicy;
+
+    const POLICY: RetryPolicy = RetryPolicy {
+        max_attempts: 4,
+        base_delay: Duration::from_secs(10),
+        max_delay: Duration::from_secs(25),
+    };
+
+    #[test]
+    fn retry_delay_doubles_with_jitter_up_to_the_maximum() {
+        for (n_retries, delay) in
+            [(0, 10), (1, 20), (2, 25)]
+        {
+            let delay = Duration::from_secs(delay);
+            let retry_delay =
+                POLICY.retry_delay(n_retries).unwrap();
+
+            assert!(
+                retry_delay >= delay / 2,
+                "{retry_delay:?}"
+            );
+            assert!(
+                retry_delay <= delay,
+                "{retry_delay:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn no_retry_after_the_last_attempt() {
+        assert_eq!(POLICY.retry_delay(3), None);
+    }
+}
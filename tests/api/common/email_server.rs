@@ -1,6 +1,130 @@
+use std::time::Duration;
+
+/// Postmark error code of a recipient which bounced or complained before.
+pub const INACTIVE_RECIPIENT: i64 = 406;
+/// Postmark error code of the API being offline for maintenance.
+pub const MAINTENANCE: i64 = 100;
+
 pub fn get_mock_builder() -> wiremock::MockBuilder {
     wiremock::Mock::given(wiremock::matchers::path(
         "/email",
     ))
     .and(wiremock::matchers::method("POST"))
 }
+
+/// Matches batches of emails, which newsletter issues are sent in.
+pub fn get_batch_mock_builder() -> wiremock::MockBuilder {
+    wiremock::Mock::given(wiremock::matchers::path(
+        "/email/batch",
+    ))
+    .and(wiremock::matchers::method("POST"))
+}
+
+/// Accepts every email, replying to batches with a result per email.
+#[derive(Default)]
+pub struct Accepted {
+    delay: Duration,
+    n_rejected: usize,
+    error_code: i64,
+}
+
+impl Accepted {
+    pub fn with_delay(self, delay: Duration) -> Self {
+        Self { delay, ..self }
+    }
+
+    /// Rejects the first emails of a batch, on their own, with the Postmark
+    /// error code.
+    pub fn rejecting_first(
+        self,
+        n_rejected: usize,
+        error_code: i64,
+    ) -> Self {
+        Self {
+            n_rejected,
+            error_code,
+            ..self
+        }
+    }
+}
+
+pub fn accepted() -> Accepted {
+    Accepted::default()
+}
+
+impl wiremock::Respond for Accepted {
+    fn respond(
+        &self,
+        request: &wiremock::Request,
+    ) -> wiremock::ResponseTemplate {
+        let response = wiremock::ResponseTemplate::new(200)
+            .set_delay(self.delay);
+
+        match serde_json::from_slice(&request.body) {
+            Ok(serde_json::Value::Array(emails)) => {
+                response.set_body_json(
+                    emails
+                        .iter()
+                        .enumerate()
+                        .map(|(n, i)| {
+                            if n < self.n_rejected {
+                                serde_json::json!({
+                                    "To": i["To"],
+                                    "ErrorCode": self.error_code,
+                                    "Message": "Rejected",
+                                })
+                            } else {
+                                serde_json::json!({
+                                    "To": i["To"],
+                                    "ErrorCode": 0,
+                                    "Message": "OK",
+                                    "MessageID": message_id(
+                                        i["To"]
+                                            .as_str()
+                                            .unwrap_or_default(),
+                                    ),
+                                })
+                            }
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            }
+            _ => response,
+        }
+    }
+}
+
+/// Error response of Postmark rejecting a whole request.
+pub fn rejected(
+    http_status: u16,
+    error_code: i64,
+) -> wiremock::ResponseTemplate {
+    wiremock::ResponseTemplate::new(http_status)
+        .set_body_json(serde_json::json!({
+            "ErrorCode": error_code,
+            "Message": "Rejected",
+        }))
+}
+
+/// Provider message id of an email accepted for the recipient.
+pub fn message_id(recipient: &str) -> String {
+    format!("message-to-{recipient}")
+}
+
+/// Every email received, in order, whether sent on its own or in a batch.
+pub async fn sent_emails(
+    email_server: &wiremock::MockServer,
+) -> Vec<serde_json::Value> {
+    email_server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .flat_map(|i| {
+            match serde_json::from_slice(&i.body).unwrap() {
+                serde_json::Value::Array(emails) => emails,
+                email => vec![email],
+            }
+        })
+        .collect()
+}
@@ -1,12 +1,16 @@
 use core::str;
 use eyre::Context;
+use fake::Fake as _;
+use nameof::name_of;
 use once_cell::sync::Lazy;
 use reqwest::Url;
 use secrecy::ExposeSecret;
 use secrecy::SecretString;
 use sqlx::{Connection, Executor, PgConnection, PgPool};
-use std::{borrow::Cow, ops::Deref};
+use std::{borrow::Cow, num::NonZeroU32, ops::Deref};
 use uuid::Uuid;
+use zero2prod::confirmation_email_worker::ConfirmationEmailTaskResult;
+use zero2prod::confirmation_email_worker::send_next_confirmation_email;
 use zero2prod::dependency_injection::app_state::AppState;
 use zero2prod::dependency_injection::app_state::AppStateFactory;
 use zero2prod::dependency_injection::app_state::AppStateTypes;
@@ -14,18 +18,22 @@ use zero2prod::dependency_injection::app_state::DefaultAppStateFactory;
 use zero2prod::dependency_injection::app_state::DefaultAppStateTypes;
 use zero2prod::dependency_injection::app_state::IssueDeliveryWorkerTypes;
 use zero2prod::email_client::EmailClient;
+use zero2prod::email_client::SendEmailError;
 use zero2prod::hkt::SendHKT;
 use zero2prod::hkt::SyncHKT;
 use zero2prod::issue_delivery_worker::IssueDeliveryWorkerDependencies;
 use zero2prod::issue_delivery_worker::SingleNewsletterPickingAndSendingTaskResult;
 use zero2prod::issue_delivery_worker::get_single_newsletter_picking_and_sending_iterator;
+use zero2prod::worker::RetryPolicy;
 use zero2prod::{
     authentication::BasicAuthCredentials,
     configuration::{
-        DatabaseSettings, Settings, get_configuration,
+        DatabaseSettings, EmailTransportSettings, Settings,
+        UnsubscribeSecret, WebhookSettings,
+        get_configuration,
     },
     hkt::{RefHKT, SharedPointerHKT},
-    startup::{self, Application},
+    startup::{self, Application, ApplicationBaseUrl},
     utils::Pipe,
 };
 
@@ -56,7 +64,13 @@ pub struct TestApp<
     pub email_server: wiremock::MockServer,
     pub port: u16,
     pub http_client: reqwest::Client,
-    pub email_client: EmailClient<P>,
+    pub email_client: EmailClient<P, A::EmailSender>,
+    pub application_base_url: ApplicationBaseUrl<P>,
+    pub unsubscribe_secret: UnsubscribeSecret<P>,
+    pub retry_policy: RetryPolicy,
+    pub confirmation_email_retry_policy: RetryPolicy,
+    pub batch_size: usize,
+    pub webhook: WebhookSettings,
     pub app_state: AppState<A>,
     pub test_app_state: TestAppState<TA>,
 }
@@ -94,6 +108,20 @@ impl<P: RefHKT> TestApp<'_, P> {
             .await
     }
 
+    pub async fn post_newsletter_json(
+        &self,
+        body: &impl serde::Serialize,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        self.http_client
+            .post(format!(
+                "{}/admin/newsletters",
+                self.address.as_ref()
+            ))
+            .json(body)
+            .send()
+            .await
+    }
+
     pub async fn get_newsletter_form(
         &self,
     ) -> Result<reqwest::Response, reqwest::Error> {
@@ -106,6 +134,267 @@ impl<P: RefHKT> TestApp<'_, P> {
             .await
     }
 
+    pub async fn get_scheduled_newsletters_html(
+        &self,
+    ) -> Result<String, reqwest::Error> {
+        self.http_client
+            .get(format!(
+                "{}/admin/newsletters/scheduled",
+                self.address.as_ref()
+            ))
+            .send()
+            .await?
+            .text()
+            .await
+    }
+
+    pub async fn post_cancel_scheduled_newsletter(
+        &self,
+        newsletter_issue_id: &str,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        self.http_client
+            .post(format!(
+                "{}/admin/newsletters/scheduled/{}/cancel",
+                self.address.as_ref(),
+                newsletter_issue_id
+            ))
+            .send()
+            .await
+    }
+
+    pub async fn post_newsletter_draft(
+        &self,
+        body: &impl serde::Serialize,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        self.http_client
+            .post(format!(
+                "{}/admin/newsletters/drafts",
+                self.address.as_ref()
+            ))
+            .form(body)
+            .send()
+            .await
+    }
+
+    pub async fn get_newsletter_drafts_html(
+        &self,
+    ) -> Result<String, reqwest::Error> {
+        self.http_client
+            .get(format!(
+                "{}/admin/newsletters/drafts",
+                self.address.as_ref()
+            ))
+            .send()
+            .await?
+            .text()
+            .await
+    }
+
+    /// `path` is relative to the draft, e.g. `/publish`.
+    pub async fn post_newsletter_draft_action(
+        &self,
+        draft_location: &str,
+        path: &str,
+        body: &impl serde::Serialize,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        self.http_client
+            .post(format!(
+                "{}{}{}",
+                self.address.as_ref(),
+                draft_location,
+                path
+            ))
+            .form(body)
+            .send()
+            .await
+    }
+
+    pub async fn get_newsletter_draft_page(
+        &self,
+        draft_location: &str,
+        path: &str,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        self.http_client
+            .get(format!(
+                "{}{}{}",
+                self.address.as_ref(),
+                draft_location,
+                path
+            ))
+            .send()
+            .await
+    }
+
+    pub async fn post_test_newsletter(
+        &self,
+        body: &impl serde::Serialize,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        self.http_client
+            .post(format!(
+                "{}/admin/newsletters/test",
+                self.address.as_ref()
+            ))
+            .form(body)
+            .send()
+            .await
+    }
+
+    pub async fn get_mailbox_html(
+        &self,
+    ) -> Result<String, reqwest::Error> {
+        self.http_client
+            .get(format!(
+                "{}/dev/mailbox",
+                self.address.as_ref()
+            ))
+            .send()
+            .await?
+            .text()
+            .await
+    }
+
+    /// Public pages such as `/archive` or `/feed.atom`.
+    pub async fn get_public_page(
+        &self,
+        path: &str,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        self.http_client
+            .get(format!(
+                "{}{}",
+                self.address.as_ref(),
+                path
+            ))
+            .send()
+            .await
+    }
+
+    pub async fn get_published_newsletters_html(
+        &self,
+    ) -> Result<String, reqwest::Error> {
+        self.http_client
+            .get(format!(
+                "{}/admin/issues",
+                self.address.as_ref()
+            ))
+            .send()
+            .await?
+            .text()
+            .await
+    }
+
+    pub async fn get_delivery_progress(
+        &self,
+    ) -> Result<serde_json::Value, reqwest::Error> {
+        self.http_client
+            .get(format!(
+                "{}/admin/issues/progress",
+                self.address.as_ref()
+            ))
+            .send()
+            .await?
+            .json()
+            .await
+    }
+
+    /// `action` is one of `pause`, `resume` or `cancel`.
+    pub async fn post_issue_delivery_action(
+        &self,
+        newsletter_issue_id: &str,
+        action: &str,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        self.http_client
+            .post(format!(
+                "{}/admin/issues/{}/delivery/{}",
+                self.address.as_ref(),
+                newsletter_issue_id,
+                action
+            ))
+            .send()
+            .await
+    }
+
+    pub async fn get_dead_letters_html(
+        &self,
+    ) -> Result<String, reqwest::Error> {
+        self.http_client
+            .get(format!(
+                "{}/admin/dead-letters",
+                self.address.as_ref()
+            ))
+            .send()
+            .await?
+            .text()
+            .await
+    }
+
+    pub async fn get_email_deliveries(
+        &self,
+        query: &[(&str, &str)],
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        self.http_client
+            .get(format!(
+                "{}/admin/deliveries",
+                self.address.as_ref()
+            ))
+            .query(query)
+            .send()
+            .await
+    }
+
+    pub async fn post_email_event(
+        &self,
+        body: &serde_json::Value,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        self.http_client
+            .post(format!(
+                "{}/webhooks/email-events",
+                self.address.as_ref()
+            ))
+            .basic_auth(
+                &self.webhook.username,
+                Some(self.webhook.password.expose_secret()),
+            )
+            .json(body)
+            .send()
+            .await
+    }
+
+    pub async fn post_requeue_dead_letter(
+        &self,
+        newsletter_issue_id: &str,
+        subscriber_email: &str,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        self.http_client
+            .post(format!(
+                "{}/admin/dead-letters/requeue",
+                self.address.as_ref()
+            ))
+            .form(&serde_json::json!({
+                "newsletter_issue_id": newsletter_issue_id,
+                "subscriber_email": subscriber_email,
+            }))
+            .send()
+            .await
+    }
+
+    pub async fn post_newsletter_visibility(
+        &self,
+        newsletter_issue_id: &str,
+        is_private: bool,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        self.http_client
+            .post(format!(
+                "{}/admin/issues/{}/visibility",
+                self.address.as_ref(),
+                newsletter_issue_id
+            ))
+            .form(&serde_json::json!({
+                "is_private": is_private,
+            }))
+            .send()
+            .await
+    }
+
     pub fn get_confirmation_links<'a>(
         &self,
         email_request: &wiremock::Request,
@@ -221,6 +510,28 @@ impl<P: RefHKT> TestApp<'_, P> {
         .context("Request password reset should always return response.")
     }
 
+    pub async fn get_change_email_html(
+        &self,
+    ) -> Result<String, reqwest::Error> {
+        self.http_client
+            .get(format!("{}/admin/email", &self.address))
+            .send()
+            .await?
+            .text()
+            .await
+    }
+
+    pub async fn post_change_email(
+        &self,
+        email: &str,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        self.http_client
+            .post(format!("{}/admin/email", &self.address))
+            .form(&serde_json::json!({ "email": email }))
+            .send()
+            .await
+    }
+
     pub async fn post_logout(
         &self,
     ) -> Result<reqwest::Response, eyre::Report> {
@@ -239,33 +550,100 @@ impl<
     A: AppStateTypes,
 > TestApp<'_, P, A>
 {
-    pub async fn dispatch_all_pending_emails(&self) {
-        let dependencies =
-            IssueDeliveryWorkerDependencies::<
-                IssueDeliveryWorkerTypes<P, A>,
-            > {
-                issue_delivery_queue_repository: &self
-                    .app_state
-                    .issue_delivery_queue_repository,
-                begin_unit_of_work: &self
-                    .app_state
-                    .begin_unit_of_work,
-                newsletters_repository: &self
-                    .app_state
-                    .newsletters_repository,
-                email_client: &self.email_client,
-            };
-
-        let iterator = get_single_newsletter_picking_and_sending_iterator(
-            &dependencies,
-        );
-
-        for task in iterator {
-            if let SingleNewsletterPickingAndSendingTaskResult::NothingFound = task.await {
-                break;
+    /// Sends the confirmation emails of the outbox until it is drained.
+    /// Failed sends are retried right away until out of attempts, any other
+    /// error fails the test.
+    pub async fn dispatch_all_pending_confirmation_emails(
+        &self,
+    ) {
+        // Emails are left in the outbox while the circuit breaker is open.
+        while self.email_client.unavailable_for().is_none()
+        {
+            match send_next_confirmation_email(
+                &*self.app_state.begin_unit_of_work,
+                &*self.app_state.subscriptions_repository,
+                &self.email_client,
+                &self.application_base_url.0,
+                &self.confirmation_email_retry_policy,
+            )
+            .await
+            {
+                Ok(ConfirmationEmailTaskResult::Completed) => {
+                }
+                Ok(
+                    ConfirmationEmailTaskResult::NothingFound,
+                ) => break,
+                Err(e)
+                    if e.downcast_ref::<SendEmailError>()
+                        .is_some() => {}
+                Err(e) => panic!(
+                    "Failed to dispatch confirmation email: {e:?}"
+                ),
             }
         }
     }
+
+    pub async fn dispatch_all_pending_emails(&self) {
+        self.dispatch_all_pending_emails_concurrently(1)
+            .await;
+    }
+
+    pub fn issue_delivery_worker_dependencies(
+        &self,
+    ) -> IssueDeliveryWorkerDependencies<
+        '_,
+        IssueDeliveryWorkerTypes<P, A>,
+    > {
+        IssueDeliveryWorkerDependencies {
+            issue_delivery_queue_repository: &self
+                .app_state
+                .issue_delivery_queue_repository,
+            begin_unit_of_work: &self
+                .app_state
+                .begin_unit_of_work,
+            newsletters_repository: &self
+                .app_state
+                .newsletters_repository,
+            subscriptions_repository: &self
+                .app_state
+                .subscriptions_repository,
+            email_client: &self.email_client,
+            application_base_url: &self
+                .application_base_url,
+            unsubscribe_secret: &self.unsubscribe_secret,
+            retry_policy: &self.retry_policy,
+            batch_size: self.batch_size,
+        }
+    }
+
+    /// Runs `concurrency` workers until each finds the queue drained.
+    pub async fn dispatch_all_pending_emails_concurrently(
+        &self,
+        concurrency: usize,
+    ) {
+        let dependencies =
+            self.issue_delivery_worker_dependencies();
+
+        (0..concurrency)
+            .map(|_| async {
+                let iterator = get_single_newsletter_picking_and_sending_iterator(
+                    &dependencies,
+                );
+
+                for task in iterator {
+                    // Tasks are left queued while the circuit breaker is
+                    // open.
+                    if self.email_client.unavailable_for().is_some() {
+                        break;
+                    }
+                    if let SingleNewsletterPickingAndSendingTaskResult::NothingFound = task.await {
+                        break;
+                    }
+                }
+            })
+            .pipe(futures::future::join_all)
+            .await;
+    }
 }
 
 pub const TEST: &str = "test";
@@ -295,13 +673,25 @@ static TRACING: Lazy<()> = Lazy::new(|| {
 
 /// Spinup an instance of our application
 /// and returns its address(i.e.http://localhost:XXXX)
+/// Emails are sent to the mock server standing in for Postmark.
 pub async fn spawn_app<'a>()
 -> TestApp<'a, startup::GlobalSharedPointerType> {
     spawn_app_generic::<
         startup::GlobalSharedPointerType,
         DefaultAppStateFactory,
         TestAppStateFactoryImpl,
-    >()
+    >(EmailTransportSettings::Postmark)
+    .await
+}
+
+/// Emails are kept in the mailbox of `/dev/mailbox` instead.
+pub async fn spawn_app_with_mailbox<'a>()
+-> TestApp<'a, startup::GlobalSharedPointerType> {
+    spawn_app_generic::<
+        startup::GlobalSharedPointerType,
+        DefaultAppStateFactory,
+        TestAppStateFactoryImpl,
+    >(EmailTransportSettings::Memory)
     .await
 }
 
@@ -310,8 +700,10 @@ pub async fn spawn_app_generic<
     P: SharedPointerHKT + SendHKT + SyncHKT,
     A: AppStateFactory,
     TA: TestAppStateFactory<AppStateTypes = A::AppStateTypes>,
->()
--> TestApp<'a, P, A::AppStateTypes, TA::TestAppStateTypes> {
+>(
+    transport: EmailTransportSettings,
+) -> TestApp<'a, P, A::AppStateTypes, TA::TestAppStateTypes>
+{
     Lazy::force(&TRACING);
 
     let configuration = get_configuration::<P>()
@@ -345,6 +737,11 @@ pub async fn spawn_app_generic<
             configuration.email_client.deref().clone();
         email_client.base_url =
             email_server.uri().pipe(P::from_string);
+        email_client.transport = transport;
+        // Tests of retries keep failing past the default threshold, without
+        // waiting for the circuit to close.
+        email_client.circuit_breaker.failure_threshold =
+            NonZeroU32::MAX;
         email_client.pipe(P::new)
     };
 
@@ -353,11 +750,15 @@ pub async fn spawn_app_generic<
         database,
         application,
         email_client,
+        issue_delivery: configuration.issue_delivery,
+        confirmation_email: configuration
+            .confirmation_email,
     };
 
     configure_database(&configuration.database).await;
 
-    let app_state = A::build(&configuration);
+    let app_state = A::build(&configuration)
+        .expect("Failed to build app state.");
 
     let application = Application::build_with(
         &configuration,
@@ -392,7 +793,34 @@ pub async fn spawn_app_generic<
             .email_client
             .as_ref()
             .clone()
-            .client(),
+            .client(
+                app_state.email_sender.clone(),
+                app_state.fallback_email_sender.clone(),
+            ),
+        application_base_url: ApplicationBaseUrl(
+            configuration.application.base_url.clone(),
+        ),
+        unsubscribe_secret: configuration
+            .application
+            .unsubscribe_secret
+            .as_ref()
+            .clone(),
+        // Retries are due right away, for dispatching until the queue is
+        // drained.
+        retry_policy: RetryPolicy {
+            base_delay: std::time::Duration::ZERO,
+            ..RetryPolicy::from(
+                configuration.issue_delivery.as_ref(),
+            )
+        },
+        confirmation_email_retry_policy: RetryPolicy {
+            base_delay: std::time::Duration::ZERO,
+            ..RetryPolicy::from(
+                configuration.confirmation_email.as_ref(),
+            )
+        },
+        batch_size: configuration.issue_delivery.batch_size,
+        webhook: configuration.email_client.webhook.clone(),
         app_state,
         test_app_state,
     }
@@ -453,6 +881,9 @@ pub fn get_test_newsletter_writer<'a>()
     }
 }
 
+pub const TEST_NEWSLETTER_WRITER_EMAIL: &str =
+    "test_user@example.com";
+
 pub async fn create_test_newsletter_writer(
     app: &TestApp<'_>,
 ) {
@@ -475,6 +906,7 @@ pub async fn create_test_newsletter_writer(
         .insert(
             user_id,
             test_newsletter_writer.username.as_ref(),
+            TEST_NEWSLETTER_WRITER_EMAIL,
             &hash,
         )
         .await
@@ -505,3 +937,98 @@ pub fn assert_is_redirect_to(
         uri_path
     );
 }
+
+pub async fn create_confirmed_subscribers(
+    app: &TestApp<'_>,
+) {
+    let confirmation_link =
+        create_unconfirmed_subscribers(app).await;
+
+    confirm_subscriber(confirmation_link).await;
+}
+
+pub async fn confirm_subscriber(
+    confirmation_link: reqwest::Url,
+) {
+    assert_eq!(
+        confirmation_link.host_str().unwrap(),
+        "127.0.0.1",
+        "The confirmation link must be correctly mocked."
+    );
+
+    let _confirmation_response =
+        reqwest::get(confirmation_link)
+            .await
+            .expect("Text link must be callable")
+            .error_for_status()
+            .unwrap();
+}
+
+pub async fn create_unconfirmed_subscribers(
+    app: &TestApp<'_>,
+) -> reqwest::Url {
+    let name =
+        fake::faker::name::en::Name().fake::<String>();
+    let email = fake::faker::internet::en::SafeEmail()
+        .fake::<String>();
+
+    create_unconfirmed_subscriber_with(app, &name, &email)
+        .await
+}
+
+pub async fn create_unconfirmed_subscriber_with(
+    app: &TestApp<'_>,
+    name: &str,
+    email: &str,
+) -> reqwest::Url {
+    let mock_guard = email_server::get_mock_builder()
+        .respond_with(wiremock::ResponseTemplate::new(200))
+        .expect(1)
+        .mount_as_scoped(&app.email_server)
+        .await;
+
+    let body = serde_json::json!({
+        name_of!(name): name,
+        name_of!(email): email,
+    })
+    .pipe(serde_urlencoded::to_string)
+    .unwrap();
+
+    app.post_subscriptions(body)
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .unwrap();
+    app.dispatch_all_pending_confirmation_emails().await;
+
+    let confirmation_links = app
+        .email_server
+        .received_requests()
+        .await
+        .unwrap()
+        .pop()
+        .unwrap()
+        .pipe_ref(|i| app.get_confirmation_links(i))
+        .unwrap();
+
+    let confirmation_link = confirmation_links
+        .plain_text
+        .into_owned()
+        .pipe(|mut i| {
+            i.set_port(app.port.pipe(Some)).unwrap();
+            i
+        });
+
+    drop(mock_guard);
+
+    confirmation_link
+}
+
+pub fn a_valid_newsletter_request_body() -> serde_json::Value
+{
+    serde_json::json!({
+        "title": "Newsletter title",
+        "content_text": "Newsletter body as plain text",
+        "content_html": "<p>Newsletter body as HTML</p>",
+        "idempotency_key": Uuid::new_v4().to_string(),
+    })
+}
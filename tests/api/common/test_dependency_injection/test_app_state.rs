@@ -8,12 +8,14 @@ use zero2prod::{
     startup::GlobalSharedPointer,
 };
 
-use crate::common::test_dependency_injection::test_database::{get_subscriptions_repository::GetSubscriptionsRepository, insert_newsletter_writer_repository::InsertNewsletterWriterRepository, repository_suspender::RepositorySuspender};
+use crate::common::test_dependency_injection::test_database::{confirmation_email_dead_letters_repository::ConfirmationEmailDeadLettersRepository, get_subscriptions_repository::GetSubscriptionsRepository, insert_newsletter_writer_repository::InsertNewsletterWriterRepository, repository_suspender::RepositorySuspender, subscription_tokens_repository::SubscriptionTokensRepository};
 
 pub trait TestAppStateTypes {
     type InsertNewsletterWriterRepository: InsertNewsletterWriterRepository;
     type GetSubscriptionsRepository: GetSubscriptionsRepository;
     type RepositorySuspender: RepositorySuspender;
+    type SubscriptionTokensRepository: SubscriptionTokensRepository;
+    type ConfirmationEmailDeadLettersRepository: ConfirmationEmailDeadLettersRepository;
 }
 
 pub struct TestAppState<A: TestAppStateTypes> {
@@ -25,6 +27,13 @@ pub struct TestAppState<A: TestAppStateTypes> {
         GlobalSharedPointer<A::GetSubscriptionsRepository>,
     pub repository_suspender:
         GlobalSharedPointer<A::RepositorySuspender>,
+    pub subscription_tokens_repository: GlobalSharedPointer<
+        A::SubscriptionTokensRepository,
+    >,
+    pub confirmation_email_dead_letters_repository:
+        GlobalSharedPointer<
+            A::ConfirmationEmailDeadLettersRepository,
+        >,
 }
 
 impl<A: TestAppStateTypes> Clone for TestAppState<A> {
@@ -39,6 +48,12 @@ impl<A: TestAppStateTypes> Clone for TestAppState<A> {
             repository_suspender: self
                 .repository_suspender
                 .clone(),
+            subscription_tokens_repository: self
+                .subscription_tokens_repository
+                .clone(),
+            confirmation_email_dead_letters_repository:
+                self.confirmation_email_dead_letters_repository
+                    .clone(),
         }
     }
 }
@@ -49,6 +64,9 @@ impl TestAppStateTypes for TestAppTypesImpl {
     type InsertNewsletterWriterRepository = PgPoolConcrete;
     type GetSubscriptionsRepository = PgPoolConcrete;
     type RepositorySuspender = PgPoolConcrete;
+    type SubscriptionTokensRepository = PgPoolConcrete;
+    type ConfirmationEmailDeadLettersRepository =
+        PgPoolConcrete;
 }
 
 pub fn get_test_app_state(
@@ -62,6 +80,9 @@ pub fn get_test_app_state(
             .clone(),
         get_subscriptions_repository: pg_pool.clone(),
         repository_suspender: pg_pool.clone(),
+        subscription_tokens_repository: pg_pool.clone(),
+        confirmation_email_dead_letters_repository: pg_pool
+            .clone(),
     }
 }
 
@@ -0,0 +1,15 @@
+pub struct DeadLetteredConfirmationEmail {
+    pub subscriber_email: String,
+    pub n_attempts: i32,
+}
+
+pub trait ConfirmationEmailDeadLettersRepository {
+    fn get_dead_lettered_confirmation_emails(
+        &self,
+    ) -> impl Future<
+        Output = Result<
+            Vec<DeadLetteredConfirmationEmail>,
+            eyre::Report,
+        >,
+    > + Send;
+}
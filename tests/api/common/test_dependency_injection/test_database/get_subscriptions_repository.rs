@@ -10,6 +10,12 @@ pub enum SubscriptionStatus {
     PendingConfirmation,
     #[display("confirmed")]
     Confirmed,
+    #[display("unsubscribed")]
+    Unsubscribed,
+    #[display("bounced")]
+    Bounced,
+    #[display("complained")]
+    Complained,
 }
 
 impl TryFrom<&str> for SubscriptionStatus {
@@ -23,6 +29,13 @@ impl TryFrom<&str> for SubscriptionStatus {
             "confirmed" => {
                 Ok(SubscriptionStatus::Confirmed)
             }
+            "unsubscribed" => {
+                Ok(SubscriptionStatus::Unsubscribed)
+            }
+            "bounced" => Ok(SubscriptionStatus::Bounced),
+            "complained" => {
+                Ok(SubscriptionStatus::Complained)
+            }
             _ => Err(()),
         }
     }
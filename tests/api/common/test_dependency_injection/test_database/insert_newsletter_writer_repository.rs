@@ -6,6 +6,7 @@ pub trait InsertNewsletterWriterRepository {
         &self,
         user_id: Uuid,
         username: &str,
+        email: &str,
         password_hash: &SecretString,
     ) -> impl Future<Output = Result<(), eyre::Report>> + Send;
 }
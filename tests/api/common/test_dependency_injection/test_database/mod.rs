@@ -1,4 +1,6 @@
+pub mod confirmation_email_dead_letters_repository;
 pub mod get_subscriptions_repository;
 pub mod insert_newsletter_writer_repository;
 pub mod postgres;
 pub mod repository_suspender;
+pub mod subscription_tokens_repository;
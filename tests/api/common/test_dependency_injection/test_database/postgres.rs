@@ -3,8 +3,9 @@ use secrecy::ExposeSecret;
 use zero2prod::database::postgres::{
     PgPool, PgPoolDependencies,
 };
+use zero2prod::utils::Pipe;
 
-use crate::common::test_dependency_injection::test_database::{get_subscriptions_repository::GetSubscriptionsRepository, insert_newsletter_writer_repository::InsertNewsletterWriterRepository, repository_suspender::RepositorySuspender};
+use crate::common::test_dependency_injection::test_database::{confirmation_email_dead_letters_repository::{ConfirmationEmailDeadLettersRepository, DeadLetteredConfirmationEmail}, get_subscriptions_repository::GetSubscriptionsRepository, insert_newsletter_writer_repository::InsertNewsletterWriterRepository, repository_suspender::RepositorySuspender, subscription_tokens_repository::SubscriptionTokensRepository};
 
 use super::get_subscriptions_repository::SubscriptionStatus;
 
@@ -44,13 +45,15 @@ impl<D: PgPoolDependencies> InsertNewsletterWriterRepository
         &self,
         user_id: uuid::Uuid,
         username: &str,
+        email: &str,
         password_hash: &secrecy::SecretString,
     ) -> Result<(), eyre::Report> {
         sqlx::query!("--sql
-            INSERT INTO newsletter_writers (user_id, username, salted_password) 
-            VALUES ($1, $2, $3)",
+            INSERT INTO newsletter_writers (user_id, username, email, salted_password) 
+            VALUES ($1, $2, $3, $4)",
             user_id,
             username,
+            email,
             password_hash.expose_secret(),
         )
         .execute(self.pool())
@@ -75,4 +78,95 @@ impl<D: PgPoolDependencies> RepositorySuspender
 
         Ok(())
     }
+
+    async fn suspend_confirmation_email_outbox(
+        &self,
+    ) -> Result<(), eyre::Report> {
+        sqlx::raw_sql(
+            "--sql
+            CREATE FUNCTION reject_confirmation_email_outbox_writes()
+            RETURNS trigger AS $$
+            BEGIN
+                RAISE EXCEPTION 'The confirmation email outbox is suspended.';
+            END;
+            $$ LANGUAGE plpgsql;
+
+            CREATE TRIGGER suspend_confirmation_email_outbox
+            BEFORE UPDATE OR DELETE ON confirmation_email_outbox
+            FOR EACH ROW
+            EXECUTE FUNCTION reject_confirmation_email_outbox_writes();",
+        )
+        .execute(self.pool())
+        .await
+        .context("Expected to suspend the confirmation email outbox.")?;
+
+        Ok(())
+    }
+}
+
+impl<D: PgPoolDependencies> SubscriptionTokensRepository
+    for PgPool<D>
+{
+    async fn age_tokens(
+        &self,
+        age: std::time::Duration,
+    ) -> Result<(), eyre::Report> {
+        sqlx::query!(
+            "--sql
+            UPDATE subscription_tokens
+            SET created_at = created_at - $1::interval",
+            age.pipe(
+                sqlx::postgres::types::PgInterval::try_from
+            )
+            .map_err(|e| eyre::eyre!(e))?
+        )
+        .execute(self.pool())
+        .await
+        .context("Expected to age subscription tokens.")?;
+
+        Ok(())
+    }
+
+    async fn get_token_hashes(
+        &self,
+    ) -> Result<Vec<Vec<u8>>, eyre::Report> {
+        sqlx::query!(
+            "--sql
+            SELECT token_hash FROM subscription_tokens"
+        )
+        .fetch_all(self.pool())
+        .await
+        .context("Expected to fetch subscription tokens.")?
+        .into_iter()
+        .map(|row| row.token_hash)
+        .collect::<Vec<_>>()
+        .pipe(Ok)
+    }
+}
+
+impl<D: PgPoolDependencies>
+    ConfirmationEmailDeadLettersRepository for PgPool<D>
+{
+    async fn get_dead_lettered_confirmation_emails(
+        &self,
+    ) -> Result<
+        Vec<DeadLetteredConfirmationEmail>,
+        eyre::Report,
+    > {
+        sqlx::query!(
+            "--sql
+            SELECT subscriber_email, n_attempts
+            FROM confirmation_email_dead_letters"
+        )
+        .fetch_all(self.pool())
+        .await
+        .context("Expected to fetch dead lettered confirmation emails.")?
+        .into_iter()
+        .map(|row| DeadLetteredConfirmationEmail {
+            subscriber_email: row.subscriber_email,
+            n_attempts: row.n_attempts,
+        })
+        .collect::<Vec<_>>()
+        .pipe(Ok)
+    }
 }
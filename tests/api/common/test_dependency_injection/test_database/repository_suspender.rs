@@ -2,4 +2,10 @@ pub trait RepositorySuspender {
     fn suspend(
         &self,
     ) -> impl Future<Output = Result<(), eyre::Report>> + Send;
+
+    /// Fails every later update or delete of the confirmation email outbox,
+    /// i.e. storing the outcome of a sent email.
+    fn suspend_confirmation_email_outbox(
+        &self,
+    ) -> impl Future<Output = Result<(), eyre::Report>> + Send;
 }
@@ -0,0 +1,15 @@
+use std::time::Duration;
+
+pub trait SubscriptionTokensRepository {
+    /// Moves the creation time of every token into the past.
+    fn age_tokens(
+        &self,
+        age: Duration,
+    ) -> impl Future<Output = Result<(), eyre::Report>> + Send;
+
+    fn get_token_hashes(
+        &self,
+    ) -> impl Future<
+        Output = Result<Vec<Vec<u8>>, eyre::Report>,
+    > + Send;
+}
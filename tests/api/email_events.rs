@@ -0,0 +1,326 @@
+use std::time::Duration;
+
+use nameof::name_of;
+use zero2prod::utils::Pipe;
+
+use crate::common::{
+    self, TestApp, a_valid_newsletter_request_body,
+    confirm_subscriber, create_test_newsletter_writer,
+    create_unconfirmed_subscriber_with, email_server,
+    test_dependency_injection::test_database::get_subscriptions_repository::{
+        GetSubscriptionsRepository as _, SubscriptionStatus,
+    },
+};
+
+const NAME: &str = "le guin";
+const EMAIL: &str = "ursula_le_guin@gmail.com";
+
+async fn arrange<'a>() -> TestApp<'a> {
+    let app = common::spawn_app().await;
+
+    create_test_newsletter_writer(&app).await;
+    create_unconfirmed_subscriber_with(&app, NAME, EMAIL)
+        .await
+        .pipe(confirm_subscriber)
+        .await;
+
+    app.post_login_with_default().await.unwrap();
+
+    app
+}
+
+fn email_event(
+    record_type: &str,
+    bounce_type: &str,
+) -> serde_json::Value {
+    serde_json::json!({
+        "RecordType": record_type,
+        "Type": bounce_type,
+        "MessageID": email_server::message_id(EMAIL),
+        "Email": EMAIL,
+        "Description": "The server was unable to deliver your message.",
+        "BouncedAt": "2026-10-18T09:12:00Z",
+    })
+}
+
+async fn subscription_status(
+    app: &TestApp<'_>,
+) -> SubscriptionStatus {
+    app.test_app_state
+        .get_subscriptions_repository
+        .get_subscriptions(NAME)
+        .await
+        .unwrap()
+        .status
+}
+
+async fn publish_and_dispatch_newsletter(
+    app: &TestApp<'_>,
+) {
+    app.post_newsletter(&a_valid_newsletter_request_body())
+        .await
+        .unwrap();
+
+    app.dispatch_all_pending_emails().await;
+}
+
+#[actix_web::test]
+async fn hard_bounce_suppresses_the_address() {
+    let app = arrange().await;
+
+    let response = app
+        .post_email_event(&email_event(
+            "Bounce",
+            "HardBounce",
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        subscription_status(&app).await,
+        SubscriptionStatus::Bounced
+    );
+
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+
+    publish_and_dispatch_newsletter(&app).await;
+    // Mock verifies on Drop that nothing was sent.
+}
+
+#[actix_web::test]
+async fn spam_complaint_suppresses_the_address() {
+    let app = arrange().await;
+
+    app.post_email_event(&email_event(
+        "SpamComplaint",
+        "SpamComplaint",
+    ))
+    .await
+    .unwrap()
+    .error_for_status()
+    .unwrap();
+
+    assert_eq!(
+        subscription_status(&app).await,
+        SubscriptionStatus::Complained
+    );
+}
+
+#[actix_web::test]
+async fn address_is_suppressed_whatever_its_case() {
+    let app = arrange().await;
+
+    let mut email_event =
+        email_event("Bounce", "HardBounce");
+    email_event["Email"] = EMAIL.to_uppercase().into();
+
+    app.post_email_event(&email_event)
+        .await
+        .unwrap()
+        .error_for_status()
+        .unwrap();
+
+    assert_eq!(
+        subscription_status(&app).await,
+        SubscriptionStatus::Bounced
+    );
+}
+
+#[actix_web::test]
+async fn soft_bounce_does_not_suppress_the_address() {
+    let app = arrange().await;
+
+    app.post_email_event(&email_event(
+        "Bounce",
+        "SoftBounce",
+    ))
+    .await
+    .unwrap()
+    .error_for_status()
+    .unwrap();
+
+    assert_eq!(
+        subscription_status(&app).await,
+        SubscriptionStatus::Confirmed
+    );
+
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    publish_and_dispatch_newsletter(&app).await;
+}
+
+#[actix_web::test]
+async fn other_record_types_are_ignored() {
+    let app = arrange().await;
+
+    let response = app
+        .post_email_event(&serde_json::json!({
+            "RecordType": "Delivery",
+            "Recipient": EMAIL,
+        }))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        subscription_status(&app).await,
+        SubscriptionStatus::Confirmed
+    );
+}
+
+#[actix_web::test]
+async fn wrong_credentials_are_rejected() {
+    let app = arrange().await;
+
+    let response = app
+        .http_client
+        .post(format!(
+            "{}/webhooks/email-events",
+            &app.address
+        ))
+        .basic_auth(&app.webhook.username, Some("forged"))
+        .json(&email_event("Bounce", "HardBounce"))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status().as_u16(), 401);
+    assert_eq!(
+        response.headers()["WWW-Authenticate"],
+        r#"Basic realm="email-events""#
+    );
+    assert_eq!(
+        subscription_status(&app).await,
+        SubscriptionStatus::Confirmed
+    );
+}
+
+#[actix_web::test]
+async fn resubscribing_a_suppressed_address_sends_no_confirmation()
+ {
+    let app = arrange().await;
+
+    app.post_email_event(&email_event(
+        "Bounce",
+        "HardBounce",
+    ))
+    .await
+    .unwrap()
+    .error_for_status()
+    .unwrap();
+
+    email_server::get_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+
+    let (name, email) = (NAME, EMAIL);
+    let response = serde_json::json!({
+        name_of!(name): name,
+        name_of!(email): email,
+    })
+    .pipe(serde_urlencoded::to_string)
+    .unwrap()
+    .pipe(|body| app.post_subscriptions(body))
+    .await
+    .unwrap();
+    app.dispatch_all_pending_confirmation_emails().await;
+
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        subscription_status(&app).await,
+        SubscriptionStatus::Bounced
+    );
+}
+
+#[actix_web::test]
+async fn recipient_rejected_by_the_provider_is_suppressed()
+{
+    let app = arrange().await;
+
+    email_server::get_batch_mock_builder()
+        .respond_with(
+            email_server::accepted().rejecting_first(
+                1,
+                email_server::INACTIVE_RECIPIENT,
+            ),
+        )
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    publish_and_dispatch_newsletter(&app).await;
+
+    assert_eq!(
+        subscription_status(&app).await,
+        SubscriptionStatus::Bounced
+    );
+    let progress =
+        app.get_delivery_progress().await.unwrap();
+    assert_eq!(progress[0]["retrying"], 0);
+}
+
+#[actix_web::test]
+async fn failed_batch_suppresses_none_of_its_recipients() {
+    for response in [
+        wiremock::ResponseTemplate::new(404),
+        // Naming a recipient, but failing the whole batch.
+        email_server::rejected(
+            422,
+            email_server::INACTIVE_RECIPIENT,
+        ),
+    ] {
+        let mut app = arrange().await;
+        app.retry_policy.base_delay =
+            Duration::from_secs(3600);
+
+        email_server::get_batch_mock_builder()
+            .respond_with(response)
+            .expect(1)
+            .mount(&app.email_server)
+            .await;
+
+        publish_and_dispatch_newsletter(&app).await;
+
+        assert_eq!(
+            subscription_status(&app).await,
+            SubscriptionStatus::Confirmed
+        );
+        let progress =
+            app.get_delivery_progress().await.unwrap();
+        assert_eq!(progress[0]["retrying"], 1);
+    }
+}
+
+#[actix_web::test]
+async fn throttled_recipient_is_retried_rather_than_suppressed()
+ {
+    let mut app = arrange().await;
+    app.retry_policy.base_delay = Duration::from_secs(3600);
+
+    email_server::get_batch_mock_builder()
+        .respond_with(wiremock::ResponseTemplate::new(429))
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    publish_and_dispatch_newsletter(&app).await;
+
+    assert_eq!(
+        subscription_status(&app).await,
+        SubscriptionStatus::Confirmed
+    );
+    let progress =
+        app.get_delivery_progress().await.unwrap();
+    assert_eq!(progress[0]["retrying"], 1);
+}
@@ -28,5 +28,8 @@ async fn health_check_works() {
         .expect("Failed to execute request.");
     //Assert
     assert!(response.status().is_success());
-    assert_eq!(Some(0), response.content_length());
+    assert_eq!(
+        response.json::<serde_json::Value>().await.unwrap(),
+        serde_json::json!({ "email_circuit_state": "closed" })
+    );
 }
@@ -0,0 +1,124 @@
+use reqwest::Url;
+use zero2prod::utils::Pipe;
+
+use crate::common::{
+    get_link, spawn_app, spawn_app_with_mailbox,
+    test_dependency_injection::test_database::get_subscriptions_repository::{
+        GetSubscriptionsRepository, SubscriptionStatus,
+    },
+};
+
+/// Path of the first email listed by the mailbox page.
+fn first_email_path(mailbox_html: &str) -> &str {
+    let start = mailbox_html
+        .find("/dev/mailbox/")
+        .expect("The mailbox lists an email.");
+    let end = start
+        + mailbox_html[start..]
+            .find('"')
+            .expect("The email link is quoted.");
+
+    &mailbox_html[start..end]
+}
+
+fn text_body(email_html: &str) -> &str {
+    let start = email_html
+        .find("<pre>")
+        .expect("The email page shows its text body.")
+        + "<pre>".len();
+    let end = email_html
+        .find("</pre>")
+        .expect("The text body is closed.");
+
+    &email_html[start..end]
+}
+
+#[actix_rt::test]
+async fn mailbox_is_not_found_without_the_in_memory_transport()
+ {
+    // Arrange
+    let app = spawn_app().await;
+
+    // Act
+    let response =
+        app.get_public_page("/dev/mailbox").await.unwrap();
+
+    // Assert
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[actix_rt::test]
+async fn confirmation_link_of_the_mailbox_confirms_the_subscriber()
+ {
+    // Arrange
+    let app = spawn_app_with_mailbox().await;
+
+    app.post_subscriptions(
+        "name=le%20guin&email=ursula_le_guin%40gmail.com",
+    )
+    .await
+    .unwrap();
+    app.dispatch_all_pending_confirmation_emails().await;
+
+    // Act - Part 1 - List the captured emails
+    let mailbox_html =
+        app.get_mailbox_html().await.unwrap();
+    assert!(
+        mailbox_html.contains("ursula_le_guin@gmail.com")
+    );
+    assert!(mailbox_html.contains("Welcome!"));
+
+    // Act - Part 2 - Read the confirmation email
+    let response = app
+        .get_public_page(first_email_path(&mailbox_html))
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    let email_html = response.text().await.unwrap();
+    assert!(email_html.contains("<iframe"));
+
+    // Act - Part 3 - Click the confirmation link
+    let confirmation_link =
+        get_link(text_body(&email_html))
+            .as_str()
+            .pipe(Url::parse)
+            .unwrap()
+            .pipe(|mut i| {
+                i.set_port(Some(app.port)).unwrap();
+                i
+            });
+    let response =
+        reqwest::get(confirmation_link).await.unwrap();
+
+    // Assert
+    assert_eq!(response.status().as_u16(), 200);
+
+    let record = app
+        .test_app_state
+        .get_subscriptions_repository
+        .get_subscriptions("le guin")
+        .await
+        .unwrap();
+    assert_eq!(
+        record.status,
+        SubscriptionStatus::Confirmed
+    );
+}
+
+#[actix_rt::test]
+async fn unknown_email_of_the_mailbox_is_not_found() {
+    // Arrange
+    let app = spawn_app_with_mailbox().await;
+
+    // Act
+    let response = app
+        .get_public_page(&format!(
+            "/dev/mailbox/{}",
+            uuid::Uuid::new_v4()
+        ))
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(response.status().as_u16(), 404);
+}
@@ -1,8 +1,22 @@
 mod admin;
 mod common;
+mod email_events;
 mod health_check;
 mod login;
+mod mailbox;
 mod newsletter;
+mod newsletter_archive;
+mod newsletter_dead_letters;
+mod newsletter_deliveries;
+mod newsletter_delivery_notifications;
+mod newsletter_drafts;
+mod newsletter_issues;
+mod newsletter_markdown;
+mod newsletter_sanitisation;
+mod newsletter_scheduled;
+mod newsletter_templates;
+mod newsletter_test_send;
 mod reset_password;
 mod subscriptions;
 mod subscriptions_confirm;
+mod subscriptions_unsubscribe;
@@ -1,11 +1,12 @@
-use fake::Fake as _;
-use nameof::name_of;
-use uuid::Uuid;
-use zero2prod::{routes::newsletter, utils::Pipe};
+use std::{collections::HashSet, time::Duration};
+
+use zero2prod::{routes::newsletter, utils::escape_html};
 
 use crate::common::{
-    self, TestApp, assert_is_redirect_to,
-    create_test_newsletter_writer, email_server,
+    self, a_valid_newsletter_request_body,
+    assert_is_redirect_to, create_confirmed_subscribers,
+    create_test_newsletter_writer,
+    create_unconfirmed_subscribers, email_server,
 };
 
 #[actix_web::test]
@@ -19,8 +20,8 @@ async fn newsletter_is_sent_to_confirmed_subscribers_and_redirects_to_self_with_
     // Create confirmed subscribers using public APIs and Mock V
     create_confirmed_subscribers(&app).await;
 
-    email_server::get_mock_builder()
-        .respond_with(wiremock::ResponseTemplate::new(200))
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
         .expect(1)
         .mount(&app.email_server)
         .await;
@@ -49,11 +50,55 @@ async fn newsletter_is_sent_to_confirmed_subscribers_and_redirects_to_self_with_
         .await
         .unwrap();
 
-    assert!(text.contains(newsletter::SUCCESS_MESSAGE));
+    assert!(text.contains(&*escape_html(
+        newsletter::SUCCESS_MESSAGE
+    )));
 
     app.dispatch_all_pending_emails().await;
 }
 
+#[actix_web::test]
+async fn concurrent_workers_send_to_each_subscriber_once() {
+    const N_SUBSCRIBERS: usize = 6;
+
+    let mut app = common::spawn_app().await;
+    // Several batches for the workers to share.
+    app.batch_size = 2;
+
+    create_test_newsletter_writer(&app).await;
+    for _ in 0..N_SUBSCRIBERS {
+        create_confirmed_subscribers(&app).await;
+    }
+    // Forget the confirmation emails.
+    app.email_server.reset().await;
+
+    // Slow sends keep several batches in flight at once.
+    email_server::get_batch_mock_builder()
+        .respond_with(
+            email_server::accepted()
+                .with_delay(Duration::from_millis(200)),
+        )
+        .expect((N_SUBSCRIBERS / app.batch_size) as u64)
+        .mount(&app.email_server)
+        .await;
+
+    app.post_login_with_default().await.unwrap();
+    app.post_newsletter(&a_valid_newsletter_request_body())
+        .await
+        .unwrap();
+
+    app.dispatch_all_pending_emails_concurrently(3).await;
+
+    let emails =
+        email_server::sent_emails(&app.email_server).await;
+    let recipients = emails
+        .iter()
+        .map(|i| i["To"].to_string())
+        .collect::<HashSet<_>>();
+    assert_eq!(emails.len(), N_SUBSCRIBERS);
+    assert_eq!(recipients.len(), N_SUBSCRIBERS);
+}
+
 #[actix_web::test]
 async fn newsletter_is_sent_to_confirmed_subscribers_only_once_per_idempotency_key()
  {
@@ -65,8 +110,8 @@ async fn newsletter_is_sent_to_confirmed_subscribers_only_once_per_idempotency_k
     // Create confirmed subscribers using public APIs and Mock V
     create_confirmed_subscribers(&app).await;
 
-    email_server::get_mock_builder()
-        .respond_with(wiremock::ResponseTemplate::new(200))
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
         .expect(1)
         .mount(&app.email_server)
         .await;
@@ -99,7 +144,9 @@ async fn newsletter_is_sent_to_confirmed_subscribers_only_once_per_idempotency_k
             .await
             .unwrap();
 
-        assert!(text.contains(newsletter::SUCCESS_MESSAGE));
+        assert!(text.contains(&*escape_html(
+            newsletter::SUCCESS_MESSAGE
+        )));
     };
 
     call_api().await;
@@ -119,9 +166,9 @@ async fn newsletter_is_sent_to_confirmed_subscribers_only_once_between_concurren
     // Create confirmed subscribers using public APIs and Mock V
     create_confirmed_subscribers(&app).await;
 
-    email_server::get_mock_builder()
+    email_server::get_batch_mock_builder()
         .respond_with(
-            wiremock::ResponseTemplate::new(200).set_delay(
+            email_server::accepted().with_delay(
                 std::time::Duration::from_secs(1),
             ),
         )
@@ -161,7 +208,9 @@ async fn newsletter_is_sent_to_confirmed_subscribers_only_once_between_concurren
 async fn transient_errors_do_not_cause_duplicate_deliveries_on_retries()
  {
     // Arrange
-    let app = common::spawn_app().await;
+    let mut app = common::spawn_app().await;
+    // One send request per subscriber.
+    app.batch_size = 1;
     let newsletter_request_body =
         a_valid_newsletter_request_body();
     // Two subscribers instead of one!
@@ -176,14 +225,14 @@ async fn transient_errors_do_not_cause_duplicate_deliveries_on_retries()
 
     // Part 1 - Submit newsletter form
     // Email delivery fails for the second subscriber
-    email_server::get_mock_builder()
-        .respond_with(wiremock::ResponseTemplate::new(200))
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
         .named("First send request")
         .up_to_n_times(1)
         .expect(1)
         .mount(&app.email_server)
         .await;
-    email_server::get_mock_builder()
+    email_server::get_batch_mock_builder()
         .respond_with(wiremock::ResponseTemplate::new(500))
         .named("Second send request")
         .up_to_n_times(1)
@@ -198,8 +247,8 @@ async fn transient_errors_do_not_cause_duplicate_deliveries_on_retries()
     assert_eq!(response.status().as_u16(), 303);
     // Part 2 - Retry submitting the form
     // Email delivery will succeed for both subscribers now
-    email_server::get_mock_builder()
-        .respond_with(wiremock::ResponseTemplate::new(200))
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
         .expect(1)
         .named("Delivery retry")
         .mount(&app.email_server)
@@ -225,8 +274,8 @@ async fn newsletter_is_not_sent_to_unconfirmed_subscribers()
     // Create unconfirmed subscribers using public APIs and Mock V
     create_unconfirmed_subscribers(&app).await;
 
-    email_server::get_mock_builder()
-        .respond_with(wiremock::ResponseTemplate::new(200))
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
         .expect(0)
         .mount(&app.email_server)
         .await;
@@ -255,7 +304,9 @@ async fn newsletter_is_not_sent_to_unconfirmed_subscribers()
         .await
         .unwrap();
 
-    assert!(text.contains(newsletter::SUCCESS_MESSAGE));
+    assert!(text.contains(&*escape_html(
+        newsletter::SUCCESS_MESSAGE
+    )));
 
     app.dispatch_all_pending_emails().await;
     // Confirm Mock email client received no requests
@@ -267,8 +318,8 @@ async fn post_unauthorized_access_redirects_to_login() {
 
     create_test_newsletter_writer(&app).await;
 
-    email_server::get_mock_builder()
-        .respond_with(wiremock::ResponseTemplate::new(200))
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
         .expect(0)
         .mount(&app.email_server)
         .await;
@@ -284,79 +335,3 @@ async fn post_unauthorized_access_redirects_to_login() {
 
     assert_is_redirect_to(&response, "/login");
 }
-
-async fn create_confirmed_subscribers(app: &TestApp<'_>) {
-    let confirmation_link =
-        create_unconfirmed_subscribers(app).await;
-
-    assert_eq!(
-        confirmation_link.host_str().unwrap(),
-        "127.0.0.1",
-        "The confirmation link must be correctly mocked."
-    );
-
-    let _confirmation_response =
-        reqwest::get(confirmation_link)
-            .await
-            .expect("Text link must be callable")
-            .error_for_status()
-            .unwrap();
-}
-
-async fn create_unconfirmed_subscribers(
-    app: &TestApp<'_>,
-) -> reqwest::Url {
-    let mock_guard = email_server::get_mock_builder()
-        .respond_with(wiremock::ResponseTemplate::new(200))
-        .expect(1)
-        .mount_as_scoped(&app.email_server)
-        .await;
-
-    let name =
-        fake::faker::name::en::Name().fake::<String>();
-    let email = fake::faker::internet::en::SafeEmail()
-        .fake::<String>();
-
-    let body = serde_json::json!({
-        name_of!(name): name,
-        name_of!(email): email,
-    })
-    .pipe(serde_urlencoded::to_string)
-    .unwrap();
-
-    app.post_subscriptions(body)
-        .await
-        .and_then(reqwest::Response::error_for_status)
-        .unwrap();
-
-    let confirmation_links = app
-        .email_server
-        .received_requests()
-        .await
-        .unwrap()
-        .pop()
-        .unwrap()
-        .pipe_ref(|i| app.get_confirmation_links(i))
-        .unwrap();
-
-    let confirmation_link = confirmation_links
-        .plain_text
-        .into_owned()
-        .pipe(|mut i| {
-            i.set_port(app.port.pipe(Some)).unwrap();
-            i
-        });
-
-    drop(mock_guard);
-
-    confirmation_link
-}
-
-fn a_valid_newsletter_request_body() -> serde_json::Value {
-    serde_json::json!({
-        "title": "Newsletter title",
-        "content_text": "Newsletter body as plain text",
-        "content_html": "<p>Newsletter body as HTML</p>",
-        "idempotency_key": Uuid::new_v4().to_string(),
-    })
-}
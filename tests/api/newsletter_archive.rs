@@ -0,0 +1,177 @@
+use uuid::Uuid;
+use zero2prod::routes::newsletter;
+
+use crate::common::{
+    self, TestApp, assert_is_redirect_to,
+    create_confirmed_subscribers,
+    create_test_newsletter_writer, email_server,
+};
+
+const TITLE: &str = "Archived & syndicated";
+
+async fn arrange<'a>() -> TestApp<'a> {
+    let app = common::spawn_app().await;
+
+    create_test_newsletter_writer(&app).await;
+    create_confirmed_subscribers(&app).await;
+
+    app.post_login_with_default().await.unwrap();
+
+    app
+}
+
+/// Publishes & delivers an issue, returning the view in browser link of the
+/// email.
+async fn publish_issue(app: &TestApp<'_>) -> String {
+    let mock_guard = email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(1)
+        .mount_as_scoped(&app.email_server)
+        .await;
+
+    app.post_newsletter(&serde_json::json!({
+        "title": TITLE,
+        "content_text": "Plain body",
+        "content_html": "<p>Html body</p>",
+        "idempotency_key": Uuid::new_v4().to_string(),
+    }))
+    .await
+    .unwrap();
+
+    app.dispatch_all_pending_emails().await;
+
+    let body = email_server::sent_emails(&app.email_server)
+        .await
+        .pop()
+        .unwrap();
+
+    drop(mock_guard);
+
+    let text_body = body["TextBody"].as_str().unwrap();
+    let link = text_body
+        .strip_prefix("View in browser: ")
+        .and_then(|rest| rest.lines().next())
+        .unwrap()
+        .to_owned();
+
+    assert!(body["HtmlBody"].as_str().unwrap().contains(
+        &format!(r#"<a href="{link}">View in browser</a>"#)
+    ));
+
+    link
+}
+
+fn path_of(link: &str) -> &str {
+    &link[link.find("/archive/").unwrap()..]
+}
+
+#[actix_web::test]
+async fn published_issue_is_readable_on_the_web_and_in_feeds()
+ {
+    let app = arrange().await;
+
+    let link = publish_issue(&app).await;
+
+    let response =
+        app.get_public_page(path_of(&link)).await.unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    let html = response.text().await.unwrap();
+    assert!(html.contains("Archived &amp; syndicated"));
+    assert!(html.contains("<p>Html body</p>"));
+
+    let archive = app
+        .get_public_page("/archive")
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(archive.contains(path_of(&link)));
+
+    for (path, content_type) in [
+        ("/feed.atom", "application/atom+xml"),
+        ("/feed.rss", "application/rss+xml"),
+    ] {
+        let response =
+            app.get_public_page(path).await.unwrap();
+        assert!(
+            response.headers()["Content-Type"]
+                .to_str()
+                .unwrap()
+                .starts_with(content_type)
+        );
+        let feed = response.text().await.unwrap();
+        assert!(feed.contains("Archived &amp; syndicated"));
+        assert!(feed.contains(&link));
+    }
+}
+
+#[actix_web::test]
+async fn private_issue_is_hidden_from_the_public() {
+    let app = arrange().await;
+
+    let link = publish_issue(&app).await;
+    let newsletter_issue_id =
+        link.rsplit('/').next().unwrap().to_owned();
+
+    let response = app
+        .post_newsletter_visibility(
+            &newsletter_issue_id,
+            true,
+        )
+        .await
+        .unwrap();
+    assert_is_redirect_to(&response, "/admin/issues");
+
+    let html =
+        app.get_published_newsletters_html().await.unwrap();
+    assert!(
+        html.contains(newsletter::MADE_PRIVATE_MESSAGE)
+    );
+    assert!(html.contains("Private"));
+
+    let response =
+        app.get_public_page(path_of(&link)).await.unwrap();
+    assert_eq!(response.status().as_u16(), 404);
+
+    for path in ["/archive", "/feed.atom", "/feed.rss"] {
+        let page = app
+            .get_public_page(path)
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert!(!page.contains(&newsletter_issue_id));
+    }
+}
+
+#[actix_web::test]
+async fn archive_is_shown_a_page_at_a_time() {
+    let app = arrange().await;
+
+    let link = publish_issue(&app).await;
+
+    let first_page = app
+        .get_public_page("/archive")
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+
+    assert!(first_page.contains(path_of(&link)));
+    assert!(!first_page.contains("Older</a>"));
+    assert!(!first_page.contains("Newer</a>"));
+
+    let second_page = app
+        .get_public_page("/archive?page=1")
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+
+    assert!(!second_page.contains(path_of(&link)));
+    assert!(second_page.contains("page=0\">Newer</a>"));
+}
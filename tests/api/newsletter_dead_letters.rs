@@ -0,0 +1,185 @@
+use std::{num::NonZeroU32, time::Duration};
+
+use zero2prod::{
+    email_client::{CircuitBreaker, CircuitBreakerPolicy},
+    issue_delivery_worker::{
+        IssueDeliveryWorkerDependencies,
+        get_single_newsletter_picking_and_sending_iterator,
+    },
+    routes::newsletter,
+};
+
+use crate::common::{
+    self, TestApp, a_valid_newsletter_request_body,
+    assert_is_redirect_to, create_confirmed_subscribers,
+    create_test_newsletter_writer, email_server,
+};
+
+async fn arrange<'a>(n_subscribers: usize) -> TestApp<'a> {
+    let app = common::spawn_app().await;
+
+    create_test_newsletter_writer(&app).await;
+    for _ in 0..n_subscribers {
+        create_confirmed_subscribers(&app).await;
+    }
+    // Forget the confirmation emails.
+    app.email_server.reset().await;
+
+    app.post_login_with_default().await.unwrap();
+
+    app
+}
+
+#[actix_web::test]
+async fn only_the_failed_recipient_is_delayed() {
+    let mut app = arrange(2).await;
+    app.retry_policy.base_delay = Duration::from_secs(3600);
+
+    // Both recipients are in the same batch, only one is rejected.
+    email_server::get_batch_mock_builder()
+        .respond_with(
+            email_server::accepted().rejecting_first(
+                1,
+                email_server::MAINTENANCE,
+            ),
+        )
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    app.post_newsletter(&a_valid_newsletter_request_body())
+        .await
+        .unwrap();
+
+    app.dispatch_all_pending_emails().await;
+
+    let progress =
+        app.get_delivery_progress().await.unwrap();
+    let issue = &progress[0];
+    assert_eq!(issue["retrying"], 1);
+    assert_eq!(issue["delivered"], 1);
+}
+
+#[actix_web::test]
+async fn recipient_out_of_attempts_is_dead_lettered_until_requeued()
+ {
+    let app = arrange(1).await;
+
+    let mock_guard = email_server::get_batch_mock_builder()
+        .respond_with(wiremock::ResponseTemplate::new(500))
+        .expect(u64::from(app.retry_policy.max_attempts))
+        .mount_as_scoped(&app.email_server)
+        .await;
+
+    app.post_newsletter(&a_valid_newsletter_request_body())
+        .await
+        .unwrap();
+
+    app.dispatch_all_pending_emails().await;
+
+    drop(mock_guard);
+
+    let progress =
+        app.get_delivery_progress().await.unwrap();
+    let issue = &progress[0];
+    assert_eq!(issue["pending"], 0);
+    assert_eq!(issue["retrying"], 0);
+    assert_eq!(issue["dead_lettered"], 1);
+    assert_eq!(issue["delivered"], 0);
+
+    let newsletter_issue_id =
+        issue["newsletter_issue_id"].as_str().unwrap();
+    let html = app.get_dead_letters_html().await.unwrap();
+    let subscriber_email = html
+        .split(r#"name="subscriber_email" value=""#)
+        .nth(1)
+        .and_then(|rest| rest.split('"').next())
+        .unwrap()
+        .to_owned();
+    assert!(html.contains(&format!(
+        "<td>{}</td>",
+        app.retry_policy.max_attempts
+    )));
+
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    let response = app
+        .post_requeue_dead_letter(
+            newsletter_issue_id,
+            &subscriber_email,
+        )
+        .await
+        .unwrap();
+    assert_is_redirect_to(&response, "/admin/dead-letters");
+
+    let html = app.get_dead_letters_html().await.unwrap();
+    assert!(html.contains(
+        newsletter::DEAD_LETTER_REQUEUED_MESSAGE
+    ));
+    assert!(!html.contains(&subscriber_email));
+
+    app.dispatch_all_pending_emails().await;
+
+    let progress =
+        app.get_delivery_progress().await.unwrap();
+    assert_eq!(progress[0]["dead_lettered"], 0);
+    assert_eq!(progress[0]["delivered"], 1);
+}
+
+#[actix_web::test]
+async fn recipient_waits_in_the_queue_while_the_circuit_is_open()
+ {
+    let mut app = arrange(1).await;
+    // Dead letters recipients on their second failed attempt.
+    app.retry_policy.max_attempts = 2;
+    let email_client =
+        app.email_client.clone().with_circuit_breaker(
+            CircuitBreaker::new(CircuitBreakerPolicy {
+                failure_threshold: NonZeroU32::MIN,
+                open_duration_milliseconds: 60_000,
+            }),
+        );
+
+    email_server::get_batch_mock_builder()
+        .respond_with(wiremock::ResponseTemplate::new(500))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    app.post_newsletter(&a_valid_newsletter_request_body())
+        .await
+        .unwrap();
+
+    // The first batch fails & opens the circuit, the next ones are not sent.
+    let dependencies = IssueDeliveryWorkerDependencies {
+        email_client: &email_client,
+        ..app.issue_delivery_worker_dependencies()
+    };
+    for task in
+        get_single_newsletter_picking_and_sending_iterator(
+            &dependencies,
+        )
+        .take(1 + app.retry_policy.max_attempts as usize)
+    {
+        task.await;
+    }
+
+    // Sent once the provider is back, with attempts left.
+    app.dispatch_all_pending_emails().await;
+
+    let progress =
+        app.get_delivery_progress().await.unwrap();
+    let issue = &progress[0];
+    assert_eq!(issue["dead_lettered"], 0);
+    assert_eq!(issue["delivered"], 1);
+}
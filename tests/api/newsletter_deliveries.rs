@@ -0,0 +1,224 @@
+use zero2prod::utils::Pipe;
+
+use crate::common::{
+    self, TestApp, a_valid_newsletter_request_body,
+    confirm_subscriber, create_test_newsletter_writer,
+    create_unconfirmed_subscriber_with, email_server,
+};
+
+const SUBSCRIBER_EMAIL: &str = "ursula_le_guin@gmail.com";
+
+async fn arrange<'a>() -> TestApp<'a> {
+    let app = common::spawn_app().await;
+
+    create_test_newsletter_writer(&app).await;
+    create_unconfirmed_subscriber_with(
+        &app,
+        "le guin",
+        SUBSCRIBER_EMAIL,
+    )
+    .await
+    .pipe(confirm_subscriber)
+    .await;
+
+    app.post_login_with_default().await.unwrap();
+
+    app
+}
+
+#[actix_web::test]
+async fn every_attempt_is_logged_with_the_provider_message_id()
+ {
+    let app = arrange().await;
+
+    email_server::get_batch_mock_builder()
+        .respond_with(wiremock::ResponseTemplate::new(500))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    app.post_newsletter(&a_valid_newsletter_request_body())
+        .await
+        .unwrap();
+
+    app.dispatch_all_pending_emails().await;
+
+    let newsletter_issue_id = app
+        .get_delivery_progress()
+        .await
+        .unwrap()[0]["newsletter_issue_id"]
+        .as_str()
+        .unwrap()
+        .to_owned();
+
+    // The log outlives the queue, drained by now.
+    for query in [
+        (
+            "newsletter_issue_id",
+            newsletter_issue_id.as_str(),
+        ),
+        ("subscriber_email", SUBSCRIBER_EMAIL),
+    ] {
+        let html = app
+            .get_email_deliveries(&[query])
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+
+        // Newest first.
+        let sent = html.find("<td>sent</td>").unwrap();
+        let failed = html.find("<td>failed</td>").unwrap();
+        assert!(sent < failed);
+
+        assert!(html.contains(
+            "<td>2</td>\n<td>sent</td>\n<td>200</td>"
+        ));
+        assert!(html.contains(&format!(
+            "<td>{}</td>",
+            email_server::message_id(SUBSCRIBER_EMAIL)
+        )));
+        assert!(html.contains(
+            "<td>1</td>\n<td>failed</td>\n<td>500</td>"
+        ));
+    }
+}
+
+#[actix_web::test]
+async fn emails_missing_from_batch_results_are_not_resent()
+{
+    let app = arrange().await;
+
+    email_server::get_batch_mock_builder()
+        .respond_with(
+            wiremock::ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!([])),
+        )
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    app.post_newsletter(&a_valid_newsletter_request_body())
+        .await
+        .unwrap();
+
+    app.dispatch_all_pending_emails().await;
+
+    let progress =
+        app.get_delivery_progress().await.unwrap();
+    assert_eq!(progress[0]["pending"], 0);
+    assert_eq!(progress[0]["retrying"], 0);
+
+    let html = app
+        .get_email_deliveries(&[(
+            "subscriber_email",
+            SUBSCRIBER_EMAIL,
+        )])
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+
+    assert!(html.contains(
+        "<td>1</td>\n<td>unknown</td>\n<td>200</td>"
+    ));
+}
+
+#[actix_web::test]
+async fn deliveries_of_other_subscribers_are_not_shown() {
+    let app = arrange().await;
+
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    app.post_newsletter(&a_valid_newsletter_request_body())
+        .await
+        .unwrap();
+
+    app.dispatch_all_pending_emails().await;
+
+    let html = app
+        .get_email_deliveries(&[(
+            "subscriber_email",
+            "someone_else@example.com",
+        )])
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+
+    assert!(!html.contains(SUBSCRIBER_EMAIL));
+}
+
+#[actix_web::test]
+async fn deliveries_are_shown_a_page_at_a_time() {
+    let app = arrange().await;
+
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    app.post_newsletter(&a_valid_newsletter_request_body())
+        .await
+        .unwrap();
+
+    app.dispatch_all_pending_emails().await;
+
+    let first_page = app
+        .get_email_deliveries(&[(
+            "subscriber_email",
+            SUBSCRIBER_EMAIL,
+        )])
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+
+    assert!(first_page.contains("<td>sent</td>"));
+    assert!(!first_page.contains("Older</a>"));
+    assert!(!first_page.contains("Newer</a>"));
+
+    let second_page = app
+        .get_email_deliveries(&[
+            ("subscriber_email", SUBSCRIBER_EMAIL),
+            ("page", "1"),
+        ])
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+
+    assert!(!second_page.contains("<td>sent</td>"));
+    assert!(second_page.contains("page=0\">Newer</a>"));
+}
+
+#[actix_web::test]
+async fn invalid_issue_id_is_rejected() {
+    let app = arrange().await;
+
+    let response = app
+        .get_email_deliveries(&[(
+            "newsletter_issue_id",
+            "not-an-id",
+        )])
+        .await
+        .unwrap();
+
+    assert_eq!(response.status().as_u16(), 400);
+}
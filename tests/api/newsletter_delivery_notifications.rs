@@ -0,0 +1,98 @@
+use std::time::{Duration, Instant};
+
+use zero2prod::database::transactional::notifications::{
+    NotificationListener as _,
+    NotificationSubscription as _,
+};
+
+use crate::common::{
+    self, TestApp, a_valid_newsletter_request_body,
+    create_confirmed_subscribers,
+    create_test_newsletter_writer,
+};
+
+// Long enough to tell a notification from the polling fallback.
+const IDLE_DELAY: Duration = Duration::from_secs(30);
+
+async fn arrange<'a>() -> TestApp<'a> {
+    let app = common::spawn_app().await;
+
+    create_test_newsletter_writer(&app).await;
+    create_confirmed_subscribers(&app).await;
+
+    app.post_login_with_default().await.unwrap();
+
+    app
+}
+
+#[actix_web::test]
+async fn publishing_wakes_idle_workers_up() {
+    let app = arrange().await;
+
+    let mut subscription = app
+        .app_state
+        .issue_delivery_queue_listener
+        .listen()
+        .await
+        .unwrap();
+
+    app.post_newsletter(&a_valid_newsletter_request_body())
+        .await
+        .unwrap();
+
+    let start = Instant::now();
+    subscription
+        .wait_for_notification(IDLE_DELAY)
+        .await
+        .unwrap();
+
+    assert!(start.elapsed() < IDLE_DELAY);
+}
+
+#[actix_web::test]
+async fn every_idle_worker_is_woken_up() {
+    let app = arrange().await;
+
+    let listener =
+        &app.app_state.issue_delivery_queue_listener;
+    let mut subscription = listener.listen().await.unwrap();
+    let mut other_subscription =
+        listener.listen().await.unwrap();
+
+    app.post_newsletter(&a_valid_newsletter_request_body())
+        .await
+        .unwrap();
+
+    let start = Instant::now();
+    subscription
+        .wait_for_notification(IDLE_DELAY)
+        .await
+        .unwrap();
+    other_subscription
+        .wait_for_notification(IDLE_DELAY)
+        .await
+        .unwrap();
+
+    assert!(start.elapsed() < IDLE_DELAY);
+}
+
+#[actix_web::test]
+async fn idle_workers_fall_back_on_polling() {
+    let app = arrange().await;
+
+    let mut subscription = app
+        .app_state
+        .issue_delivery_queue_listener
+        .listen()
+        .await
+        .unwrap();
+
+    let idle_delay = Duration::from_millis(100);
+    let start = Instant::now();
+    subscription
+        .wait_for_notification(idle_delay)
+        .await
+        .unwrap();
+
+    assert!(start.elapsed() >= idle_delay);
+}
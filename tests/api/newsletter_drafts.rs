@@ -0,0 +1,204 @@
+use uuid::Uuid;
+use zero2prod::{routes::newsletter, utils::escape_html};
+
+use crate::common::{
+    self, TestApp, assert_is_redirect_to,
+    create_confirmed_subscribers,
+    create_test_newsletter_writer, email_server,
+};
+
+async fn arrange<'a>() -> TestApp<'a> {
+    let app = common::spawn_app().await;
+
+    create_test_newsletter_writer(&app).await;
+    create_confirmed_subscribers(&app).await;
+
+    app.post_login_with_default().await.unwrap();
+
+    app
+}
+
+fn a_draft_body(title: &str) -> serde_json::Value {
+    serde_json::json!({
+        "title": title,
+        "content_text": "Draft body as plain text",
+        "content_html": "<p>Draft body as HTML</p>",
+    })
+}
+
+/// Returns the location of the created draft's edit page.
+async fn create_draft(
+    app: &TestApp<'_>,
+    title: &str,
+) -> String {
+    let response = app
+        .post_newsletter_draft(&a_draft_body(title))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status().as_u16(), 303);
+
+    response
+        .headers()
+        .get("Location")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_owned()
+}
+
+#[actix_web::test]
+async fn draft_is_not_sent_until_published() {
+    let app = arrange().await;
+
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+
+    let location = create_draft(&app, "Draft title").await;
+
+    let html =
+        app.get_newsletter_drafts_html().await.unwrap();
+    assert!(html.contains("Draft title"));
+    assert!(html.contains(&location));
+
+    app.dispatch_all_pending_emails().await;
+    // Mock verifies on Drop that nothing was sent.
+}
+
+#[actix_web::test]
+async fn published_draft_is_sent_only_once_per_idempotency_key()
+ {
+    let app = arrange().await;
+
+    let location = create_draft(&app, "Draft title").await;
+
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    let body = serde_json::json!({
+        "idempotency_key": Uuid::new_v4().to_string(),
+        "scheduled_for": "",
+    });
+
+    for _ in 0..2 {
+        let response = app
+            .post_newsletter_draft_action(
+                &location, "/publish", &body,
+            )
+            .await
+            .unwrap();
+
+        assert_is_redirect_to(
+            &response,
+            "/admin/newsletters",
+        );
+    }
+
+    let text = app
+        .get_newsletter_form()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(text.contains(&*escape_html(
+        newsletter::SUCCESS_MESSAGE
+    )));
+
+    let html =
+        app.get_newsletter_drafts_html().await.unwrap();
+    assert!(!html.contains("Draft title"));
+
+    app.dispatch_all_pending_emails().await;
+    // Mock verifies on Drop that the draft was sent once.
+}
+
+#[actix_web::test]
+async fn preview_renders_the_edited_draft_bodies() {
+    let app = arrange().await;
+
+    let location = create_draft(&app, "Draft title").await;
+
+    let response = app
+        .post_newsletter_draft_action(
+            &location,
+            "",
+            &serde_json::json!({
+                "title": "Edited title",
+                "content_text": "Edited text",
+                "content_html": "<p>Edited HTML</p>",
+            }),
+        )
+        .await
+        .unwrap();
+
+    assert_is_redirect_to(&response, &location);
+
+    let html_preview = app
+        .get_newsletter_draft_page(
+            &location,
+            "/preview/html",
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        html_preview.headers()["Content-Type"],
+        "text/html; charset=utf-8"
+    );
+    assert_eq!(
+        html_preview.text().await.unwrap(),
+        "<p>Edited HTML</p>"
+    );
+
+    let text_preview = app
+        .get_newsletter_draft_page(
+            &location,
+            "/preview/text",
+        )
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert_eq!(text_preview, "Edited text");
+}
+
+#[actix_web::test]
+async fn deleted_draft_is_no_longer_listed() {
+    let app = arrange().await;
+
+    let location = create_draft(&app, "Draft title").await;
+
+    let response = app
+        .post_newsletter_draft_action(
+            &location,
+            "/delete",
+            &serde_json::json!({}),
+        )
+        .await
+        .unwrap();
+
+    assert_is_redirect_to(
+        &response,
+        "/admin/newsletters/drafts",
+    );
+
+    let html =
+        app.get_newsletter_drafts_html().await.unwrap();
+    assert!(
+        html.contains(newsletter::DRAFT_DELETED_MESSAGE)
+    );
+    assert!(!html.contains("Draft title"));
+
+    let response = app
+        .get_newsletter_draft_page(&location, "")
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 404);
+}
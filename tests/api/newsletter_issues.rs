@@ -0,0 +1,227 @@
+use zero2prod::routes::newsletter;
+
+use crate::common::{
+    self, TestApp, a_valid_newsletter_request_body,
+    assert_is_redirect_to, create_confirmed_subscribers,
+    create_test_newsletter_writer, email_server,
+};
+
+const N_SUBSCRIBERS: i64 = 2;
+
+async fn arrange<'a>() -> TestApp<'a> {
+    let app = common::spawn_app().await;
+
+    create_test_newsletter_writer(&app).await;
+    for _ in 0..N_SUBSCRIBERS {
+        create_confirmed_subscribers(&app).await;
+    }
+
+    app.post_login_with_default().await.unwrap();
+
+    app
+}
+
+/// Publishes an issue without delivering it, returning its id.
+async fn publish_issue(app: &TestApp<'_>) -> String {
+    app.post_newsletter(&a_valid_newsletter_request_body())
+        .await
+        .unwrap();
+
+    app.get_delivery_progress().await.unwrap()[0]
+        ["newsletter_issue_id"]
+        .as_str()
+        .unwrap()
+        .to_owned()
+}
+
+#[actix_web::test]
+async fn delivery_progress_follows_the_queue_being_drained()
+{
+    let app = arrange().await;
+
+    // Both subscribers in a single batch.
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    app.post_newsletter(&a_valid_newsletter_request_body())
+        .await
+        .unwrap();
+
+    let progress =
+        app.get_delivery_progress().await.unwrap();
+    let issue = &progress[0];
+    assert_eq!(issue["total_recipients"], N_SUBSCRIBERS);
+    assert_eq!(issue["pending"], N_SUBSCRIBERS);
+    assert_eq!(issue["retrying"], 0);
+    assert_eq!(issue["failed"], 0);
+    assert_eq!(issue["skipped"], 0);
+    assert_eq!(issue["delivered"], 0);
+
+    let newsletter_issue_id =
+        issue["newsletter_issue_id"].as_str().unwrap();
+    let html =
+        app.get_published_newsletters_html().await.unwrap();
+    assert!(html.contains(&format!(
+        r#"<td id="{newsletter_issue_id}-pending">{N_SUBSCRIBERS}</td>"#
+    )));
+
+    app.dispatch_all_pending_emails().await;
+
+    let progress =
+        app.get_delivery_progress().await.unwrap();
+    let issue = &progress[0];
+    assert_eq!(issue["total_recipients"], N_SUBSCRIBERS);
+    assert_eq!(issue["pending"], 0);
+    assert_eq!(issue["delivered"], N_SUBSCRIBERS);
+}
+
+#[actix_web::test]
+async fn rejected_recipients_are_reported_as_failed() {
+    let app = arrange().await;
+
+    email_server::get_batch_mock_builder()
+        .respond_with(
+            email_server::accepted().rejecting_first(
+                1,
+                email_server::INACTIVE_RECIPIENT,
+            ),
+        )
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    app.post_newsletter(&a_valid_newsletter_request_body())
+        .await
+        .unwrap();
+    app.dispatch_all_pending_emails().await;
+
+    let progress =
+        app.get_delivery_progress().await.unwrap();
+    let issue = &progress[0];
+    assert_eq!(issue["total_recipients"], N_SUBSCRIBERS);
+    assert_eq!(issue["pending"], 0);
+    assert_eq!(issue["failed"], 1);
+    assert_eq!(issue["skipped"], 0);
+    assert_eq!(issue["delivered"], N_SUBSCRIBERS - 1);
+}
+
+#[actix_web::test]
+async fn delivery_progress_requires_login() {
+    let app = common::spawn_app().await;
+
+    let response = app
+        .http_client
+        .get(format!(
+            "{}/admin/issues/progress",
+            app.address
+        ))
+        .send()
+        .await
+        .unwrap();
+
+    common::assert_is_redirect_to(&response, "/login");
+}
+
+#[actix_web::test]
+async fn paused_issue_is_skipped_until_resumed() {
+    let app = arrange().await;
+
+    let newsletter_issue_id = publish_issue(&app).await;
+
+    let response = app
+        .post_issue_delivery_action(
+            &newsletter_issue_id,
+            "pause",
+        )
+        .await
+        .unwrap();
+    assert_is_redirect_to(&response, "/admin/issues");
+
+    let html =
+        app.get_published_newsletters_html().await.unwrap();
+    assert!(
+        html.contains(newsletter::DELIVERY_PAUSED_MESSAGE)
+    );
+    assert!(html.contains(&format!(
+        r#"<td id="{newsletter_issue_id}-delivery_status">paused</td>"#
+    )));
+
+    let mock_guard = email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(0)
+        .mount_as_scoped(&app.email_server)
+        .await;
+
+    app.dispatch_all_pending_emails().await;
+
+    drop(mock_guard);
+
+    app.post_issue_delivery_action(
+        &newsletter_issue_id,
+        "resume",
+    )
+    .await
+    .unwrap();
+
+    // Both subscribers in a single batch.
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    app.dispatch_all_pending_emails().await;
+
+    let progress =
+        app.get_delivery_progress().await.unwrap();
+    assert_eq!(progress[0]["delivery_status"], "active");
+    assert_eq!(progress[0]["delivered"], N_SUBSCRIBERS);
+}
+
+#[actix_web::test]
+async fn cancelled_issue_keeps_its_remaining_tasks_as_cancelled()
+ {
+    let app = arrange().await;
+
+    let newsletter_issue_id = publish_issue(&app).await;
+
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+
+    app.post_issue_delivery_action(
+        &newsletter_issue_id,
+        "cancel",
+    )
+    .await
+    .unwrap();
+
+    app.dispatch_all_pending_emails().await;
+
+    let progress =
+        app.get_delivery_progress().await.unwrap();
+    let issue = &progress[0];
+    assert_eq!(issue["delivery_status"], "cancelled");
+    assert_eq!(issue["total_recipients"], N_SUBSCRIBERS);
+    assert_eq!(issue["pending"], 0);
+    assert_eq!(issue["cancelled"], N_SUBSCRIBERS);
+    assert_eq!(issue["delivered"], 0);
+
+    app.post_issue_delivery_action(
+        &newsletter_issue_id,
+        "resume",
+    )
+    .await
+    .unwrap();
+
+    let html =
+        app.get_published_newsletters_html().await.unwrap();
+    assert!(html.contains(
+        newsletter::DELIVERY_NOT_CHANGEABLE_MESSAGE
+    ));
+}
@@ -0,0 +1,97 @@
+use uuid::Uuid;
+
+use crate::common::{
+    self, TestApp, create_confirmed_subscribers,
+    create_test_newsletter_writer, email_server,
+};
+
+const MARKDOWN: &str = "# Hello\n\nSome *news* for you.";
+
+async fn arrange<'a>() -> TestApp<'a> {
+    let app = common::spawn_app().await;
+
+    create_test_newsletter_writer(&app).await;
+    create_confirmed_subscribers(&app).await;
+
+    app.post_login_with_default().await.unwrap();
+
+    app
+}
+
+#[actix_web::test]
+async fn markdown_newsletter_is_sent_as_html_and_text() {
+    let app = arrange().await;
+
+    let mock_guard = email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(1)
+        .mount_as_scoped(&app.email_server)
+        .await;
+
+    app.post_newsletter(&serde_json::json!({
+        "title": "Newsletter title",
+        "content_markdown": MARKDOWN,
+        "idempotency_key": Uuid::new_v4().to_string(),
+    }))
+    .await
+    .unwrap();
+
+    app.dispatch_all_pending_emails().await;
+
+    let body = email_server::sent_emails(&app.email_server)
+        .await
+        .pop()
+        .unwrap();
+
+    drop(mock_guard);
+
+    // Preceded by the view in browser link.
+    assert!(body["HtmlBody"].as_str().unwrap().ends_with(
+        "<h1>Hello</h1>\n<p>Some <em>news</em> for you.</p>\n"
+    ));
+    assert!(
+        body["TextBody"].as_str().unwrap().ends_with(
+            "Hello\n=====\n\nSome news for you."
+        )
+    );
+}
+
+#[actix_web::test]
+async fn markdown_source_of_draft_is_kept_for_editing() {
+    let app = arrange().await;
+
+    let location = app
+        .post_newsletter_draft(&serde_json::json!({
+            "title": "Draft title",
+            "content_markdown": MARKDOWN,
+        }))
+        .await
+        .unwrap()
+        .headers()
+        .get("Location")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_owned();
+
+    let html = app
+        .get_newsletter_draft_page(&location, "")
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(html.contains(MARKDOWN));
+
+    let preview = app
+        .get_newsletter_draft_page(
+            &location,
+            "/preview/html",
+        )
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(preview.contains("<h1>Hello</h1>"));
+}
@@ -0,0 +1,130 @@
+use uuid::Uuid;
+use zero2prod::routes::newsletter;
+
+use crate::common::{
+    self, TestApp, assert_is_redirect_to,
+    create_confirmed_subscribers,
+    create_test_newsletter_writer, email_server,
+};
+
+async fn arrange<'a>() -> TestApp<'a> {
+    let app = common::spawn_app().await;
+
+    create_test_newsletter_writer(&app).await;
+    create_confirmed_subscribers(&app).await;
+
+    app.post_login_with_default().await.unwrap();
+
+    app
+}
+
+#[actix_web::test]
+async fn html_outside_of_the_email_safe_subset_is_rejected_with_every_violation()
+ {
+    let app = arrange().await;
+
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+
+    let response = app
+        .post_newsletter(&serde_json::json!({
+            "title": "Newsletter title",
+            "content_text": "Newsletter body as plain text",
+            "content_html": r#"<p onclick="steal()">Hi</p><script>alert(1)</script>"#,
+            "idempotency_key": Uuid::new_v4().to_string(),
+        }))
+        .await
+        .unwrap();
+
+    assert_is_redirect_to(&response, "/admin/newsletters");
+
+    let text = app
+        .get_newsletter_form()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(
+        text.contains(newsletter::INVALID_HTML_MESSAGE)
+    );
+    assert!(text.contains(
+        "Attribute &#x27;onclick&#x27; is not allowed on &lt;p&gt;."
+    ));
+    assert!(
+        text.contains("Tag &lt;script&gt; is not allowed.")
+    );
+
+    app.dispatch_all_pending_emails().await;
+    // Mock verifies on Drop that nothing was sent.
+}
+
+#[actix_web::test]
+async fn json_requests_get_validation_errors_in_a_400_response()
+ {
+    let app = arrange().await;
+
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+
+    let response = app
+        .post_newsletter_json(&serde_json::json!({
+            "issue": { "New": {
+                "title": "Newsletter title",
+                "content": {
+                    "text": "Newsletter body as plain text",
+                    "html": r#"<a href="javascript:steal()">Hi</a>"#,
+                },
+            }},
+            "idempotency_key": Uuid::new_v4().to_string(),
+        }))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status().as_u16(), 400);
+    let body: serde_json::Value =
+        response.json().await.unwrap();
+    assert_eq!(
+        body["message"],
+        newsletter::INVALID_HTML_MESSAGE
+    );
+    assert_eq!(
+        body["errors"],
+        serde_json::json!([
+            "Url 'javascript:steal()' of <a> is neither http, https or mailto nor a path of the application."
+        ])
+    );
+
+    app.dispatch_all_pending_emails().await;
+    // Mock verifies on Drop that nothing was sent.
+}
+
+#[actix_web::test]
+async fn user_controlled_strings_are_escaped_on_admin_pages()
+ {
+    let app = arrange().await;
+
+    app.post_newsletter_draft(&serde_json::json!({
+        "title": "<script>alert(1)</script>",
+        "content_text": "text",
+        "content_html": "<p>html</p>",
+    }))
+    .await
+    .unwrap();
+
+    let html =
+        app.get_newsletter_drafts_html().await.unwrap();
+
+    assert!(!html.contains("<script>"));
+    assert!(
+        html.contains(
+            "&lt;script&gt;alert(1)&lt;/script&gt;"
+        )
+    );
+}
@@ -0,0 +1,207 @@
+use chrono::{TimeDelta, Utc};
+use zero2prod::{routes::newsletter, utils::escape_html};
+
+use crate::common::{
+    self, TestApp, a_valid_newsletter_request_body,
+    assert_is_redirect_to, create_confirmed_subscribers,
+    create_test_newsletter_writer, email_server,
+};
+
+async fn arrange<'a>() -> TestApp<'a> {
+    let app = common::spawn_app().await;
+
+    create_test_newsletter_writer(&app).await;
+    create_confirmed_subscribers(&app).await;
+
+    app.post_login_with_default().await.unwrap();
+
+    app
+}
+
+fn a_newsletter_request_body_scheduled_in(
+    delay: TimeDelta,
+) -> serde_json::Value {
+    let mut body = a_valid_newsletter_request_body();
+    body["scheduled_for"] =
+        (Utc::now() + delay).to_rfc3339().into();
+    body
+}
+
+fn get_cancel_id(html: &str) -> &str {
+    html.split("/admin/newsletters/scheduled/")
+        .nth(1)
+        .and_then(|i| i.split("/cancel").next())
+        .expect("Scheduled newsletter must be listed.")
+}
+
+#[actix_web::test]
+async fn scheduled_newsletter_is_not_sent_before_its_time()
+{
+    let app = arrange().await;
+
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+
+    let response = app
+        .post_newsletter(
+            &a_newsletter_request_body_scheduled_in(
+                TimeDelta::hours(1),
+            ),
+        )
+        .await
+        .unwrap();
+
+    assert_is_redirect_to(&response, "/admin/newsletters");
+
+    let text = app
+        .get_newsletter_form()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+
+    assert!(text.contains(&*escape_html(
+        newsletter::SCHEDULED_MESSAGE
+    )));
+
+    app.dispatch_all_pending_emails().await;
+
+    let html =
+        app.get_scheduled_newsletters_html().await.unwrap();
+
+    assert!(html.contains("Newsletter title"));
+    // Mock verifies on Drop that nothing was sent.
+}
+
+#[actix_web::test]
+async fn scheduled_newsletter_is_sent_once_its_time_is_reached()
+ {
+    let app = arrange().await;
+
+    let mock_guard = email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(0)
+        .mount_as_scoped(&app.email_server)
+        .await;
+
+    app.post_newsletter(
+        &a_newsletter_request_body_scheduled_in(
+            TimeDelta::seconds(2),
+        ),
+    )
+    .await
+    .unwrap();
+
+    app.dispatch_all_pending_emails().await;
+    drop(mock_guard);
+
+    tokio::time::sleep(std::time::Duration::from_secs(3))
+        .await;
+
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    app.dispatch_all_pending_emails().await;
+    // Mock verifies on Drop that the newsletter was sent.
+}
+
+#[actix_web::test]
+async fn cancelled_scheduled_newsletter_is_removed_from_the_list()
+ {
+    let app = arrange().await;
+
+    app.post_newsletter(
+        &a_newsletter_request_body_scheduled_in(
+            TimeDelta::hours(1),
+        ),
+    )
+    .await
+    .unwrap();
+
+    let html =
+        app.get_scheduled_newsletters_html().await.unwrap();
+
+    let response = app
+        .post_cancel_scheduled_newsletter(get_cancel_id(
+            &html,
+        ))
+        .await
+        .unwrap();
+
+    assert_is_redirect_to(
+        &response,
+        "/admin/newsletters/scheduled",
+    );
+
+    let html =
+        app.get_scheduled_newsletters_html().await.unwrap();
+
+    assert!(html.contains(newsletter::CANCELLED_MESSAGE));
+    assert!(!html.contains("Newsletter title"));
+}
+
+#[actix_web::test]
+async fn newsletter_that_is_not_scheduled_cannot_be_cancelled()
+ {
+    let app = arrange().await;
+
+    let response = app
+        .post_cancel_scheduled_newsletter(
+            &uuid::Uuid::new_v4().to_string(),
+        )
+        .await
+        .unwrap();
+
+    assert_is_redirect_to(
+        &response,
+        "/admin/newsletters/scheduled",
+    );
+
+    let html =
+        app.get_scheduled_newsletters_html().await.unwrap();
+
+    assert!(html.contains(&*escape_html(
+        newsletter::NOT_CANCELLABLE_MESSAGE
+    )));
+}
+
+#[actix_web::test]
+async fn cancelled_scheduled_newsletter_is_never_sent() {
+    let app = arrange().await;
+
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+
+    app.post_newsletter(
+        &a_newsletter_request_body_scheduled_in(
+            TimeDelta::seconds(2),
+        ),
+    )
+    .await
+    .unwrap();
+
+    let html =
+        app.get_scheduled_newsletters_html().await.unwrap();
+
+    app.post_cancel_scheduled_newsletter(get_cancel_id(
+        &html,
+    ))
+    .await
+    .unwrap();
+
+    tokio::time::sleep(std::time::Duration::from_secs(3))
+        .await;
+
+    app.dispatch_all_pending_emails().await;
+    // Mock verifies on Drop that nothing was sent.
+}
@@ -0,0 +1,168 @@
+use uuid::Uuid;
+use zero2prod::{routes::newsletter, utils::Pipe};
+
+use crate::common::{
+    self, TestApp, assert_is_redirect_to,
+    confirm_subscriber, create_test_newsletter_writer,
+    create_unconfirmed_subscriber_with, email_server,
+};
+
+const NAME: &str = "Ursula & co";
+const EMAIL: &str = "ursula_le_guin@gmail.com";
+
+async fn arrange<'a>() -> TestApp<'a> {
+    let app = common::spawn_app().await;
+
+    create_test_newsletter_writer(&app).await;
+    create_unconfirmed_subscriber_with(&app, NAME, EMAIL)
+        .await
+        .pipe(confirm_subscriber)
+        .await;
+
+    app.post_login_with_default().await.unwrap();
+
+    app
+}
+
+fn a_templated_newsletter_request_body(
+    content_text: &str,
+    content_html: &str,
+) -> serde_json::Value {
+    serde_json::json!({
+        "title": "Newsletter title",
+        "content_text": content_text,
+        "content_html": content_html,
+        "idempotency_key": Uuid::new_v4().to_string(),
+    })
+}
+
+#[actix_web::test]
+async fn placeholders_are_rendered_for_each_subscriber() {
+    let app = arrange().await;
+
+    let mock_guard = email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(1)
+        .mount_as_scoped(&app.email_server)
+        .await;
+
+    app.post_newsletter(&a_templated_newsletter_request_body(
+        "Hi {{ subscriber.name }}, leave at {{ unsubscribe_url }}",
+        "<p>Hi {{subscriber.name}}</p>",
+    ))
+    .await
+    .unwrap();
+
+    app.dispatch_all_pending_emails().await;
+
+    let body = email_server::sent_emails(&app.email_server)
+        .await
+        .pop()
+        .unwrap();
+
+    drop(mock_guard);
+
+    // Preceded by the view in browser link.
+    let text_body = body["TextBody"].as_str().unwrap();
+    assert!(
+        text_body
+            .contains(&format!("\n\nHi {NAME}, leave at "))
+    );
+    assert!(
+        text_body.contains("/subscriptions/unsubscribe")
+    );
+    assert!(!text_body.contains("{{"));
+
+    assert!(
+        body["HtmlBody"]
+            .as_str()
+            .unwrap()
+            .ends_with("<p>Hi Ursula &amp; co</p>")
+    );
+}
+
+#[actix_web::test]
+async fn unknown_placeholder_is_rejected_before_anything_is_enqueued()
+ {
+    let app = arrange().await;
+
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+
+    let response = app
+        .post_newsletter(
+            &a_templated_newsletter_request_body(
+                "Hi {{ subscriber.age }}",
+                "<p>Hi</p>",
+            ),
+        )
+        .await
+        .unwrap();
+
+    assert_is_redirect_to(&response, "/admin/newsletters");
+
+    let text = app
+        .get_newsletter_form()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(
+        text.contains(newsletter::INVALID_TEMPLATE_MESSAGE)
+    );
+    assert!(text.contains("subscriber.age"));
+
+    app.dispatch_all_pending_emails().await;
+    // Mock verifies on Drop that nothing was sent.
+}
+
+#[actix_web::test]
+async fn draft_with_unknown_placeholder_is_not_published() {
+    let app = arrange().await;
+
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+
+    let location = app
+        .post_newsletter_draft(&serde_json::json!({
+            "title": "Draft title",
+            "content_text": "Hi {{ subscriber.name",
+            "content_html": "<p>Hi</p>",
+        }))
+        .await
+        .unwrap()
+        .headers()
+        .get("Location")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_owned();
+
+    let response = app
+        .post_newsletter_draft_action(
+            &location,
+            "/publish",
+            &serde_json::json!({
+                "idempotency_key": Uuid::new_v4().to_string(),
+                "scheduled_for": "",
+            }),
+        )
+        .await
+        .unwrap();
+
+    assert_is_redirect_to(&response, "/admin/newsletters");
+
+    let html =
+        app.get_newsletter_drafts_html().await.unwrap();
+    assert!(html.contains("Draft title"));
+
+    app.dispatch_all_pending_emails().await;
+    // Mock verifies on Drop that nothing was sent.
+}
@@ -0,0 +1,212 @@
+use zero2prod::routes::{email, newsletter};
+
+use crate::common::{
+    self, TEST_NEWSLETTER_WRITER_EMAIL, TestApp,
+    assert_is_redirect_to, create_confirmed_subscribers,
+    create_test_newsletter_writer, email_server,
+};
+
+async fn arrange<'a>() -> TestApp<'a> {
+    let app = common::spawn_app().await;
+
+    create_test_newsletter_writer(&app).await;
+    create_confirmed_subscribers(&app).await;
+    // Forget the confirmation email.
+    app.email_server.reset().await;
+
+    app.post_login_with_default().await.unwrap();
+
+    app
+}
+
+fn a_test_send_request_body(
+    test_recipients: &str,
+) -> serde_json::Value {
+    serde_json::json!({
+        "title": "Newsletter title",
+        "content_text": "Hi {{ subscriber.name }}",
+        "content_html": "<p>Hi {{ subscriber.name }}</p>",
+        "test_recipients": test_recipients,
+    })
+}
+
+#[actix_web::test]
+async fn test_copy_is_sent_to_the_given_addresses_only() {
+    let app = arrange().await;
+
+    email_server::get_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(2)
+        .mount(&app.email_server)
+        .await;
+
+    let response = app
+        .post_test_newsletter(&a_test_send_request_body(
+            "first@example.com, second@example.com",
+        ))
+        .await
+        .unwrap();
+
+    assert_is_redirect_to(&response, "/admin/newsletters");
+
+    let text = app
+        .get_newsletter_form()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(text.contains(newsletter::TEST_SENT_MESSAGE));
+
+    let emails =
+        email_server::sent_emails(&app.email_server).await;
+    let recipients = emails
+        .iter()
+        .map(|i| i["To"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        recipients,
+        ["first@example.com", "second@example.com"]
+    );
+    assert_eq!(
+        emails[0]["Subject"],
+        format!(
+            "{} Newsletter title",
+            newsletter::TEST_SUBJECT_PREFIX
+        )
+    );
+    assert_eq!(emails[0]["TextBody"], "Hi Jane Doe");
+
+    // Nothing was enqueued for subscribers.
+    app.dispatch_all_pending_emails().await;
+    let progress =
+        app.get_delivery_progress().await.unwrap();
+    assert!(progress.as_array().unwrap().is_empty());
+}
+
+#[actix_web::test]
+async fn test_copy_defaults_to_the_writers_own_address() {
+    let app = arrange().await;
+
+    email_server::get_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    app.post_test_newsletter(&a_test_send_request_body(""))
+        .await
+        .unwrap();
+
+    let emails =
+        email_server::sent_emails(&app.email_server).await;
+    assert_eq!(
+        emails[0]["To"],
+        TEST_NEWSLETTER_WRITER_EMAIL
+    );
+}
+
+#[actix_web::test]
+async fn test_copy_defaults_to_the_address_set_by_the_writer()
+ {
+    let app = arrange().await;
+
+    email_server::get_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    let response = app
+        .post_change_email("writer@example.com")
+        .await
+        .unwrap();
+    assert_is_redirect_to(&response, "/admin/email");
+
+    let html = app.get_change_email_html().await.unwrap();
+    assert!(html.contains(email::EMAIL_CHANGED_MESSAGE));
+    assert!(html.contains(r#"value="writer@example.com""#));
+
+    app.post_test_newsletter(&a_test_send_request_body(""))
+        .await
+        .unwrap();
+
+    let emails =
+        email_server::sent_emails(&app.email_server).await;
+    assert_eq!(emails[0]["To"], "writer@example.com");
+}
+
+#[actix_web::test]
+async fn test_copy_is_not_sent_without_recipients_nor_address()
+ {
+    let app = arrange().await;
+
+    email_server::get_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+
+    app.post_change_email("").await.unwrap();
+
+    let response = app
+        .post_test_newsletter(&a_test_send_request_body(""))
+        .await
+        .unwrap();
+    assert_is_redirect_to(&response, "/admin/newsletters");
+
+    let text = app
+        .get_newsletter_form()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(
+        text.contains(
+            newsletter::NO_TEST_RECIPIENTS_MESSAGE
+        )
+    );
+}
+
+#[actix_web::test]
+async fn invalid_writer_address_is_rejected() {
+    let app = arrange().await;
+
+    app.post_change_email("not-an-email").await.unwrap();
+
+    let html = app.get_change_email_html().await.unwrap();
+    assert!(html.contains(email::INVALID_EMAIL_MESSAGE));
+    assert!(html.contains(&format!(
+        r#"value="{TEST_NEWSLETTER_WRITER_EMAIL}""#
+    )));
+}
+
+#[actix_web::test]
+async fn test_copy_is_not_sent_if_an_address_is_invalid() {
+    let app = arrange().await;
+
+    email_server::get_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+
+    app.post_test_newsletter(&a_test_send_request_body(
+        "first@example.com, not-an-email",
+    ))
+    .await
+    .unwrap();
+
+    let text = app
+        .get_newsletter_form()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(text.contains(
+        newsletter::INVALID_TEST_RECIPIENT_MESSAGE
+    ));
+    assert!(text.contains("not-an-email"));
+}
@@ -1,6 +1,18 @@
+use std::num::NonZeroU32;
+
+use zero2prod::{
+    confirmation_email_worker::send_next_confirmation_email,
+    email_client::{CircuitBreaker, CircuitBreakerPolicy},
+    utils::Pipe,
+    worker::RetryPolicy,
+};
+
 use crate::common::{
-    self, email_server, spawn_app,
+    self, confirm_subscriber,
+    create_unconfirmed_subscriber_with, email_server,
+    spawn_app,
     test_dependency_injection::test_database::{
+        confirmation_email_dead_letters_repository::ConfirmationEmailDeadLettersRepository as _,
         get_subscriptions_repository::{
             GetSubscriptionsRepository as _,
             SubscriptionStatus,
@@ -146,6 +158,7 @@ async fn subscribe_sends_a_confirmation_email_for_valid_data()
     // Act
     let result =
         app.post_subscriptions(body.to_string()).await;
+    app.dispatch_all_pending_confirmation_emails().await;
     // Assert
     claims::assert_ok!(result);
     // Mock asserts on drop
@@ -165,6 +178,7 @@ async fn subscribe_sends_a_confirmation_email_with_a_link()
     // Act
     let result =
         app.post_subscriptions(body.to_string()).await;
+    app.dispatch_all_pending_confirmation_emails().await;
     // Assert
     claims::assert_ok!(result);
     // Get the first intercepted request
@@ -200,3 +214,330 @@ async fn subscription_fails_after_fatal_database_error() {
 
     assert_eq!(response.status(), 500);
 }
+
+#[actix_web::test]
+async fn subscribing_twice_resends_confirmation_with_a_new_token()
+ {
+    let app = spawn_app().await;
+
+    let first_link = create_unconfirmed_subscriber_with(
+        &app,
+        "le guin",
+        "ursula_le_guin@gmail.com",
+    )
+    .await;
+    let second_link = create_unconfirmed_subscriber_with(
+        &app,
+        "le guin",
+        "ursula_le_guin@gmail.com",
+    )
+    .await;
+
+    assert_ne!(first_link, second_link);
+
+    let saved = app
+        .test_app_state
+        .get_subscriptions_repository
+        .get_subscriptions("le guin")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        saved.status,
+        SubscriptionStatus::PendingConfirmation
+    );
+}
+
+#[actix_web::test]
+async fn subscribing_again_when_confirmed_returns_200_without_email()
+ {
+    let app = spawn_app().await;
+
+    create_unconfirmed_subscriber_with(
+        &app,
+        "le guin",
+        "ursula_le_guin@gmail.com",
+    )
+    .await
+    .pipe(confirm_subscriber)
+    .await;
+
+    email_server::get_mock_builder()
+        .respond_with(wiremock::ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+
+    let response = app
+        .post_subscriptions(
+            "name=le%20guin&email=ursula_le_guin%40gmail.com",
+        )
+        .await
+        .unwrap();
+    app.dispatch_all_pending_confirmation_emails().await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    let saved = app
+        .test_app_state
+        .get_subscriptions_repository
+        .get_subscriptions("le guin")
+        .await
+        .unwrap();
+
+    assert_eq!(saved.status, SubscriptionStatus::Confirmed);
+    // Mock verifies on Drop that no email was sent.
+}
+
+#[actix_web::test]
+async fn subscribe_succeeds_while_the_email_provider_is_down()
+ {
+    let app = spawn_app().await;
+    let body =
+        "name=le%20guin&email=ursula_le_guin%40gmail.com";
+
+    let mock_guard = email_server::get_mock_builder()
+        .respond_with(wiremock::ResponseTemplate::new(503))
+        .expect(0)
+        .mount_as_scoped(&app.email_server)
+        .await;
+
+    let response =
+        app.post_subscriptions(body).await.unwrap();
+
+    assert_eq!(response.status().as_u16(), 200);
+    drop(mock_guard);
+
+    // The confirmation email waits in the outbox until the provider is back.
+    email_server::get_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    app.dispatch_all_pending_confirmation_emails().await;
+
+    let email_request = &app
+        .email_server
+        .received_requests()
+        .await
+        .unwrap()[0];
+    claims::assert_ok!(
+        app.get_confirmation_links(email_request)
+    );
+}
+
+#[actix_web::test]
+async fn confirmation_email_is_retried_after_a_provider_outage()
+ {
+    let app = spawn_app().await;
+
+    email_server::get_mock_builder()
+        .respond_with(wiremock::ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+    email_server::get_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    app.post_subscriptions(
+        "name=le%20guin&email=ursula_le_guin%40gmail.com",
+    )
+    .await
+    .unwrap()
+    .error_for_status()
+    .unwrap();
+
+    app.dispatch_all_pending_confirmation_emails().await;
+    // Mocks verify on Drop that the email was sent on the second attempt.
+}
+
+#[actix_web::test]
+async fn confirmation_email_out_of_attempts_is_dead_lettered()
+ {
+    let app = spawn_app().await;
+    let max_attempts =
+        app.confirmation_email_retry_policy.max_attempts;
+
+    email_server::get_mock_builder()
+        .respond_with(wiremock::ResponseTemplate::new(503))
+        .expect(u64::from(max_attempts))
+        .mount(&app.email_server)
+        .await;
+
+    app.post_subscriptions(
+        "name=le%20guin&email=ursula_le_guin%40gmail.com",
+    )
+    .await
+    .unwrap()
+    .error_for_status()
+    .unwrap();
+
+    app.dispatch_all_pending_confirmation_emails().await;
+
+    let dead_letters = app
+        .test_app_state
+        .confirmation_email_dead_letters_repository
+        .get_dead_lettered_confirmation_emails()
+        .await
+        .unwrap();
+
+    assert_eq!(dead_letters.len(), 1);
+    assert_eq!(
+        dead_letters[0].subscriber_email,
+        "ursula_le_guin@gmail.com"
+    );
+    assert_eq!(
+        dead_letters[0].n_attempts,
+        i32::try_from(max_attempts).unwrap()
+    );
+}
+
+#[actix_web::test]
+async fn confirmation_email_waits_in_the_outbox_while_the_circuit_is_open()
+ {
+    let app = spawn_app().await;
+    // Dead letters emails on their second failed attempt.
+    let retry_policy = RetryPolicy {
+        max_attempts: 2,
+        ..app.confirmation_email_retry_policy
+    };
+    let email_client =
+        app.email_client.clone().with_circuit_breaker(
+            CircuitBreaker::new(CircuitBreakerPolicy {
+                failure_threshold: NonZeroU32::MIN,
+                open_duration_milliseconds: 60_000,
+            }),
+        );
+
+    email_server::get_mock_builder()
+        .respond_with(wiremock::ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+    email_server::get_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    app.post_subscriptions(
+        "name=le%20guin&email=ursula_le_guin%40gmail.com",
+    )
+    .await
+    .unwrap()
+    .error_for_status()
+    .unwrap();
+
+    // Opens the circuit.
+    assert!(
+        send_next_confirmation_email(
+            &*app.app_state.begin_unit_of_work,
+            &*app.app_state.subscriptions_repository,
+            &email_client,
+            &app.application_base_url.0,
+            &retry_policy,
+        )
+        .await
+        .is_err()
+    );
+    for _ in 0..retry_policy.max_attempts {
+        claims::assert_ok!(
+            send_next_confirmation_email(
+                &*app.app_state.begin_unit_of_work,
+                &*app.app_state.subscriptions_repository,
+                &email_client,
+                &app.application_base_url.0,
+                &retry_policy,
+            )
+            .await
+        );
+    }
+    // Sent once the provider is back, with attempts left.
+    claims::assert_ok!(
+        send_next_confirmation_email(
+            &*app.app_state.begin_unit_of_work,
+            &*app.app_state.subscriptions_repository,
+            &app.email_client,
+            &app.application_base_url.0,
+            &retry_policy,
+        )
+        .await
+    );
+
+    assert!(
+        app.test_app_state
+            .confirmation_email_dead_letters_repository
+            .get_dead_lettered_confirmation_emails()
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    // Mocks verify on Drop that the email was sent on its second attempt.
+}
+
+#[actix_web::test]
+async fn sent_confirmation_link_stays_valid_when_storing_the_outcome_fails()
+ {
+    let app = spawn_app().await;
+
+    email_server::get_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    app.post_subscriptions(
+        "name=le%20guin&email=ursula_le_guin%40gmail.com",
+    )
+    .await
+    .unwrap()
+    .error_for_status()
+    .unwrap();
+
+    app.test_app_state
+        .repository_suspender
+        .suspend_confirmation_email_outbox()
+        .await
+        .unwrap();
+
+    assert!(
+        send_next_confirmation_email(
+            &*app.app_state.begin_unit_of_work,
+            &*app.app_state.subscriptions_repository,
+            &app.email_client,
+            &app.application_base_url.0,
+            &app.confirmation_email_retry_policy,
+        )
+        .await
+        .is_err()
+    );
+
+    let email_request = &app
+        .email_server
+        .received_requests()
+        .await
+        .unwrap()[0];
+    let mut confirmation_link = app
+        .get_confirmation_links(email_request)
+        .unwrap()
+        .plain_text
+        .into_owned();
+    confirmation_link.set_port(Some(app.port)).unwrap();
+
+    confirm_subscriber(confirmation_link).await;
+
+    let saved = app
+        .test_app_state
+        .get_subscriptions_repository
+        .get_subscriptions("le guin")
+        .await
+        .unwrap();
+
+    assert_eq!(saved.status, SubscriptionStatus::Confirmed);
+}
@@ -1,13 +1,17 @@
+use std::{str::FromStr, time::Duration};
+
 use uuid::Uuid;
-use zero2prod::utils::Pipe;
+use zero2prod::{domain::SubscriptionToken, utils::Pipe};
 
 use crate::common::{
-    self, TestApp, email_server,
+    self, TestApp, create_unconfirmed_subscriber_with,
+    email_server,
     test_dependency_injection::test_database::{
         get_subscriptions_repository::{
             GetSubscriptionsRepository, SubscriptionStatus,
         },
         repository_suspender::RepositorySuspender,
+        subscription_tokens_repository::SubscriptionTokensRepository,
     },
 };
 
@@ -60,6 +64,8 @@ async fn subscription_link_returns_200() {
         "The email API did not return a 200 OK"
     );
 
+    app.dispatch_all_pending_confirmation_emails().await;
+
     let links = app
         .get_confirmation_links(
             &app.email_server
@@ -117,6 +123,8 @@ async fn subscription_link_updates_status_to_confirmed() {
         "The email API did not return a 200 OK"
     );
 
+    app.dispatch_all_pending_confirmation_emails().await;
+
     let links = app
         .get_confirmation_links(
             &app.email_server
@@ -237,6 +245,109 @@ async fn confirm_subscription_token_returns_500_with_fatal_database_error()
     );
 }
 
+#[actix_web::test]
+async fn confirmation_link_can_only_be_used_once() {
+    let (app, _client) = arrange().await;
+
+    let confirmation_link =
+        create_unconfirmed_subscriber_with(
+            &app,
+            "le guin",
+            "ursula_le_guin@gmail.com",
+        )
+        .await;
+
+    let first_response =
+        reqwest::get(confirmation_link.clone())
+            .await
+            .unwrap();
+    let second_response =
+        reqwest::get(confirmation_link).await.unwrap();
+
+    assert_eq!(first_response.status().as_u16(), 200);
+    assert_eq!(second_response.status().as_u16(), 401);
+}
+
+#[actix_web::test]
+async fn expired_confirmation_link_returns_410_with_a_new_link_form()
+ {
+    let (app, _client) = arrange().await;
+
+    let confirmation_link =
+        create_unconfirmed_subscriber_with(
+            &app,
+            "le guin",
+            "ursula_le_guin@gmail.com",
+        )
+        .await;
+
+    app.test_app_state
+        .subscription_tokens_repository
+        .age_tokens(Duration::from_secs(60 * 60 * 24 * 2))
+        .await
+        .unwrap();
+
+    let response =
+        reqwest::get(confirmation_link).await.unwrap();
+
+    assert_eq!(response.status().as_u16(), 410);
+    assert!(
+        response
+            .text()
+            .await
+            .unwrap()
+            .contains("Request a new link")
+    );
+
+    let record = app
+        .test_app_state
+        .get_subscriptions_repository
+        .get_subscriptions("le guin")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        record.status,
+        SubscriptionStatus::PendingConfirmation
+    );
+}
+
+#[actix_web::test]
+async fn subscription_tokens_are_only_stored_as_hashes() {
+    let (app, _client) = arrange().await;
+
+    let confirmation_link =
+        create_unconfirmed_subscriber_with(
+            &app,
+            "le guin",
+            "ursula_le_guin@gmail.com",
+        )
+        .await;
+
+    let token = confirmation_link
+        .query_pairs()
+        .find(|(key, _)| key == "subscription_token")
+        .unwrap()
+        .1;
+
+    let token_hashes = app
+        .test_app_state
+        .subscription_tokens_repository
+        .get_token_hashes()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        token_hashes,
+        vec![
+            SubscriptionToken::from_str(&token)
+                .unwrap()
+                .hash()
+        ]
+    );
+    assert_ne!(token_hashes[0], token.as_bytes());
+}
+
 async fn arrange<'a>() -> (TestApp<'a>, reqwest::Client) {
     (common::spawn_app().await, reqwest::Client::new())
 }
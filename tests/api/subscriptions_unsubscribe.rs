@@ -0,0 +1,337 @@
+use uuid::Uuid;
+use zero2prod::{
+    configuration::get_configuration,
+    domain::UnsubscribeToken,
+    startup::GlobalSharedPointerType, utils::Pipe,
+};
+
+use crate::common::{
+    self, TestApp, a_valid_newsletter_request_body,
+    confirm_subscriber, create_test_newsletter_writer,
+    create_unconfirmed_subscriber_with, email_server,
+    test_dependency_injection::test_database::get_subscriptions_repository::{
+        GetSubscriptionsRepository as _, SubscriptionStatus,
+    },
+};
+
+const NAME: &str = "le guin";
+const EMAIL: &str = "ursula_le_guin@gmail.com";
+
+async fn publish_and_dispatch_newsletter(
+    app: &TestApp<'_>,
+) {
+    app.post_newsletter(&a_valid_newsletter_request_body())
+        .await
+        .unwrap();
+
+    app.dispatch_all_pending_emails().await;
+}
+
+fn get_header<'a>(
+    body: &'a serde_json::Value,
+    name: &str,
+) -> Option<&'a str> {
+    body["Headers"]
+        .as_array()?
+        .iter()
+        .find(|h| h["Name"] == name)?["Value"]
+        .as_str()
+}
+
+async fn arrange_confirmed_subscriber_and_newsletter_email<
+    'a,
+>() -> (TestApp<'a>, serde_json::Value) {
+    let app = common::spawn_app().await;
+
+    create_test_newsletter_writer(&app).await;
+    create_unconfirmed_subscriber_with(&app, NAME, EMAIL)
+        .await
+        .pipe(confirm_subscriber)
+        .await;
+
+    app.post_login_with_default().await.unwrap();
+
+    let mock_guard = email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(1)
+        .mount_as_scoped(&app.email_server)
+        .await;
+
+    publish_and_dispatch_newsletter(&app).await;
+
+    let body = email_server::sent_emails(&app.email_server)
+        .await
+        .pop()
+        .unwrap();
+
+    drop(mock_guard);
+
+    (app, body)
+}
+
+fn unsubscribe_link(
+    app: &TestApp<'_>,
+    body: &serde_json::Value,
+) -> reqwest::Url {
+    get_header(body, "List-Unsubscribe")
+        .expect("List-Unsubscribe header must be set.")
+        .trim_start_matches('<')
+        .trim_end_matches('>')
+        .pipe(reqwest::Url::parse)
+        .unwrap()
+        .pipe(|mut i| {
+            i.set_port(app.port.pipe(Some)).unwrap();
+            i
+        })
+}
+
+#[actix_web::test]
+async fn newsletter_emails_carry_one_click_unsubscribe_headers()
+ {
+    let (app, body) =
+        arrange_confirmed_subscriber_and_newsletter_email()
+            .await;
+
+    assert_eq!(
+        get_header(&body, "List-Unsubscribe-Post"),
+        Some("List-Unsubscribe=One-Click")
+    );
+
+    let link = unsubscribe_link(&app, &body);
+    assert_eq!(link.path(), "/subscriptions/unsubscribe");
+}
+
+#[actix_web::test]
+async fn newsletter_emails_are_tagged_with_their_issue_and_subscriber()
+ {
+    let (_app, body) =
+        arrange_confirmed_subscriber_and_newsletter_email()
+            .await;
+
+    let newsletter_issue_id =
+        body["Metadata"]["newsletter_issue_id"]
+            .as_str()
+            .unwrap();
+
+    assert_eq!(body["Tag"], newsletter_issue_id);
+    Uuid::parse_str(newsletter_issue_id).unwrap();
+    body["Metadata"]["subscriber_id"]
+        .as_str()
+        .unwrap()
+        .pipe(Uuid::parse_str)
+        .unwrap();
+}
+
+#[actix_web::test]
+async fn one_click_unsubscribe_updates_status_to_unsubscribed()
+ {
+    let (app, body) =
+        arrange_confirmed_subscriber_and_newsletter_email()
+            .await;
+
+    let response = reqwest::Client::new()
+        .post(unsubscribe_link(&app, &body))
+        .body("List-Unsubscribe=One-Click")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    let record = app
+        .test_app_state
+        .get_subscriptions_repository
+        .get_subscriptions(NAME)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        record.status,
+        SubscriptionStatus::Unsubscribed
+    );
+}
+
+#[actix_web::test]
+async fn newsletter_is_not_sent_to_unsubscribed_subscribers()
+ {
+    let (app, body) =
+        arrange_confirmed_subscriber_and_newsletter_email()
+            .await;
+
+    reqwest::Client::new()
+        .post(unsubscribe_link(&app, &body))
+        .send()
+        .await
+        .unwrap()
+        .error_for_status()
+        .unwrap();
+
+    email_server::get_batch_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+
+    publish_and_dispatch_newsletter(&app).await;
+    // Mock verifies on Drop that nothing was sent.
+}
+
+#[actix_web::test]
+async fn get_unsubscribe_link_does_not_unsubscribe() {
+    let (app, body) =
+        arrange_confirmed_subscriber_and_newsletter_email()
+            .await;
+
+    let response =
+        reqwest::get(unsubscribe_link(&app, &body))
+            .await
+            .unwrap();
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    let record = app
+        .test_app_state
+        .get_subscriptions_repository
+        .get_subscriptions(NAME)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        record.status,
+        SubscriptionStatus::Confirmed
+    );
+}
+
+#[actix_web::test]
+async fn unsubscribe_with_forged_token_returns_401() {
+    let app = common::spawn_app().await;
+
+    let response = reqwest::Client::new()
+        .post(format!(
+            "{}/subscriptions/unsubscribe",
+            &app.address
+        ))
+        .query(&[
+            ("subscriber_id", Uuid::new_v4().to_string()),
+            ("token", "Zm9yZ2Vk".to_string()),
+        ])
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[actix_web::test]
+async fn unsubscribe_with_token_signed_by_the_cookie_key_returns_401()
+ {
+    let app = common::spawn_app().await;
+    let configuration =
+        get_configuration::<GlobalSharedPointerType>()
+            .unwrap();
+    let subscriber_id = Uuid::new_v4();
+    let token = UnsubscribeToken::sign(
+        &configuration.application.hmac_secret,
+        subscriber_id,
+    );
+
+    let response = reqwest::Client::new()
+        .post(format!(
+            "{}/subscriptions/unsubscribe",
+            &app.address
+        ))
+        .query(&[
+            ("subscriber_id", subscriber_id.to_string()),
+            ("token", token.to_string()),
+        ])
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[actix_web::test]
+async fn subscribing_again_after_unsubscribing_requires_confirmation()
+ {
+    let (app, body) =
+        arrange_confirmed_subscriber_and_newsletter_email()
+            .await;
+
+    reqwest::Client::new()
+        .post(unsubscribe_link(&app, &body))
+        .send()
+        .await
+        .unwrap()
+        .error_for_status()
+        .unwrap();
+
+    create_unconfirmed_subscriber_with(&app, NAME, EMAIL)
+        .await
+        .pipe(confirm_subscriber)
+        .await;
+
+    let record = app
+        .test_app_state
+        .get_subscriptions_repository
+        .get_subscriptions(NAME)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        record.status,
+        SubscriptionStatus::Confirmed
+    );
+}
+
+#[actix_web::test]
+async fn bounced_address_stays_suppressed_after_unsubscribing_and_subscribing_again()
+ {
+    let (app, body) =
+        arrange_confirmed_subscriber_and_newsletter_email()
+            .await;
+
+    app.post_email_event(&serde_json::json!({
+        "RecordType": "Bounce",
+        "Type": "HardBounce",
+        "Email": EMAIL,
+        "BouncedAt": "2026-10-18T09:12:00Z",
+    }))
+    .await
+    .unwrap()
+    .error_for_status()
+    .unwrap();
+
+    reqwest::Client::new()
+        .post(unsubscribe_link(&app, &body))
+        .send()
+        .await
+        .unwrap()
+        .error_for_status()
+        .unwrap();
+
+    email_server::get_mock_builder()
+        .respond_with(email_server::accepted())
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+
+    let response = [("name", NAME), ("email", EMAIL)]
+        .pipe(serde_urlencoded::to_string)
+        .unwrap()
+        .pipe(|body| app.post_subscriptions(body))
+        .await
+        .unwrap();
+    app.dispatch_all_pending_confirmation_emails().await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    let record = app
+        .test_app_state
+        .get_subscriptions_repository
+        .get_subscriptions(NAME)
+        .await
+        .unwrap();
+
+    assert_eq!(record.status, SubscriptionStatus::Bounced);
+}